@@ -0,0 +1,9 @@
+#pragma c23
+5uwb
+/*===
+Constant
+    Integer "5"
+        IntegerBase Decimal
+        IntegerSuffix true false
+            IntegerSize BitInt
+===*/
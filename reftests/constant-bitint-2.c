@@ -0,0 +1,9 @@
+#pragma gnu
+0x7fWB
+/*===
+Constant
+    Integer "7f"
+        IntegerBase Hexadecimal
+        IntegerSuffix false false
+            IntegerSize BitInt
+===*/
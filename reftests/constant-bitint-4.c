@@ -0,0 +1,4 @@
+5wb
+/*===
+~ERROR
+===*/
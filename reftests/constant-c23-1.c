@@ -0,0 +1,9 @@
+#pragma c23
+1'000'000
+/*===
+Constant
+    Integer "1000000"
+        IntegerBase Decimal
+        IntegerSuffix false false
+            IntegerSize Int
+===*/
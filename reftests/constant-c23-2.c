@@ -0,0 +1,9 @@
+#pragma c23
+0b1010'0101u
+/*===
+Constant
+    Integer "10100101"
+        IntegerBase Binary
+        IntegerSuffix true false
+            IntegerSize Int
+===*/
@@ -0,0 +1,9 @@
+#pragma c23
+0x1'0p1'0
+/*===
+Constant
+    Float "10p10"
+        FloatBase Hexadecimal
+        FloatSuffix false
+            FloatFormat Double
+===*/
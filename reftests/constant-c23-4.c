@@ -0,0 +1,5 @@
+#pragma c23
+u8'c'
+/*===
+Constant Character u8'c'
+===*/
@@ -0,0 +1,4 @@
+1'000
+/*===
+~ERROR
+===*/
@@ -0,0 +1,5 @@
+#pragma gnu
+1'000
+/*===
+~ERROR
+===*/
@@ -0,0 +1,35 @@
+#pragma c23
+unsigned _BitInt(3 + 4) x = 1uwb;
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier Unsigned
+    DeclarationSpecifier
+        TypeSpecifier BitInt
+            Expression
+                BinaryOperatorExpression
+                    Expression
+                        Constant
+                            Integer "3"
+                                IntegerBase Decimal
+                                IntegerSuffix false false
+                                    IntegerSize Int
+                    Expression
+                        Constant
+                            Integer "4"
+                                IntegerBase Decimal
+                                IntegerSuffix false false
+                                    IntegerSize Int
+                    BinaryOperator Plus
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "x"
+        Initializer
+            Expression
+                Constant
+                    Integer "1"
+                        IntegerBase Decimal
+                        IntegerSuffix true false
+                            IntegerSize BitInt
+===*/
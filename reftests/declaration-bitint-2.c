@@ -0,0 +1,4 @@
+_BitInt(8) x;
+/*===
+~ERROR
+===*/
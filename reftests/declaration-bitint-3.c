@@ -0,0 +1,10 @@
+int _BitInt;
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "_BitInt"
+===*/
@@ -0,0 +1,16 @@
+#pragma c23
+constexpr bool b = false;
+/*===
+Declaration
+    DeclarationSpecifier
+        StorageClassSpecifier Constexpr
+    DeclarationSpecifier
+        TypeSpecifier
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "b"
+        Initializer
+            Expression
+                Constant Bool false
+===*/
@@ -0,0 +1,28 @@
+#pragma c23
+thread_local alignas(16) typeof_unqual(const int) x = {};
+/*===
+Declaration
+    DeclarationSpecifier
+        StorageClassSpecifier ThreadLocal
+    DeclarationSpecifier
+        AlignmentSpecifier
+            Expression
+                Constant
+                    Integer "16"
+                        IntegerBase Decimal
+                        IntegerSuffix false false
+                            IntegerSize Int
+    DeclarationSpecifier
+        TypeSpecifier TypeOfUnqual
+            TypeOf
+                TypeName
+                    SpecifierQualifier
+                        TypeQualifier Const
+                    SpecifierQualifier
+                        TypeSpecifier Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "x"
+        Initializer
+===*/
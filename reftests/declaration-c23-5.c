@@ -0,0 +1,5 @@
+#pragma gnu
+constexpr bool b = true;
+/*===
+~ERROR
+===*/
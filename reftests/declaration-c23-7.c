@@ -0,0 +1,18 @@
+#pragma c23
+__typeof__(1) x;
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            TypeOf
+                Expression
+                    Constant
+                        Integer "1"
+                            IntegerBase Decimal
+                            IntegerSuffix false false
+                                IntegerSize Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "x"
+===*/
@@ -0,0 +1,18 @@
+#pragma c23
+__typeof_unqual__(1) x;
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier TypeOfUnqual
+            TypeOf
+                Expression
+                    Constant
+                        Integer "1"
+                            IntegerBase Decimal
+                            IntegerSuffix false false
+                                IntegerSize Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "x"
+===*/
@@ -0,0 +1,25 @@
+#pragma msvc
+__declspec(dllimport) unsigned __int64 __stdcall f(int);
+/*===
+Declaration
+    DeclarationSpecifier
+        Extension Declspec
+            Attribute "dllimport"
+    DeclarationSpecifier
+        TypeSpecifier Unsigned
+    DeclarationSpecifier
+        TypeSpecifier SizedInt 64
+    DeclarationSpecifier
+        Extension
+            CallingConvention Stdcall
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "f"
+            DerivedDeclarator
+                FunctionDeclarator
+                    ParameterDeclaration
+                        DeclarationSpecifier
+                            TypeSpecifier Int
+                    Ellipsis None
+===*/
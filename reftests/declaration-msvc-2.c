@@ -0,0 +1,24 @@
+#pragma msvc
+typedef void (__stdcall *PFN)(void);
+/*===
+Declaration
+    DeclarationSpecifier
+        StorageClassSpecifier Typedef
+    DeclarationSpecifier
+        TypeSpecifier Void
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Declarator
+                    DeclaratorKind
+                        Identifier "PFN"
+                    DerivedDeclarator Pointer
+                    Extension
+                        CallingConvention Stdcall
+            DerivedDeclarator
+                FunctionDeclarator
+                    ParameterDeclaration
+                        DeclarationSpecifier
+                            TypeSpecifier Void
+                    Ellipsis None
+===*/
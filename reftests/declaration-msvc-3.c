@@ -0,0 +1,36 @@
+#pragma msvc
+__declspec(align(16) noreturn) __forceinline int g(int *__restrict p);
+/*===
+Declaration
+    DeclarationSpecifier
+        Extension Declspec
+            Attribute "align"
+                Expression
+                    Constant
+                        Integer "16"
+                            IntegerBase Decimal
+                            IntegerSuffix false false
+                                IntegerSize Int
+        Extension Declspec
+            Attribute "noreturn"
+    DeclarationSpecifier
+        FunctionSpecifier ForceInline
+    DeclarationSpecifier
+        TypeSpecifier Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "g"
+            DerivedDeclarator
+                FunctionDeclarator
+                    ParameterDeclaration
+                        DeclarationSpecifier
+                            TypeSpecifier Int
+                        Declarator
+                            DeclaratorKind
+                                Identifier "p"
+                            DerivedDeclarator Pointer
+                                PointerQualifier
+                                    TypeQualifier Restrict
+                    Ellipsis None
+===*/
@@ -0,0 +1,4 @@
+__int64 x;
+/*===
+~ERROR
+===*/
@@ -0,0 +1,30 @@
+#pragma msvc
+struct __declspec(align(16)) S { int x; } s;
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            StructType
+                StructKind Struct
+                Extension Declspec
+                    Attribute "align"
+                        Expression
+                            Constant
+                                Integer "16"
+                                    IntegerBase Decimal
+                                    IntegerSuffix false false
+                                        IntegerSize Int
+                Identifier "S"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            TypeSpecifier Int
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "x"
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "s"
+===*/
@@ -0,0 +1,5 @@
+#pragma gnu
+unsigned __int64 __stdcall f(void (__cdecl *)(void));
+/*===
+~ERROR
+===*/
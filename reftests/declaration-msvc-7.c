@@ -0,0 +1,35 @@
+#pragma msvc
+unsigned __int64 __stdcall f(void (__cdecl *)(void));
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier Unsigned
+    DeclarationSpecifier
+        TypeSpecifier SizedInt 64
+    DeclarationSpecifier
+        Extension
+            CallingConvention Stdcall
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "f"
+            DerivedDeclarator
+                FunctionDeclarator
+                    ParameterDeclaration
+                        DeclarationSpecifier
+                            TypeSpecifier Void
+                        Declarator
+                            DeclaratorKind
+                                Declarator
+                                    DeclaratorKind Abstract
+                                    DerivedDeclarator Pointer
+                                    Extension
+                                        CallingConvention Cdecl
+                            DerivedDeclarator
+                                FunctionDeclarator
+                                    ParameterDeclaration
+                                        DeclarationSpecifier
+                                            TypeSpecifier Void
+                                    Ellipsis None
+                    Ellipsis None
+===*/
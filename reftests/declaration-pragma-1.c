@@ -0,0 +1,31 @@
+struct s {
+    char c;
+ #pragma pack(pop)
+    int i;
+};
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            StructType
+                StructKind Struct
+                Identifier "s"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            TypeSpecifier Char
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "c"
+                StructDeclaration
+                    Pragma ["pack", "(", "pop", ")"]
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            TypeSpecifier Int
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "i"
+===*/
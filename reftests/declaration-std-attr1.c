@@ -0,0 +1,24 @@
+#pragma c23
+[[nodiscard, deprecated("use g")]] int f(void) [[gnu::const]];
+/*===
+Declaration
+    DeclarationSpecifier
+        Extension
+            StandardAttribute "nodiscard"
+        Extension
+            StandardAttribute "deprecated" ( "\"use g\"" )
+    DeclarationSpecifier
+        TypeSpecifier Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "f"
+            DerivedDeclarator
+                FunctionDeclarator
+                    ParameterDeclaration
+                        DeclarationSpecifier
+                            TypeSpecifier Void
+                    Ellipsis None
+                    Extension
+                        StandardAttribute "gnu::const"
+===*/
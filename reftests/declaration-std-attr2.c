@@ -0,0 +1,33 @@
+#pragma c23
+int [[maybe_unused]] a [[gnu::aligned(16)]] [4], * [[clang::noderef]] p;
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier Int
+    DeclarationSpecifier
+        Extension
+            StandardAttribute "maybe_unused"
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "a"
+            DerivedDeclarator
+                ArrayDeclarator
+                    ArraySize VariableExpression
+                        Expression
+                            Constant
+                                Integer "4"
+                                    IntegerBase Decimal
+                                    IntegerSuffix false false
+                                        IntegerSize Int
+            Extension
+                StandardAttribute "gnu::aligned" ( "16" )
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "p"
+            DerivedDeclarator Pointer
+                PointerQualifier
+                    Extension
+                        StandardAttribute "clang::noderef"
+===*/
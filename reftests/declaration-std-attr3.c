@@ -0,0 +1,37 @@
+#pragma c23
+struct s { [[deprecated]] int x; int y [[gnu::packed]] : 3; };
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            StructType
+                StructKind Struct
+                Identifier "s"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            Extension
+                                StandardAttribute "deprecated"
+                        SpecifierQualifier
+                            TypeSpecifier Int
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "x"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            TypeSpecifier Int
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "y"
+                                Extension
+                                    StandardAttribute "gnu::packed"
+                            Expression
+                                Constant
+                                    Integer "3"
+                                        IntegerBase Decimal
+                                        IntegerSuffix false false
+                                            IntegerSize Int
+===*/
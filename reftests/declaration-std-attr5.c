@@ -0,0 +1,48 @@
+#pragma c23
+int x [[a]] [3] [[b]], f() [[c]], (*g)(int) [[d]];
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier Int
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "x"
+            DerivedDeclarator
+                ArrayDeclarator
+                    ArraySize VariableExpression
+                        Expression
+                            Constant
+                                Integer "3"
+                                    IntegerBase Decimal
+                                    IntegerSuffix false false
+                                        IntegerSize Int
+                    Extension
+                        StandardAttribute "b"
+            Extension
+                StandardAttribute "a"
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Identifier "f"
+            DerivedDeclarator
+                FunctionDeclarator
+                    Ellipsis None
+                    Extension
+                        StandardAttribute "c"
+    InitDeclarator
+        Declarator
+            DeclaratorKind
+                Declarator
+                    DeclaratorKind
+                        Identifier "g"
+                    DerivedDeclarator Pointer
+            DerivedDeclarator
+                FunctionDeclarator
+                    ParameterDeclaration
+                        DeclarationSpecifier
+                            TypeSpecifier Int
+                    Ellipsis None
+                    Extension
+                        StandardAttribute "d"
+===*/
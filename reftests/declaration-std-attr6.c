@@ -0,0 +1,4 @@
+[[deprecated]] int x;
+/*===
+~ERROR
+===*/
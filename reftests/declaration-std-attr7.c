@@ -0,0 +1,4 @@
+int x[[1]];
+/*===
+~ERROR
+===*/
@@ -0,0 +1,27 @@
+struct s {
+_Alignas(8) char c;
+};
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            StructType
+                StructKind Struct
+                Identifier "s"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            AlignmentSpecifier
+                                Expression
+                                    Constant
+                                        Integer "8"
+                                            IntegerBase Decimal
+                                            IntegerSuffix false false
+                                                IntegerSize Int
+                        SpecifierQualifier
+                            TypeSpecifier Char
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "c"
+===*/
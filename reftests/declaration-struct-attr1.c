@@ -0,0 +1,22 @@
+#pragma gnu
+struct __attribute__((packed)) s {
+char c;
+};
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            StructType
+                StructKind Struct
+                Extension
+                    Attribute "packed"
+                Identifier "s"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            TypeSpecifier Char
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "c"
+===*/
@@ -0,0 +1,22 @@
+#pragma c23
+struct [[gnu::packed]] s {
+int x;
+};
+/*===
+Declaration
+    DeclarationSpecifier
+        TypeSpecifier
+            StructType
+                StructKind Struct
+                Extension
+                    StandardAttribute "gnu::packed"
+                Identifier "s"
+                StructDeclaration
+                    StructField
+                        SpecifierQualifier
+                            TypeSpecifier Int
+                        StructDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "x"
+===*/
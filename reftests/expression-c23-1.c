@@ -0,0 +1,6 @@
+#pragma c23
+true
+/*===
+Expression
+    Constant Bool true
+===*/
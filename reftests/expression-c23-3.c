@@ -0,0 +1,9 @@
+#pragma c23
+(int){}
+/*===
+Expression
+    CompoundLiteral
+        TypeName
+            SpecifierQualifier
+                TypeSpecifier Int
+===*/
@@ -0,0 +1,31 @@
+#pragma gnu
+__builtin_choose_expr(sizeof(x) == 4, f(x), g(x))
+/*===
+Expression
+    ChooseExpression
+        Expression
+            BinaryOperatorExpression
+                Expression
+                    SizeOfVal
+                        Expression
+                            Identifier "x"
+                Expression
+                    Constant
+                        Integer "4"
+                            IntegerBase Decimal
+                            IntegerSuffix false false
+                                IntegerSize Int
+                BinaryOperator Equals
+        Expression
+            CallExpression
+                Expression
+                    Identifier "f"
+                Expression
+                    Identifier "x"
+        Expression
+            CallExpression
+                Expression
+                    Identifier "g"
+                Expression
+                    Identifier "x"
+===*/
@@ -0,0 +1,17 @@
+#pragma gnu
+__real__ z + __imag z
+/*===
+Expression
+    BinaryOperatorExpression
+        Expression
+            UnaryOperatorExpression
+                UnaryOperator Real
+                Expression
+                    Identifier "z"
+        Expression
+            UnaryOperatorExpression
+                UnaryOperator Imag
+                Expression
+                    Identifier "z"
+        BinaryOperator Plus
+===*/
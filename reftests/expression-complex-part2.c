@@ -0,0 +1,4 @@
+__real__ z
+/*===
+~ERROR
+===*/
@@ -0,0 +1,13 @@
+#pragma clang
+#pragma typedef float4
+__builtin_convertvector(v, float4)
+/*===
+Expression
+    ConvertVectorExpression
+        Expression
+            Identifier "v"
+        TypeName
+            SpecifierQualifier
+                TypeSpecifier TypedefName
+                    Identifier "float4"
+===*/
@@ -0,0 +1,25 @@
+#pragma gnu
+x = a ?: b ? c : d ?: e
+/*===
+Expression
+    BinaryOperatorExpression
+        Expression
+            Identifier "x"
+        Expression
+            ConditionalExpression OmittedThen
+                Expression
+                    Identifier "a"
+                Expression
+                    ConditionalExpression
+                        Expression
+                            Identifier "b"
+                        Expression
+                            Identifier "c"
+                        Expression
+                            ConditionalExpression OmittedThen
+                                Expression
+                                    Identifier "d"
+                                Expression
+                                    Identifier "e"
+        BinaryOperator Assign
+===*/
@@ -0,0 +1,6 @@
+#pragma gnu
+&&done
+/*===
+Expression LabelAddress
+    Identifier "done"
+===*/
@@ -0,0 +1,4 @@
+&&done
+/*===
+~ERROR
+===*/
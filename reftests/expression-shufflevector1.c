@@ -0,0 +1,37 @@
+#pragma clang
+__builtin_shufflevector(a, b, 0, -1, 3, 2)
+/*===
+Expression
+    ShuffleVectorExpression
+        Expression
+            Identifier "a"
+        Expression
+            Identifier "b"
+        Expression
+            Constant
+                Integer "0"
+                    IntegerBase Decimal
+                    IntegerSuffix false false
+                        IntegerSize Int
+        Expression
+            UnaryOperatorExpression
+                UnaryOperator Minus
+                Expression
+                    Constant
+                        Integer "1"
+                            IntegerBase Decimal
+                            IntegerSuffix false false
+                                IntegerSize Int
+        Expression
+            Constant
+                Integer "3"
+                    IntegerBase Decimal
+                    IntegerSuffix false false
+                        IntegerSize Int
+        Expression
+            Constant
+                Integer "2"
+                    IntegerBase Decimal
+                    IntegerSuffix false false
+                        IntegerSize Int
+===*/
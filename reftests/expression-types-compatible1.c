@@ -0,0 +1,24 @@
+#pragma gnu
+#pragma typedef T
+__builtin_types_compatible_p(T *, const int[])
+/*===
+Expression
+    TypesCompatibleExpression
+        TypeName
+            SpecifierQualifier
+                TypeSpecifier TypedefName
+                    Identifier "T"
+            Declarator
+                DeclaratorKind Abstract
+                DerivedDeclarator Pointer
+        TypeName
+            SpecifierQualifier
+                TypeQualifier Const
+            SpecifierQualifier
+                TypeSpecifier Int
+            Declarator
+                DeclaratorKind Abstract
+                DerivedDeclarator
+                    ArrayDeclarator
+                        ArraySize Unknown
+===*/
@@ -0,0 +1,10 @@
+#pragma gnu
+asm volatile inline ("nop");
+/*===
+Statement
+    AsmStatement
+        GnuBasicAsmStatement
+            AsmQualifier Volatile
+            AsmQualifier Inline
+            StringLiteral ["\"nop\""]
+===*/
@@ -0,0 +1,10 @@
+#pragma gnu
+asm goto ("jmp %l0" :::: out);
+/*===
+Statement
+    AsmStatement
+        GnuExtendedAsmStatement
+            AsmQualifier Goto
+            StringLiteral ["\"jmp %l0\""]
+            Identifier "out"
+===*/
@@ -0,0 +1,22 @@
+#pragma gnu
+__asm__ __volatile__ inline goto ("btl %1, %0; jc %l2" : : "r" (x), "r" (y) : "cc" : yes, no);
+/*===
+Statement
+    AsmStatement
+        GnuExtendedAsmStatement
+            AsmQualifier Volatile
+            AsmQualifier Inline
+            AsmQualifier Goto
+            StringLiteral ["\"btl %1, %0; jc %l2\""]
+            GnuAsmOperand
+                StringLiteral ["\"r\""]
+                Expression
+                    Identifier "x"
+            GnuAsmOperand
+                StringLiteral ["\"r\""]
+                Expression
+                    Identifier "y"
+            StringLiteral ["\"cc\""]
+            Identifier "yes"
+            Identifier "no"
+===*/
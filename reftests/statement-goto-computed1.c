@@ -0,0 +1,12 @@
+#pragma gnu
+goto *table[op];
+/*===
+Statement GotoComputed
+    Expression
+        BinaryOperatorExpression
+            Expression
+                Identifier "table"
+            Expression
+                Identifier "op"
+            BinaryOperator Index
+===*/
@@ -0,0 +1,26 @@
+#pragma gnu
+{
+    __label__ retry, done;
+retry:
+    goto done;
+done:;
+}
+/*===
+Statement Compound
+    BlockItem LabelDeclaration
+        Identifier "retry"
+        Identifier "done"
+    BlockItem
+        Statement
+            LabeledStatement
+                Label
+                    Identifier "retry"
+                Statement Goto
+                    Identifier "done"
+    BlockItem
+        Statement
+            LabeledStatement
+                Label
+                    Identifier "done"
+                Statement
+===*/
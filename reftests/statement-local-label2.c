@@ -0,0 +1,6 @@
+{
+    __label__ done;
+}
+/*===
+~ERROR
+===*/
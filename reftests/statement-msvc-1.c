@@ -0,0 +1,17 @@
+#pragma msvc
+__try { __leave; } __finally { g(); }
+/*===
+Statement
+    TryStatement
+        Statement Compound
+            BlockItem
+                Statement Leave
+        TryHandler Finally
+            Statement Compound
+                BlockItem
+                    Statement
+                        Expression
+                            CallExpression
+                                Expression
+                                    Identifier "g"
+===*/
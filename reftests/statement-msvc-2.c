@@ -0,0 +1,42 @@
+#pragma msvc
+__try {
+    f();
+} __except (filter(1, 2)) {
+    g();
+}
+/*===
+Statement
+    TryStatement
+        Statement Compound
+            BlockItem
+                Statement
+                    Expression
+                        CallExpression
+                            Expression
+                                Identifier "f"
+        TryHandler
+            ExceptHandler
+                Expression
+                    CallExpression
+                        Expression
+                            Identifier "filter"
+                        Expression
+                            Constant
+                                Integer "1"
+                                    IntegerBase Decimal
+                                    IntegerSuffix false false
+                                        IntegerSize Int
+                        Expression
+                            Constant
+                                Integer "2"
+                                    IntegerBase Decimal
+                                    IntegerSuffix false false
+                                        IntegerSize Int
+                Statement Compound
+                    BlockItem
+                        Statement
+                            Expression
+                                CallExpression
+                                    Expression
+                                        Identifier "g"
+===*/
@@ -0,0 +1,30 @@
+#pragma msvc
+{
+    __pragma(pack(push, 1)) struct s { __pragma(warning(disable: 4200)) int a; } x;
+}
+/*===
+Statement Compound
+    BlockItem
+        Pragma ["pack", "(", "push", ",", "1", ")"]
+    BlockItem
+        Declaration
+            DeclarationSpecifier
+                TypeSpecifier
+                    StructType
+                        StructKind Struct
+                        Identifier "s"
+                        StructDeclaration
+                            Pragma ["warning", "(", "disable", ":", "4200", ")"]
+                        StructDeclaration
+                            StructField
+                                SpecifierQualifier
+                                    TypeSpecifier Int
+                                StructDeclarator
+                                    Declarator
+                                        DeclaratorKind
+                                            Identifier "a"
+            InitDeclarator
+                Declarator
+                    DeclaratorKind
+                        Identifier "x"
+===*/
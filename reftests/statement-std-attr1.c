@@ -0,0 +1,54 @@
+#pragma c23
+switch (x) {
+case 1:
+    x++;
+    [[fallthrough]];
+case 2:
+    [[vendor::hint(a[1], {b}, "s" + 2)]] return x;
+}
+/*===
+Statement
+    SwitchStatement
+        Expression
+            Identifier "x"
+        Statement Compound
+            BlockItem
+                Statement
+                    LabeledStatement
+                        Label
+                            Expression
+                                Constant
+                                    Integer "1"
+                                        IntegerBase Decimal
+                                        IntegerSuffix false false
+                                            IntegerSize Int
+                        Statement
+                            Expression
+                                UnaryOperatorExpression
+                                    Expression
+                                        Identifier "x"
+                                    UnaryOperator PostIncrement
+            BlockItem
+                Statement
+                    AttributedStatement
+                        Extension
+                            StandardAttribute "fallthrough"
+                        Statement
+            BlockItem
+                Statement
+                    LabeledStatement
+                        Label
+                            Expression
+                                Constant
+                                    Integer "2"
+                                        IntegerBase Decimal
+                                        IntegerSuffix false false
+                                            IntegerSize Int
+                        Statement
+                            AttributedStatement
+                                Extension
+                                    StandardAttribute "vendor::hint" ( "a" "[" "1" "]" "," "{" "b" "}" "," "\"s\"" "+" "2" )
+                                Statement Return
+                                    Expression
+                                        Identifier "x"
+===*/
@@ -0,0 +1,23 @@
+#pragma gnu
+asm(".globl foo");
+int x;
+__asm__(".section .text");
+/*===
+TranslationUnit
+    ExternalDeclaration
+        AsmStatement
+            GnuBasicAsmStatement
+                StringLiteral ["\".globl foo\""]
+    ExternalDeclaration
+        Declaration
+            DeclarationSpecifier
+                TypeSpecifier Int
+            InitDeclarator
+                Declarator
+                    DeclaratorKind
+                        Identifier "x"
+    ExternalDeclaration
+        AsmStatement
+            GnuBasicAsmStatement
+                StringLiteral ["\".section .text\""]
+===*/
@@ -0,0 +1,4 @@
+asm(".globl foo");
+/*===
+~ERROR
+===*/
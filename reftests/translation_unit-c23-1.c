@@ -0,0 +1,52 @@
+#pragma c23
+static_assert(sizeof(int) == 4);
+bool f(void) { return alignof(int) == 4; }
+/*===
+TranslationUnit
+    ExternalDeclaration
+        StaticAssert
+            Expression
+                BinaryOperatorExpression
+                    Expression
+                        SizeOfTy
+                            TypeName
+                                SpecifierQualifier
+                                    TypeSpecifier Int
+                    Expression
+                        Constant
+                            Integer "4"
+                                IntegerBase Decimal
+                                IntegerSuffix false false
+                                    IntegerSize Int
+                    BinaryOperator Equals
+    ExternalDeclaration
+        FunctionDefinition
+            DeclarationSpecifier
+                TypeSpecifier
+            Declarator
+                DeclaratorKind
+                    Identifier "f"
+                DerivedDeclarator
+                    FunctionDeclarator
+                        ParameterDeclaration
+                            DeclarationSpecifier
+                                TypeSpecifier Void
+                        Ellipsis None
+            Statement Compound
+                BlockItem
+                    Statement Return
+                        Expression
+                            BinaryOperatorExpression
+                                Expression
+                                    AlignOf
+                                        TypeName
+                                            SpecifierQualifier
+                                                TypeSpecifier Int
+                                Expression
+                                    Constant
+                                        Integer "4"
+                                            IntegerBase Decimal
+                                            IntegerSuffix false false
+                                                IntegerSize Int
+                                BinaryOperator Equals
+===*/
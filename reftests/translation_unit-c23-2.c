@@ -0,0 +1,23 @@
+#pragma c23
+static_assert(1, "");
+static_assert(1);
+/*===
+TranslationUnit
+    ExternalDeclaration
+        StaticAssert
+            Expression
+                Constant
+                    Integer "1"
+                        IntegerBase Decimal
+                        IntegerSuffix false false
+                            IntegerSize Int
+            StringLiteral ["\"\""]
+    ExternalDeclaration
+        StaticAssert
+            Expression
+                Constant
+                    Integer "1"
+                        IntegerBase Decimal
+                        IntegerSuffix false false
+                            IntegerSize Int
+===*/
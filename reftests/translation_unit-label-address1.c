@@ -0,0 +1,110 @@
+#pragma gnu
+void run(int *code) {
+    static void *table[] = { &&add, &&halt };
+    void *p = &&halt;
+    goto *table[*code++];
+add:
+    goto **(void **)&p;
+halt:
+    return;
+}
+/*===
+TranslationUnit
+    ExternalDeclaration
+        FunctionDefinition
+            DeclarationSpecifier
+                TypeSpecifier Void
+            Declarator
+                DeclaratorKind
+                    Identifier "run"
+                DerivedDeclarator
+                    FunctionDeclarator
+                        ParameterDeclaration
+                            DeclarationSpecifier
+                                TypeSpecifier Int
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "code"
+                                DerivedDeclarator Pointer
+                        Ellipsis None
+            Statement Compound
+                BlockItem
+                    Declaration
+                        DeclarationSpecifier
+                            StorageClassSpecifier Static
+                        DeclarationSpecifier
+                            TypeSpecifier Void
+                        InitDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "table"
+                                DerivedDeclarator Pointer
+                                DerivedDeclarator
+                                    ArrayDeclarator
+                                        ArraySize Unknown
+                            Initializer
+                                InitializerListItem
+                                    Initializer
+                                        Expression LabelAddress
+                                            Identifier "add"
+                                InitializerListItem
+                                    Initializer
+                                        Expression LabelAddress
+                                            Identifier "halt"
+                BlockItem
+                    Declaration
+                        DeclarationSpecifier
+                            TypeSpecifier Void
+                        InitDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "p"
+                                DerivedDeclarator Pointer
+                            Initializer
+                                Expression LabelAddress
+                                    Identifier "halt"
+                BlockItem
+                    Statement GotoComputed
+                        Expression
+                            BinaryOperatorExpression
+                                Expression
+                                    Identifier "table"
+                                Expression
+                                    UnaryOperatorExpression
+                                        UnaryOperator Indirection
+                                        Expression
+                                            UnaryOperatorExpression
+                                                Expression
+                                                    Identifier "code"
+                                                UnaryOperator PostIncrement
+                                BinaryOperator Index
+                BlockItem
+                    Statement
+                        LabeledStatement
+                            Label
+                                Identifier "add"
+                            Statement GotoComputed
+                                Expression
+                                    UnaryOperatorExpression
+                                        UnaryOperator Indirection
+                                        Expression
+                                            CastExpression
+                                                TypeName
+                                                    SpecifierQualifier
+                                                        TypeSpecifier Void
+                                                    Declarator
+                                                        DeclaratorKind Abstract
+                                                        DerivedDeclarator Pointer
+                                                        DerivedDeclarator Pointer
+                                                Expression
+                                                    UnaryOperatorExpression
+                                                        UnaryOperator Address
+                                                        Expression
+                                                            Identifier "p"
+                BlockItem
+                    Statement
+                        LabeledStatement
+                            Label
+                                Identifier "halt"
+                            Statement Return
+===*/
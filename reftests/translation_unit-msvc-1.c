@@ -0,0 +1,74 @@
+#pragma msvc
+__pragma(warning(disable: 4996)) int x;
+void f(void) {
+    __pragma(message("a)")) x = (int (__cdecl *)(void))0 != 0;
+}
+/*===
+TranslationUnit
+    ExternalDeclaration
+        Pragma ["warning", "(", "disable", ":", "4996", ")"]
+    ExternalDeclaration
+        Declaration
+            DeclarationSpecifier
+                TypeSpecifier Int
+            InitDeclarator
+                Declarator
+                    DeclaratorKind
+                        Identifier "x"
+    ExternalDeclaration
+        FunctionDefinition
+            DeclarationSpecifier
+                TypeSpecifier Void
+            Declarator
+                DeclaratorKind
+                    Identifier "f"
+                DerivedDeclarator
+                    FunctionDeclarator
+                        ParameterDeclaration
+                            DeclarationSpecifier
+                                TypeSpecifier Void
+                        Ellipsis None
+            Statement Compound
+                BlockItem
+                    Pragma ["message", "(", "\"a)\"", ")"]
+                BlockItem
+                    Statement
+                        Expression
+                            BinaryOperatorExpression
+                                Expression
+                                    Identifier "x"
+                                Expression
+                                    BinaryOperatorExpression
+                                        Expression
+                                            CastExpression
+                                                TypeName
+                                                    SpecifierQualifier
+                                                        TypeSpecifier Int
+                                                    Declarator
+                                                        DeclaratorKind
+                                                            Declarator
+                                                                DeclaratorKind Abstract
+                                                                DerivedDeclarator Pointer
+                                                                Extension
+                                                                    CallingConvention Cdecl
+                                                        DerivedDeclarator
+                                                            FunctionDeclarator
+                                                                ParameterDeclaration
+                                                                    DeclarationSpecifier
+                                                                        TypeSpecifier Void
+                                                                Ellipsis None
+                                                Expression
+                                                    Constant
+                                                        Integer "0"
+                                                            IntegerBase Decimal
+                                                            IntegerSuffix false false
+                                                                IntegerSize Int
+                                        Expression
+                                            Constant
+                                                Integer "0"
+                                                    IntegerBase Decimal
+                                                    IntegerSuffix false false
+                                                        IntegerSize Int
+                                        BinaryOperator NotEquals
+                                BinaryOperator Assign
+===*/
@@ -0,0 +1,83 @@
+#pragma msvc
+void (__stdcall *fp)(void), __cdecl g(void);
+int __declspec(dllexport) h(void) { __try { return 1; } __except (1) { return 0; } }
+/*===
+TranslationUnit
+    ExternalDeclaration
+        Declaration
+            DeclarationSpecifier
+                TypeSpecifier Void
+            InitDeclarator
+                Declarator
+                    DeclaratorKind
+                        Declarator
+                            DeclaratorKind
+                                Identifier "fp"
+                            DerivedDeclarator Pointer
+                            Extension
+                                CallingConvention Stdcall
+                    DerivedDeclarator
+                        FunctionDeclarator
+                            ParameterDeclaration
+                                DeclarationSpecifier
+                                    TypeSpecifier Void
+                            Ellipsis None
+            InitDeclarator
+                Declarator
+                    DeclaratorKind
+                        Identifier "g"
+                    DerivedDeclarator
+                        FunctionDeclarator
+                            ParameterDeclaration
+                                DeclarationSpecifier
+                                    TypeSpecifier Void
+                            Ellipsis None
+                    Extension
+                        CallingConvention Cdecl
+    ExternalDeclaration
+        FunctionDefinition
+            DeclarationSpecifier
+                TypeSpecifier Int
+            DeclarationSpecifier
+                Extension Declspec
+                    Attribute "dllexport"
+            Declarator
+                DeclaratorKind
+                    Identifier "h"
+                DerivedDeclarator
+                    FunctionDeclarator
+                        ParameterDeclaration
+                            DeclarationSpecifier
+                                TypeSpecifier Void
+                        Ellipsis None
+            Statement Compound
+                BlockItem
+                    Statement
+                        TryStatement
+                            Statement Compound
+                                BlockItem
+                                    Statement Return
+                                        Expression
+                                            Constant
+                                                Integer "1"
+                                                    IntegerBase Decimal
+                                                    IntegerSuffix false false
+                                                        IntegerSize Int
+                            TryHandler
+                                ExceptHandler
+                                    Expression
+                                        Constant
+                                            Integer "1"
+                                                IntegerBase Decimal
+                                                IntegerSuffix false false
+                                                    IntegerSize Int
+                                    Statement Compound
+                                        BlockItem
+                                            Statement Return
+                                                Expression
+                                                    Constant
+                                                        Integer "0"
+                                                            IntegerBase Decimal
+                                                            IntegerSuffix false false
+                                                                IntegerSize Int
+===*/
@@ -0,0 +1,89 @@
+#pragma gnu
+int outer(int x) {
+    int inner(int y) {
+        return x + y;
+    }
+    auto int later(void);
+    return inner(1) + later();
+}
+/*===
+TranslationUnit
+    ExternalDeclaration
+        FunctionDefinition
+            DeclarationSpecifier
+                TypeSpecifier Int
+            Declarator
+                DeclaratorKind
+                    Identifier "outer"
+                DerivedDeclarator
+                    FunctionDeclarator
+                        ParameterDeclaration
+                            DeclarationSpecifier
+                                TypeSpecifier Int
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "x"
+                        Ellipsis None
+            Statement Compound
+                BlockItem
+                    FunctionDefinition
+                        DeclarationSpecifier
+                            TypeSpecifier Int
+                        Declarator
+                            DeclaratorKind
+                                Identifier "inner"
+                            DerivedDeclarator
+                                FunctionDeclarator
+                                    ParameterDeclaration
+                                        DeclarationSpecifier
+                                            TypeSpecifier Int
+                                        Declarator
+                                            DeclaratorKind
+                                                Identifier "y"
+                                    Ellipsis None
+                        Statement Compound
+                            BlockItem
+                                Statement Return
+                                    Expression
+                                        BinaryOperatorExpression
+                                            Expression
+                                                Identifier "x"
+                                            Expression
+                                                Identifier "y"
+                                            BinaryOperator Plus
+                BlockItem
+                    Declaration
+                        DeclarationSpecifier
+                            StorageClassSpecifier Auto
+                        DeclarationSpecifier
+                            TypeSpecifier Int
+                        InitDeclarator
+                            Declarator
+                                DeclaratorKind
+                                    Identifier "later"
+                                DerivedDeclarator
+                                    FunctionDeclarator
+                                        ParameterDeclaration
+                                            DeclarationSpecifier
+                                                TypeSpecifier Void
+                                        Ellipsis None
+                BlockItem
+                    Statement Return
+                        Expression
+                            BinaryOperatorExpression
+                                Expression
+                                    CallExpression
+                                        Expression
+                                            Identifier "inner"
+                                        Expression
+                                            Constant
+                                                Integer "1"
+                                                    IntegerBase Decimal
+                                                    IntegerSuffix false false
+                                                        IntegerSize Int
+                                Expression
+                                    CallExpression
+                                        Expression
+                                            Identifier "later"
+                                BinaryOperator Plus
+===*/
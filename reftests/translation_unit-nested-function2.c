@@ -0,0 +1,9 @@
+int outer(int x) {
+    int inner(int y) {
+        return x + y;
+    }
+    return inner(1);
+}
+/*===
+~ERROR
+===*/
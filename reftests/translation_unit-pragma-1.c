@@ -0,0 +1,74 @@
+// Pragma directives and operators are kept in the tree, line markers are not
+ #pragma pack(push, 1)
+struct s { char c; int i; };
+# 1 "pack.h"
+ #pragma pack(pop)
+_Pragma("GCC diagnostic ignored \"-Wall\"") int x;
+void f(void) {
+ #pragma omp parallel for
+    for (;;) {}
+    _Pragma("once")
+}
+/*===
+TranslationUnit
+    ExternalDeclaration
+        Pragma ["pack", "(", "push", ",", "1", ")"]
+    ExternalDeclaration
+        Declaration
+            DeclarationSpecifier
+                TypeSpecifier
+                    StructType
+                        StructKind Struct
+                        Identifier "s"
+                        StructDeclaration
+                            StructField
+                                SpecifierQualifier
+                                    TypeSpecifier Char
+                                StructDeclarator
+                                    Declarator
+                                        DeclaratorKind
+                                            Identifier "c"
+                        StructDeclaration
+                            StructField
+                                SpecifierQualifier
+                                    TypeSpecifier Int
+                                StructDeclarator
+                                    Declarator
+                                        DeclaratorKind
+                                            Identifier "i"
+    ExternalDeclaration
+        Pragma ["pack", "(", "pop", ")"]
+    ExternalDeclaration
+        Pragma ["GCC", "diagnostic", "ignored", "\"-Wall\""]
+    ExternalDeclaration
+        Declaration
+            DeclarationSpecifier
+                TypeSpecifier Int
+            InitDeclarator
+                Declarator
+                    DeclaratorKind
+                        Identifier "x"
+    ExternalDeclaration
+        FunctionDefinition
+            DeclarationSpecifier
+                TypeSpecifier Void
+            Declarator
+                DeclaratorKind
+                    Identifier "f"
+                DerivedDeclarator
+                    FunctionDeclarator
+                        ParameterDeclaration
+                            DeclarationSpecifier
+                                TypeSpecifier Void
+                        Ellipsis None
+            Statement Compound
+                BlockItem
+                    Pragma ["omp", "parallel", "for"]
+                BlockItem
+                    Statement
+                        ForStatement
+                            ForInitializer Empty
+                            Statement Compound
+                BlockItem
+                    Pragma ["once"]
+===*/
@@ -42,7 +42,18 @@ pub struct Identifier {
 pub enum Constant {
     Integer(Integer),
     Float(Float),
+    /// Character constant as written, including the prefix and quotes
+    ///
+    /// The value depends on the target and is computed by `literal::CharacterValue::decode`.
     Character(String),
+    /// `true` or `false`
+    ///
+    /// (C23 6.4.4.6)
+    Bool(bool),
+    /// `nullptr`
+    ///
+    /// (C23 6.4.4.6)
+    Nullptr,
 }
 
 /// Integer number literal
@@ -51,6 +62,7 @@ pub enum Constant {
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Integer {
     pub base: IntegerBase,
+    /// Digits without the base prefix and C23 digit separators
     pub number: Box<str>,
     pub suffix: IntegerSuffix,
 }
@@ -63,7 +75,7 @@ pub enum IntegerBase {
     Decimal,
     Octal,
     Hexadecimal,
-    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Binary-constants.html)
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Binary-constants.html), standard in C23
     Binary,
 }
 
@@ -93,6 +105,10 @@ pub enum IntegerSize {
     Long,
     /// `ll`
     LongLong,
+    /// `wb`, bit-precise integer type `_BitInt(N)`
+    ///
+    /// (C23 6.4.4.1)
+    BitInt,
 }
 
 /// Floating point number literal
@@ -101,6 +117,7 @@ pub enum IntegerSize {
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Float {
     pub base: FloatBase,
+    /// Digits without the base prefix and C23 digit separators
     pub number: Box<str>,
     pub suffix: FloatSuffix,
 }
@@ -147,6 +164,10 @@ pub enum FloatFormat {
 
 /// String literal
 ///
+/// Adjacent literals are kept as separate pieces, each as written including the prefix and
+/// quotes. Concatenation and decoding of escape sequences depend on the target and are done by
+/// `literal::StringValue::decode`.
+///
 /// (C11 6.4.5)
 pub type StringLiteral = Vec<String>;
 
@@ -265,6 +286,41 @@ pub enum Expression {
     ///
     /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Statement-Exprs.html)
     Statement(Box<Node<Statement>>),
+
+    /// Address of a label
+    ///
+    /// `&&label`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Labels-as-Values.html)
+    LabelAddress(Node<Identifier>),
+
+    /// Type compatibility test
+    ///
+    /// `__builtin_types_compatible_p(type1, type2)`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Other-Builtins.html)
+    TypesCompatible(Box<Node<TypesCompatibleExpression>>),
+
+    /// Compile-time choice between two expressions
+    ///
+    /// `__builtin_choose_expr(condition, expr1, expr2)`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Other-Builtins.html)
+    ChooseExpr(Box<Node<ChooseExpression>>),
+
+    /// Vector element type conversion
+    ///
+    /// `__builtin_convertvector(vector, type)`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Vector-Extensions.html)
+    ConvertVector(Box<Node<ConvertVectorExpression>>),
+
+    /// Vector element shuffle
+    ///
+    /// `__builtin_shufflevector(vector1, vector2, index...)`
+    ///
+    /// [Clang extension](https://clang.llvm.org/docs/LanguageExtensions.html#builtin-shufflevector)
+    ShuffleVector(Box<Node<ShuffleVectorExpression>>),
 }
 
 /// Struct or union member access
@@ -376,6 +432,14 @@ pub enum UnaryOperator {
     Complement,
     /// `!operand`
     Negate,
+    /// `__real__ operand`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Complex.html)
+    Real,
+    /// `__imag__ operand`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Complex.html)
+    Imag,
 }
 
 /// Unary operator expression
@@ -486,7 +550,10 @@ pub struct BinaryOperatorExpression {
 #[derive(Debug, PartialEq, Clone)]
 pub struct ConditionalExpression {
     pub condition: Box<Node<Expression>>,
-    pub then_expression: Box<Node<Expression>>,
+    /// `None` if the middle operand is omitted, as in `a ?: b`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Conditionals.html)
+    pub then_expression: Option<Box<Node<Expression>>>,
     pub else_expression: Box<Node<Expression>>,
 }
 
@@ -512,6 +579,44 @@ pub struct OffsetOfExpression {
     pub designator: Node<OffsetDesignator>,
 }
 
+/// Type compatibility test
+///
+/// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Other-Builtins.html)
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypesCompatibleExpression {
+    pub type_name_1: Node<TypeName>,
+    pub type_name_2: Node<TypeName>,
+}
+
+/// Compile-time choice between two expressions
+///
+/// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Other-Builtins.html)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChooseExpression {
+    pub condition: Box<Node<Expression>>,
+    pub then_expression: Box<Node<Expression>>,
+    pub else_expression: Box<Node<Expression>>,
+}
+
+/// Vector element type conversion
+///
+/// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Vector-Extensions.html)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConvertVectorExpression {
+    pub vector: Box<Node<Expression>>,
+    pub type_name: Node<TypeName>,
+}
+
+/// Vector element shuffle
+///
+/// [Clang extension](https://clang.llvm.org/docs/LanguageExtensions.html#builtin-shufflevector)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShuffleVectorExpression {
+    pub vector_1: Box<Node<Expression>>,
+    pub vector_2: Box<Node<Expression>>,
+    pub indices: Vec<Node<Expression>>,
+}
+
 /// Offset designator in a `offsetof` macro expansion
 ///
 /// (C11 7.19 §3).
@@ -580,12 +685,16 @@ pub enum StorageClassSpecifier {
     Extern,
     /// `static`
     Static,
-    /// `_Thread_local`
+    /// `_Thread_local` or `thread_local`
     ThreadLocal,
     /// `auto`
     Auto,
     /// `register`
     Register,
+    /// `constexpr`
+    ///
+    /// (C23 6.7.1)
+    Constexpr,
 }
 
 // From 6.7.2
@@ -615,12 +724,20 @@ pub enum TypeSpecifier {
     Signed,
     /// `unsigned`
     Unsigned,
-    /// `_Bool`
+    /// `_Bool` or `bool`
     Bool,
     /// `_Complex`
     ///
     /// `__complex`, `__complex__` (GNU extension)
     Complex,
+    /// `__int8`, `__int16`, `__int32`, `__int64`, integer type of the given width in bits
+    ///
+    /// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/int8-int16-int32-int64)
+    SizedInt(usize),
+    /// `_BitInt(N)`, bit-precise integer type of width `N`
+    ///
+    /// (C23 6.7.2)
+    BitInt(Box<Node<Expression>>),
     /// `_Atomic(typename)`
     Atomic(Node<TypeName>),
     /// `struct identifier { … }`
@@ -633,8 +750,12 @@ pub enum TypeSpecifier {
     TypedefName(Node<Identifier>),
     /// Specifies type of another type or expression
     ///
-    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Typeof.html)
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Typeof.html), (C23 6.7.3.6)
     TypeOf(Node<TypeOf>),
+    /// Specifies unqualified type of another type or expression
+    ///
+    /// (C23 6.7.3.6)
+    TypeOfUnqual(Node<TypeOf>),
     /// Floating point types with guaranteed width and representation
     ///
     /// `_Float16`, `_Float32`, `_Float64`, `_Float128`
@@ -677,6 +798,8 @@ pub enum TS18661FloatFormat {
 #[derive(Debug, PartialEq, Clone)]
 pub struct StructType {
     pub kind: Node<StructKind>,
+    /// Vendor-specific extensions between the keyword and the tag
+    pub extensions: Vec<Node<Extension>>,
     pub identifier: Option<Node<Identifier>>,
     /// List of structure of union members, when present.
     ///
@@ -700,6 +823,8 @@ pub enum StructKind {
 pub enum StructDeclaration {
     Field(Node<StructField>),
     StaticAssert(Node<StaticAssert>),
+    /// `#pragma` directive or `_Pragma` operator between member declarations
+    Pragma(Node<Pragma>),
 }
 
 /// Struct field declaration
@@ -718,6 +843,10 @@ pub struct StructField {
 pub enum SpecifierQualifier {
     TypeSpecifier(Node<TypeSpecifier>),
     TypeQualifier(Node<TypeQualifier>),
+    /// Alignment of a structure member
+    ///
+    /// (C17 6.7.2.1)
+    Alignment(Node<AlignmentSpecifier>),
     Extension(Vec<Node<Extension>>),
 }
 
@@ -799,6 +928,10 @@ pub enum FunctionSpecifier {
     Inline,
     /// `_Noreturn`
     Noreturn,
+    /// `__forceinline`
+    ///
+    /// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/inline-functions-cpp)
+    ForceInline,
 }
 
 // From 6.7.5
@@ -875,6 +1008,8 @@ pub enum DerivedDeclarator {
 pub struct ArrayDeclarator {
     pub qualifiers: Vec<Node<TypeQualifier>>,
     pub size: ArraySize,
+    /// Attributes of the array type, following the brackets (C23 6.7.6.2)
+    pub extensions: Vec<Node<Extension>>,
 }
 
 /// Function parameter part of a declarator
@@ -882,6 +1017,8 @@ pub struct ArrayDeclarator {
 pub struct FunctionDeclarator {
     pub parameters: Vec<Node<ParameterDeclaration>>,
     pub ellipsis: Ellipsis,
+    /// Attributes of the function type, following the parameter list (C23 6.7.6.3)
+    pub extensions: Vec<Node<Extension>>,
 }
 
 /// List of qualifiers that can follow a `*` in a declaration
@@ -1006,7 +1143,8 @@ pub struct RangeDesignator {
 #[derive(Debug, PartialEq, Clone)]
 pub struct StaticAssert {
     pub expression: Box<Node<Expression>>,
-    pub message: Node<StringLiteral>,
+    /// `None` if the message is omitted (C23)
+    pub message: Option<Node<StringLiteral>>,
 }
 
 // From 6.8 Statement
@@ -1025,11 +1163,68 @@ pub enum Statement {
     DoWhile(Node<DoWhileStatement>),
     For(Node<ForStatement>),
     Goto(Node<Identifier>),
+    /// Jump to a label address
+    ///
+    /// `goto *expression;`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Labels-as-Values.html)
+    GotoComputed(Box<Node<Expression>>),
     Continue,
     Break,
     Return(Option<Box<Node<Expression>>>),
+    /// Statement preceded by standard attributes
+    ///
+    /// (C23 6.8)
+    Attributed(Node<AttributedStatement>),
     /// Vendor specific inline assembly extensions
     Asm(Node<AsmStatement>),
+    /// Structured exception handling
+    ///
+    /// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/structured-exception-handling-c-cpp)
+    Try(Node<TryStatement>),
+    /// `__leave`
+    ///
+    /// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/try-finally-statement)
+    Leave,
+}
+
+/// Statement with attributes
+///
+/// (C23 6.8)
+#[derive(Debug, PartialEq, Clone)]
+pub struct AttributedStatement {
+    pub attributes: Vec<Node<Extension>>,
+    pub statement: Box<Node<Statement>>,
+}
+
+/// Structured exception handling statement
+///
+/// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/structured-exception-handling-c-cpp)
+#[derive(Debug, PartialEq, Clone)]
+pub struct TryStatement {
+    /// Guarded body of `__try`
+    pub statement: Box<Node<Statement>>,
+    pub handler: Node<TryHandler>,
+}
+
+/// Handler part of a structured exception handling statement
+///
+/// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/structured-exception-handling-c-cpp)
+#[derive(Debug, PartialEq, Clone)]
+pub enum TryHandler {
+    /// `__except (filter) { … }`
+    Except(Node<ExceptHandler>),
+    /// `__finally { … }`
+    Finally(Box<Node<Statement>>),
+}
+
+/// Exception handler with a filter expression
+///
+/// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/try-except-statement)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExceptHandler {
+    pub filter: Box<Node<Expression>>,
+    pub statement: Box<Node<Statement>>,
 }
 
 /// Labeled statement
@@ -1144,6 +1339,20 @@ pub enum BlockItem {
     Declaration(Node<Declaration>),
     StaticAssert(Node<StaticAssert>),
     Statement(Node<Statement>),
+    /// Local label declaration
+    ///
+    /// `__label__ a, b;`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Local-Labels.html)
+    LabelDeclaration(Vec<Node<Identifier>>),
+    /// Nested function definition
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Nested-Functions.html)
+    FunctionDefinition(Node<FunctionDefinition>),
+    /// `#pragma` directive or `_Pragma` operator between block items
+    Pragma(Node<Pragma>),
+    /// Input skipped by error recovery
+    Error,
 }
 
 // From 6.9 External definitions
@@ -1162,6 +1371,14 @@ pub enum ExternalDeclaration {
     Declaration(Node<Declaration>),
     StaticAssert(Node<StaticAssert>),
     FunctionDefinition(Node<FunctionDefinition>),
+    /// Top-level assembler statement
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Basic-Asm.html)
+    Asm(Node<AsmStatement>),
+    /// `#pragma` directive or `_Pragma` operator between declarations
+    Pragma(Node<Pragma>),
+    /// Input skipped by error recovery
+    Error,
 }
 
 /// Function definition
@@ -1179,6 +1396,23 @@ pub struct FunctionDefinition {
     pub statement: Node<Statement>,
 }
 
+// From 6.10.6 and 6.10.9 Pragmas
+
+/// Implementation-defined pragma
+///
+/// `#pragma pack(push, 1)` or `_Pragma("pack(push, 1)")`. Spans of the tokens point into the
+/// source, inside the string literal for the `_Pragma` operator.
+///
+/// (C11 6.10.6, 6.10.9)
+///
+/// Also used for the [MSVC extension](https://learn.microsoft.com/en-us/cpp/preprocessor/pragma-directives-and-the-pragma-keyword)
+/// `__pragma(pack(push, 1))` left in the preprocessed source.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pragma {
+    /// Preprocessing tokens following the `pragma` keyword
+    pub tokens: Vec<Node<String>>,
+}
+
 // Syntax extensions
 
 /// Extended vendor-specific syntax that does not fit elsewhere
@@ -1196,6 +1430,37 @@ pub enum Extension {
     ///
     /// [Clang extension](https://clang.llvm.org/docs/AttributeReference.html#availability)
     AvailabilityAttribute(Node<AvailabilityAttribute>),
+    /// Standard attribute
+    ///
+    /// (C23 6.7.13.2)
+    StandardAttribute(Node<StandardAttribute>),
+    /// Microsoft declaration attribute, `__declspec(name)` or `__declspec(name(arguments))`
+    ///
+    /// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/declspec)
+    Declspec(Attribute),
+    /// Calling convention keyword
+    ///
+    /// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/calling-conventions)
+    CallingConvention(CallingConvention),
+}
+
+/// Calling conventions
+///
+/// [MSVC extension](https://learn.microsoft.com/en-us/cpp/cpp/calling-conventions)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum CallingConvention {
+    /// `__cdecl`
+    Cdecl,
+    /// `__clrcall`
+    Clrcall,
+    /// `__stdcall`
+    Stdcall,
+    /// `__fastcall`
+    Fastcall,
+    /// `__thiscall`
+    Thiscall,
+    /// `__vectorcall`
+    Vectorcall,
 }
 
 /// Attributes
@@ -1207,6 +1472,20 @@ pub struct Attribute {
     pub arguments: Vec<Node<Expression>>,
 }
 
+/// Standard attribute
+///
+/// `[[prefix::name(arguments)]]`
+///
+/// (C23 6.7.13.2)
+#[derive(Debug, PartialEq, Clone)]
+pub struct StandardAttribute {
+    /// Vendor prefix, such as `gnu` in `gnu::packed`
+    pub prefix: Option<Node<String>>,
+    pub name: Node<String>,
+    /// Tokens of the argument clause, `None` if there is no clause
+    pub arguments: Option<Vec<Node<String>>>,
+}
+
 /// Platform availability attribute
 ///
 /// [Clang extension](https://clang.llvm.org/docs/AttributeReference.html#availability)
@@ -1245,7 +1524,7 @@ pub enum AsmStatement {
     /// Basic asm statement with just source code
     ///
     /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Basic-Asm.html)
-    GnuBasic(Node<StringLiteral>),
+    GnuBasic(GnuBasicAsmStatement),
 
     /// Extended statement that has access to C variables
     ///
@@ -1253,16 +1532,40 @@ pub enum AsmStatement {
     GnuExtended(GnuExtendedAsmStatement),
 }
 
+/// Basic asm statement with just source code
+///
+/// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Basic-Asm.html)
+#[derive(Debug, PartialEq, Clone)]
+pub struct GnuBasicAsmStatement {
+    pub qualifiers: Vec<Node<AsmQualifier>>,
+    pub template: Node<StringLiteral>,
+}
+
 /// Extended statement that has access to C variables
 ///
 /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Extended-Asm.html)
 #[derive(Debug, PartialEq, Clone)]
 pub struct GnuExtendedAsmStatement {
-    pub qualifier: Option<Node<TypeQualifier>>,
+    pub qualifiers: Vec<Node<AsmQualifier>>,
     pub template: Node<StringLiteral>,
     pub outputs: Vec<Node<GnuAsmOperand>>,
     pub inputs: Vec<Node<GnuAsmOperand>>,
     pub clobbers: Vec<Node<StringLiteral>>,
+    /// Jump targets of an `asm goto` statement
+    pub labels: Vec<Node<Identifier>>,
+}
+
+/// Qualifier of GNU asm statement
+///
+/// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Extended-Asm.html#Qualifiers)
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum AsmQualifier {
+    /// `volatile`
+    Volatile,
+    /// `inline`
+    Inline,
+    /// `goto`
+    Goto,
 }
 
 /// Single input or output operand specifier for GNU extended asm statement
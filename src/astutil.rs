@@ -107,6 +107,9 @@ pub fn int_suffix(mut s: &str) -> Result<IntegerSuffix, &'static str> {
         } else if l == IntegerSize::Int && (s.starts_with("l") || s.starts_with("L")) {
             l = IntegerSize::Long;
             s = &s[1..];
+        } else if l == IntegerSize::Int && (s.starts_with("wb") || s.starts_with("WB")) {
+            l = IntegerSize::BitInt;
+            s = &s[2..];
         } else if !u && (s.starts_with("u") || s.starts_with("U")) {
             u = true;
             s = &s[1..];
@@ -129,3 +132,109 @@ pub fn int_suffix(mut s: &str) -> Result<IntegerSuffix, &'static str> {
         imaginary: i,
     })
 }
+
+pub fn strip_digit_separators(s: &str) -> Box<str> {
+    if s.contains('\'') {
+        s.replace('\'', "").into_boxed_str()
+    } else {
+        s.into()
+    }
+}
+
+/// Split the text of a `#pragma` directive starting at `start` into preprocessing tokens
+pub fn pragma_tokens(text: &str, start: usize) -> Vec<Node<String>> {
+    let offsets: Vec<usize> = (0..text.len() + 1).map(|i| start + i).collect();
+    tokenize(text, &offsets)
+}
+
+/// Destringize the operand of a `_Pragma` operator starting at `start` and split it into
+/// preprocessing tokens (C11 6.10.9)
+pub fn pragma_operator_tokens(literal: &str, start: usize) -> Vec<Node<String>> {
+    let first = literal.find('"').unwrap() + 1;
+    let last = literal.len() - 1;
+    let mut text = String::new();
+    let mut offsets = Vec::new();
+    let mut chars = literal[first..last].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let c = match (c, chars.peek()) {
+            ('\\', Some(&(_, n))) if n == '"' || n == '\\' => {
+                chars.next();
+                n
+            }
+            _ => c,
+        };
+        offsets.extend((0..c.len_utf8()).map(|_| start + first + i));
+        text.push(c);
+    }
+    offsets.push(start + last);
+    tokenize(&text, &offsets)
+}
+
+const PUNCTUATORS: &[&str] = &[
+    "%:%:", "...", "<<=", ">>=", "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||",
+    "*=", "/=", "%=", "+=", "-=", "&=", "^=", "|=", "##", "<:", ":>", "<%", "%>", "%:", "::",
+];
+
+/// Split `text` into preprocessing tokens, `offsets` maps byte positions in `text` to the source
+fn tokenize(text: &str, offsets: &[usize]) -> Vec<Node<String>> {
+    let bytes = text.as_bytes();
+    let ident = |b: u8| b == b'_' || b.is_ascii_alphanumeric();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b' ' || b == b'\t' || b == b'\r' {
+            i += 1;
+            continue;
+        }
+        let mut j = i;
+        if ident(b) && !b.is_ascii_digit() {
+            while j < bytes.len() && ident(bytes[j]) {
+                j += 1;
+            }
+            let prefix = ["L", "u", "U", "u8"].contains(&&text[i..j]);
+            if prefix && j < bytes.len() && (bytes[j] == b'"' || bytes[j] == b'\'') {
+                j = quoted(bytes, j);
+            }
+        } else if b.is_ascii_digit()
+            || b == b'.' && i + 1 < bytes.len() && bytes[i + 1].is_ascii_digit()
+        {
+            j += 1;
+            while j < bytes.len() {
+                match bytes[j] {
+                    b'e' | b'E' | b'p' | b'P'
+                        if j + 1 < bytes.len()
+                            && (bytes[j + 1] == b'+' || bytes[j + 1] == b'-') =>
+                    {
+                        j += 2
+                    }
+                    b'.' | b'\'' => j += 1,
+                    c if ident(c) => j += 1,
+                    _ => break,
+                }
+            }
+        } else if b == b'"' || b == b'\'' {
+            j = quoted(bytes, j);
+        } else if let Some(p) = PUNCTUATORS.iter().find(|p| text[i..].starts_with(*p)) {
+            j += p.len();
+        } else {
+            j += text[i..].chars().next().unwrap().len_utf8();
+        }
+        tokens.push(Node::new(
+            text[i..j].to_owned(),
+            Span::span(offsets[i], offsets[j]),
+        ));
+        i = j;
+    }
+    tokens
+}
+
+/// End of the character constant or string literal opening at `i`
+fn quoted(bytes: &[u8], i: usize) -> usize {
+    let quote = bytes[i];
+    let mut j = i + 1;
+    while j < bytes.len() && bytes[j] != quote {
+        j += if bytes[j] == b'\\' { 2 } else { 1 };
+    }
+    (j + 1).min(bytes.len())
+}
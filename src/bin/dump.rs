@@ -4,21 +4,41 @@ extern crate lang_c;
 
 use std::process::exit;
 
-use lang_c::driver::{Config, Flavor};
+use lang_c::diagnostics::{Diagnostic, Emitter};
+use lang_c::driver::{Config, Error, Flavor};
+use lang_c::loc::SourceMap;
 use lang_c::visit::Visit;
 
 fn main() {
     let mut config = Config::default();
     let mut source = None;
     let mut quiet = false;
+    let mut unparse = false;
+    let mut json = false;
+    let mut color = false;
 
     for opt in std::env::args().skip(1) {
         if opt == "-use-gcc" {
             config = Config::with_gcc();
         } else if opt == "-use-clang" {
             config = Config::with_clang();
+        } else if opt == "-use-msvc" {
+            config = Config::with_msvc();
+        } else if opt == "-use-builtin" {
+            config = Config::with_builtin();
         } else if opt == "-use-std" {
             config.flavor = Flavor::StdC11;
+        } else if opt == "-use-c23" {
+            config.flavor = match config.flavor {
+                Flavor::StdC11 | Flavor::StdC23 => Flavor::StdC23,
+                _ => Flavor::GnuC23,
+            };
+        } else if opt == "-unparse" {
+            unparse = true;
+        } else if opt == "-json" {
+            json = true;
+        } else if opt == "-color" {
+            color = true;
         } else if opt == "-q" {
             quiet = true;
         } else if opt.starts_with("-") {
@@ -45,13 +65,27 @@ fn main() {
         Ok(parse) => {
             if !quiet {
                 let mut buf = String::new();
-                {
+                if unparse {
+                    lang_c::unparse::Unparser::new(&mut buf).translation_unit(&parse.unit);
+                } else {
                     let mut printer = lang_c::print::Printer::new(&mut buf);
                     printer.visit_translation_unit(&parse.unit);
                 }
                 println!("{}", buf);
             }
         }
+        Err(Error::SyntaxError(err)) => {
+            let map = SourceMap::new(&err.source);
+            let mut emitter = Emitter::new(&err.source, &map);
+            emitter.color = color;
+            let diagnostic = Diagnostic::from(&err);
+            if json {
+                println!("{}", emitter.json(&diagnostic));
+            } else {
+                print!("{}", emitter.render(&diagnostic));
+            }
+            exit(1);
+        }
         Err(err) => {
             println!("{}", err);
             exit(1);
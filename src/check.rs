@@ -0,0 +1,1914 @@
+//! Expression type checking
+//!
+//! Computes the type of every expression in a translation unit and reports violations of the
+//! constraints on operand types (C11 6.5).
+//!
+//! ```
+//! # use lang_c::check::check;
+//! # use lang_c::driver::{parse_preprocessed, Config};
+//! # use lang_c::layout::{DataModel, Target};
+//! let source = "struct s { char name[8]; } *p; long n; void f(void) { p->name + n; }";
+//! let parse = parse_preprocessed(&Config::default(), source.into()).unwrap();
+//! let typing = check(Target::new(DataModel::LP64), &parse.unit);
+//! assert!(typing.errors.is_empty());
+//!
+//! let start = source.find("p->").unwrap();
+//! let member = typing.expression_type(start, start + 7).unwrap();
+//! assert_eq!(member.ty.to_string(), "char [8]");
+//! assert!(member.is_lvalue);
+//! let sum = typing.expression_type(start, start + 11).unwrap();
+//! assert_eq!(sum.ty.to_string(), "char *");
+//! ```
+//!
+//! Types of expressions are recorded as written in the source: arrays and functions are not
+//! converted to pointers and lvalues keep their qualifiers. `ExpressionType::value_type` gives
+//! the type after these conversions.
+//!
+//! Expressions in initializer lists are typed, but not checked against the types of the
+//! elements they initialize. Vector types and complex integer types are not supported,
+//! expressions that involve them have no type.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+use ast::*;
+use eval::{EvalError, IntegerType};
+use layout::Target;
+use literal::{CharacterValue, IntegerValue, LiteralError, LiteralErrorKind, StringValue};
+use span::{Node, Span};
+use types::{self, ArrayLength, CType, FloatType, FunctionType, QualType, Tag, TypeResolver};
+use visit::{self, Visit};
+
+macro_rules! otry {
+    ($e:expr) => {
+        match $e {
+            Some(v) => v,
+            None => return None,
+        }
+    };
+}
+
+/// Type and value category of an expression
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExpressionType {
+    pub ty: QualType,
+    /// Expression designates an object (C11 6.3.2.1)
+    pub is_lvalue: bool,
+}
+
+impl ExpressionType {
+    fn rvalue(ty: QualType) -> ExpressionType {
+        ExpressionType {
+            ty,
+            is_lvalue: false,
+        }
+    }
+
+    fn lvalue(ty: QualType) -> ExpressionType {
+        ExpressionType {
+            ty,
+            is_lvalue: true,
+        }
+    }
+
+    /// Type of the value of the expression
+    ///
+    /// Arrays and functions are converted to pointers and qualifiers are removed (C11 6.3.2.1).
+    /// Types of expressions in `typeof` are not available here, use
+    /// `TypeChecker::value_type` to convert them too.
+    pub fn value_type(&self) -> QualType {
+        let canonical = self.ty.canonical();
+        match canonical.ty {
+            CType::Array(a) => QualType::new(CType::Pointer(a.element)),
+            CType::Function(_) => QualType::new(CType::Pointer(Box::new(canonical))),
+            _ if canonical.qualifiers.is_empty() => self.ty.unqualified(),
+            _ => canonical.unqualified(),
+        }
+    }
+}
+
+/// Kind of a type error
+#[derive(Debug, PartialEq, Clone)]
+pub enum TypeErrorKind {
+    /// Identifier is not declared
+    Undeclared(String),
+    /// Type of a declaration or value of a constant can not be determined
+    Evaluation(EvalError),
+    /// Character constant or string literal is malformed
+    InvalidLiteral(LiteralErrorKind),
+    /// Operand types are not valid for the operator
+    InvalidOperands,
+    /// Operand of `*` is not a pointer
+    NotPointer,
+    /// Called expression is not a function
+    NotFunction,
+    /// Number of arguments does not match the prototype
+    ArgumentCount { expected: usize, found: usize },
+    /// Operand of `.` or `->` is not a structure or union
+    NotStructure,
+    /// Structure or union has no member with this name
+    UnknownMember(String),
+    /// Structure or union is not defined
+    IncompleteType,
+    /// Operand of `&` is not an lvalue or a function
+    NotLvalue,
+    /// Left operand of an assignment or operand of `++` or `--` is not a modifiable lvalue
+    NotModifiable,
+    /// Value can not be converted to the type it is assigned to
+    ///
+    /// Applies to assignments, function arguments, initializers and `return`.
+    IncompatibleTypes { expected: QualType, found: QualType },
+    /// Controlling expression does not have a scalar type
+    NotScalar,
+    /// Controlling expression of `switch` does not have an integer type
+    NotInteger,
+    /// Cast between these types is not allowed
+    InvalidCast,
+    /// No association of a generic selection matches the controlling expression
+    NoGenericMatch(QualType),
+    /// `return` with a value in a function returning `void`, or without a value in a function
+    /// returning a value
+    InvalidReturn,
+}
+
+/// Type error
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypeError {
+    pub kind: TypeErrorKind,
+    /// Offending expression, declaration or statement
+    pub span: Span,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            TypeErrorKind::Undeclared(ref name) => write!(fmt, "'{}' is not declared", name),
+            TypeErrorKind::Evaluation(ref e) => write!(fmt, "{}", e),
+            TypeErrorKind::InvalidLiteral(ref kind) => {
+                let e = LiteralError {
+                    kind: kind.clone(),
+                    span: self.span,
+                };
+                write!(fmt, "{}", e)
+            }
+            TypeErrorKind::InvalidOperands => write!(fmt, "invalid operands"),
+            TypeErrorKind::NotPointer => write!(fmt, "operand is not a pointer"),
+            TypeErrorKind::NotFunction => write!(fmt, "called object is not a function"),
+            TypeErrorKind::ArgumentCount { expected, found } => write!(
+                fmt,
+                "function expects {} arguments, {} given",
+                expected, found
+            ),
+            TypeErrorKind::NotStructure => write!(fmt, "operand is not a structure or union"),
+            TypeErrorKind::UnknownMember(ref name) => write!(fmt, "no member named '{}'", name),
+            TypeErrorKind::IncompleteType => write!(fmt, "incomplete type"),
+            TypeErrorKind::NotLvalue => write!(fmt, "operand is not an lvalue"),
+            TypeErrorKind::NotModifiable => write!(fmt, "operand is not a modifiable lvalue"),
+            TypeErrorKind::IncompatibleTypes {
+                ref expected,
+                ref found,
+            } => write!(fmt, "expected '{}', found '{}'", expected, found),
+            TypeErrorKind::NotScalar => write!(fmt, "scalar type required"),
+            TypeErrorKind::NotInteger => write!(fmt, "integer type required"),
+            TypeErrorKind::InvalidCast => write!(fmt, "invalid cast"),
+            TypeErrorKind::NoGenericMatch(ref ty) => {
+                write!(fmt, "no generic association matches '{}'", ty)
+            }
+            TypeErrorKind::InvalidReturn => write!(fmt, "return type mismatch"),
+        }
+    }
+}
+
+impl error::Error for TypeError {
+    fn description(&self) -> &str {
+        "type error"
+    }
+}
+
+/// Result of type checking
+#[derive(Debug, PartialEq, Clone)]
+pub struct Typing {
+    /// Types of expressions, ordered by span
+    pub expressions: Vec<(Span, ExpressionType)>,
+    /// Type errors in the order they were found
+    pub errors: Vec<TypeError>,
+}
+
+impl Typing {
+    /// Type of the expression that covers exactly the given source range
+    pub fn expression_type(&self, start: usize, end: usize) -> Option<&ExpressionType> {
+        self.expressions
+            .binary_search_by_key(&(start, end), |&(s, _)| (s.start, s.end))
+            .ok()
+            .map(|i| &self.expressions[i].1)
+    }
+}
+
+/// Type check a translation unit
+pub fn check(target: Target, unit: &TranslationUnit) -> Typing {
+    let mut checker = TypeChecker::new(target);
+    checker.check_translation_unit(unit);
+    checker.finish()
+}
+
+/// Expression type checker
+///
+/// Keeps track of declarations in the current scope while visiting the syntax tree.
+#[derive(Debug, Clone)]
+pub struct TypeChecker {
+    resolver: TypeResolver,
+    scopes: Vec<Scope>,
+    /// Members of structure and union definitions, by start of the specifier span
+    records: HashMap<usize, Vec<(Option<String>, QualType)>>,
+    types: HashMap<(usize, usize), ExpressionType>,
+    errors: Vec<TypeError>,
+    /// Return type of the function being checked
+    return_type: Option<QualType>,
+    /// Parameter list of the function definition being checked
+    parameters: Option<*const FunctionDeclarator>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    /// Objects, functions and enumeration constants, `None` if type is not known
+    names: HashMap<String, Option<ExpressionType>>,
+    /// Defined structure and union tags, by start of the specifier span
+    tags: HashMap<String, usize>,
+}
+
+impl TypeChecker {
+    pub fn new(target: Target) -> TypeChecker {
+        TypeChecker {
+            resolver: TypeResolver::new(target),
+            scopes: vec![Scope::default()],
+            records: HashMap::new(),
+            types: HashMap::new(),
+            errors: Vec::new(),
+            return_type: None,
+            parameters: None,
+        }
+    }
+
+    /// Resolver for declared types, holds typedef names visible in the current scope
+    pub fn resolver(&self) -> &TypeResolver {
+        &self.resolver
+    }
+
+    pub fn resolver_mut(&mut self) -> &mut TypeResolver {
+        &mut self.resolver
+    }
+
+    /// Declare an object or function in the current scope
+    pub fn add_name(&mut self, name: &str, ty: QualType) {
+        let is_lvalue = match ty.canonical().ty {
+            CType::Function(_) => false,
+            _ => true,
+        };
+        self.bind(name, Some(ExpressionType { ty, is_lvalue }));
+    }
+
+    pub fn check_translation_unit(&mut self, unit: &TranslationUnit) {
+        self.visit_translation_unit(unit);
+    }
+
+    /// Type of an expression, `None` if it could not be determined
+    ///
+    /// Errors found in the expression are added to the result.
+    pub fn check_expression(&mut self, n: &Node<Expression>) -> Option<ExpressionType> {
+        self.operand(n)
+    }
+
+    /// Collect the results
+    pub fn finish(self) -> Typing {
+        let mut expressions: Vec<_> = self
+            .types
+            .into_iter()
+            .map(|((start, end), ty)| (Span::span(start, end), ty))
+            .collect();
+        expressions.sort_by_key(|&(s, _)| (s.start, s.end));
+        Typing {
+            expressions,
+            errors: self.errors,
+        }
+    }
+
+    /// Type with typedefs and `typeof` replaced by the types they stand for
+    ///
+    /// Like `QualType::canonical`, only the outermost type is replaced.
+    pub fn canonical(&mut self, ty: &QualType) -> QualType {
+        match ty.ty {
+            CType::Typedef(ref t) => self.canonical(&t.ty).qualified(ty.qualifiers),
+            CType::TypeOf(ref t) => {
+                let e = &t.expression;
+                let known = self.types.get(&(e.span.start, e.span.end)).cloned();
+                let inner = match known.or_else(|| self.operand(e)) {
+                    Some(inner) => inner.ty,
+                    None => return ty.clone(),
+                };
+                let inner = match t.unqualified {
+                    true => self.canonical(&inner).unqualified(),
+                    false => self.canonical(&inner),
+                };
+                inner.qualified(ty.qualifiers)
+            }
+            _ => ty.clone(),
+        }
+    }
+
+    /// Type of the value of an expression, see `ExpressionType::value_type`
+    pub fn value_type(&mut self, e: &ExpressionType) -> QualType {
+        let canonical = self.canonical(&e.ty);
+        let plain = match e.ty.ty {
+            CType::TypeOf(_) => false,
+            _ => canonical.qualifiers.is_empty(),
+        };
+        match canonical.ty {
+            CType::Array(a) => QualType::new(CType::Pointer(a.element)),
+            CType::Function(_) => QualType::new(CType::Pointer(Box::new(canonical))),
+            _ if plain => e.ty.unqualified(),
+            _ => canonical.unqualified(),
+        }
+    }
+
+    /// Test if two types are compatible (C11 6.2.7)
+    pub fn is_compatible(&mut self, a: &QualType, b: &QualType) -> bool {
+        let a = self.canonical(a);
+        let b = self.canonical(b);
+        if a.qualifiers != b.qualifiers {
+            return false;
+        }
+        match (&a.ty, &b.ty) {
+            (&CType::Void, &CType::Void) | (&CType::Nullptr, &CType::Nullptr) => true,
+            (&CType::Integer(x), &CType::Integer(y)) => x == y,
+            // Enumerated types are compatible with their underlying type, which is `int` or
+            // `unsigned int` depending on the values of the constants
+            (&CType::Enum(_), &CType::Integer(i)) | (&CType::Integer(i), &CType::Enum(_)) => {
+                i == IntegerType::Int || i == IntegerType::UnsignedInt
+            }
+            (CType::Enum(x), CType::Enum(y)) => same_tag(x, y),
+            (&CType::BitInt(x), &CType::BitInt(y)) => x == y,
+            (&CType::Float(x), &CType::Float(y)) | (&CType::Complex(x), &CType::Complex(y)) => {
+                x == y
+            }
+            (&CType::Pointer(ref x), &CType::Pointer(ref y))
+            | (&CType::BlockPointer(ref x), &CType::BlockPointer(ref y)) => {
+                self.is_compatible(x, y)
+            }
+            (CType::Array(x), CType::Array(y)) => {
+                let length = match (&x.length, &y.length) {
+                    (&ArrayLength::Fixed(n), &ArrayLength::Fixed(m)) => n == m,
+                    _ => true,
+                };
+                length && self.is_compatible(&x.element, &y.element)
+            }
+            (CType::Function(x), CType::Function(y)) => {
+                if !self.is_compatible(&x.return_type, &y.return_type) {
+                    return false;
+                }
+                if !x.prototype || !y.prototype {
+                    return true;
+                }
+                if x.variadic != y.variadic || x.parameters.len() != y.parameters.len() {
+                    return false;
+                }
+                x.parameters.iter().zip(&y.parameters).all(|(p, q)| {
+                    let p = p.ty.adjusted().unqualified();
+                    let q = q.ty.adjusted().unqualified();
+                    self.is_compatible(&p, &q)
+                })
+            }
+            (&CType::Struct(k, ref x), &CType::Struct(l, ref y)) => k == l && same_tag(x, y),
+            _ => false,
+        }
+    }
+
+    fn error(&mut self, kind: TypeErrorKind, span: Span) {
+        self.errors.push(TypeError { kind, span });
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(Scope::default());
+        self.resolver.enter_scope();
+    }
+
+    fn leave_scope(&mut self) {
+        self.scopes.pop().expect("more scope pops than pushes");
+        self.resolver.leave_scope();
+    }
+
+    fn bind(&mut self, name: &str, ty: Option<ExpressionType>) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .names
+            .insert(name.to_owned(), ty);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Option<ExpressionType>> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|s| s.names.get(name))
+            .next()
+    }
+
+    /// Members of a structure or union type, `None` if the type is incomplete
+    fn members(&self, tag: &Tag) -> Option<Vec<(Option<String>, QualType)>> {
+        let key = match tag.name {
+            Some(ref name) => *otry!(self
+                .scopes
+                .iter()
+                .rev()
+                .filter_map(|s| s.tags.get(name))
+                .next()),
+            None => tag.span.start,
+        };
+        self.records.get(&key).cloned()
+    }
+
+    /// Find a member by name, looking into anonymous structure and union members
+    fn member(&mut self, tag: &Tag, name: &str) -> Option<Option<QualType>> {
+        let members = otry!(self.members(tag));
+        for (member, ty) in members {
+            match member {
+                Some(ref member) if member == name => return Some(Some(ty)),
+                Some(_) => {}
+                None => {
+                    let canonical = self.canonical(&ty);
+                    if let CType::Struct(_, ref inner) = canonical.ty {
+                        if let Some(Some(ty)) = self.member(inner, name) {
+                            return Some(Some(ty.qualified(canonical.qualifiers)));
+                        }
+                    }
+                }
+            }
+        }
+        Some(None)
+    }
+
+    fn type_name(&mut self, n: &Node<TypeName>) -> Option<QualType> {
+        self.visit_type_name(&n.node, &n.span);
+        match self.resolver.type_name(n) {
+            Ok(ty) => Some(ty),
+            Err(e) => {
+                self.error(TypeErrorKind::Evaluation(e), n.span);
+                None
+            }
+        }
+    }
+
+    fn declaration(&mut self, n: &Node<Declaration>) {
+        for specifier in &n.node.specifiers {
+            self.visit_declaration_specifier(&specifier.node, &specifier.span);
+        }
+        if let Err(e) = self.resolver.add_declaration(n) {
+            self.error(TypeErrorKind::Evaluation(e), n.span);
+            return;
+        }
+        let typedef = n.node.specifiers.iter().any(|s| match s.node {
+            DeclarationSpecifier::StorageClass(ref s) => s.node == StorageClassSpecifier::Typedef,
+            _ => false,
+        });
+
+        for declarator in &n.node.declarators {
+            let d = &declarator.node.declarator;
+            self.visit_declarator(&d.node, &d.span);
+            if typedef {
+                continue;
+            }
+            let name = match declarator_identifier(&d.node) {
+                Some(name) => &name.node.name,
+                None => continue,
+            };
+            let ty = match self.resolver.declarator_type(&n.node.specifiers, d) {
+                Ok(ty) => ty,
+                Err(e) => {
+                    self.error(TypeErrorKind::Evaluation(e), d.span);
+                    self.bind(name, None);
+                    continue;
+                }
+            };
+            let ty = match declarator.node.initializer {
+                Some(ref i) => self.complete_array(ty, i),
+                None => ty,
+            };
+            self.add_name(name, ty.clone());
+            if let Some(ref i) = declarator.node.initializer {
+                self.initializer(&ty, i);
+            }
+        }
+    }
+
+    fn function_definition(&mut self, n: &Node<FunctionDefinition>) {
+        for specifier in &n.node.specifiers {
+            self.visit_declaration_specifier(&specifier.node, &specifier.span);
+        }
+        let ty = match self.resolver.function_definition_type(n) {
+            Ok(ty) => Some(ty),
+            Err(e) => {
+                self.error(TypeErrorKind::Evaluation(e), n.node.declarator.span);
+                None
+            }
+        };
+        if let Some(name) = declarator_identifier(&n.node.declarator.node) {
+            match ty {
+                Some(ref ty) => self.add_name(&name.node.name, ty.clone()),
+                None => self.bind(&name.node.name, None),
+            }
+        }
+
+        self.enter_scope();
+        let d = &n.node.declarator;
+        let outer_parameters = self.parameters;
+        self.parameters = match types::function_declarator(&d.node).map(|d| &d.node) {
+            Some(DerivedDeclarator::Function(f)) => Some(&f.node as *const _),
+            _ => None,
+        };
+        self.visit_declarator(&d.node, &d.span);
+        self.parameters = outer_parameters;
+        for declaration in &n.node.declarations {
+            for specifier in &declaration.node.specifiers {
+                self.visit_declaration_specifier(&specifier.node, &specifier.span);
+            }
+        }
+
+        let function = ty.map(|ty| ty.canonical().ty);
+        let outer_return = self.return_type.take();
+        if let Some(CType::Function(FunctionType {
+            return_type,
+            parameters,
+            ..
+        })) = function
+        {
+            for p in parameters {
+                if let Some(name) = p.name {
+                    self.add_name(&name.node.name, p.ty.adjusted());
+                }
+            }
+            self.return_type = Some(*return_type);
+        }
+
+        match n.node.statement.node {
+            Statement::Compound(ref items) => {
+                for item in items {
+                    self.visit_block_item(&item.node, &item.span);
+                }
+            }
+            ref s => self.visit_statement(s, &n.node.statement.span),
+        }
+        self.return_type = outer_return;
+        self.leave_scope();
+    }
+
+    /// Length of an array of unknown size from its initializer (C11 6.7.9 §22)
+    fn complete_array(&mut self, ty: QualType, n: &Node<Initializer>) -> QualType {
+        let mut array = match ty.canonical().ty {
+            CType::Array(ref a) if a.length == ArrayLength::Unknown => a.clone(),
+            _ => return ty,
+        };
+        let length = match n.node {
+            Initializer::Expression(ref e) => match e.node {
+                Expression::StringLiteral(_) => match self.operand(e).map(|t| t.ty.ty) {
+                    Some(CType::Array(a)) => a.length,
+                    _ => return ty,
+                },
+                _ => return ty,
+            },
+            Initializer::List(ref items) if items.iter().all(|i| i.node.designation.is_empty()) => {
+                ArrayLength::Fixed(items.len() as u64)
+            }
+            Initializer::List(_) => return ty,
+        };
+        array.length = length;
+        QualType {
+            ty: CType::Array(array),
+            qualifiers: ty.qualifiers,
+        }
+    }
+
+    fn initializer(&mut self, ty: &QualType, n: &Node<Initializer>) {
+        match n.node {
+            Initializer::Expression(ref e) => {
+                let found = match self.operand(e) {
+                    Some(found) => found,
+                    None => return,
+                };
+                let expected = self.canonical(ty);
+                match (&expected.ty, &e.node) {
+                    (&CType::Array(_), &Expression::StringLiteral(_)) => {}
+                    _ => self.check_assignment(&expected, e, &found),
+                }
+            }
+            Initializer::List(ref items) => {
+                for item in items {
+                    self.visit_initializer_list_item(&item.node, &item.span);
+                }
+            }
+        }
+    }
+
+    /// Check that a value can be assigned to an object of type `expected` (C11 6.5.16.1)
+    fn check_assignment(&mut self, expected: &QualType, n: &Node<Expression>, e: &ExpressionType) {
+        let to = self.canonical(expected).unqualified();
+        let from = self.value_type(e);
+        let from = self.canonical(&from);
+        if !self.is_assignable(&to, &from, n) {
+            let kind = TypeErrorKind::IncompatibleTypes {
+                expected: expected.unqualified(),
+                found: self.value_type(e),
+            };
+            self.error(kind, n.span);
+        }
+    }
+
+    fn is_assignable(&mut self, to: &QualType, from: &QualType, n: &Node<Expression>) -> bool {
+        if is_arithmetic(&to.ty) && is_arithmetic(&from.ty) {
+            return true;
+        }
+        match (&to.ty, &from.ty) {
+            (&CType::Struct(..), &CType::Struct(..)) => self.is_compatible(to, from),
+            (&CType::Pointer(ref p), &CType::Pointer(ref q))
+            | (&CType::BlockPointer(ref p), &CType::BlockPointer(ref q)) => {
+                let p = self.canonical(p);
+                let q = self.canonical(q);
+                let qualifiers = p.qualifiers.union(q.qualifiers) == p.qualifiers;
+                let void = match (&p.ty, &q.ty) {
+                    (&CType::Void, &CType::Function(_)) | (&CType::Function(_), &CType::Void) => {
+                        false
+                    }
+                    (&CType::Void, _) | (_, &CType::Void) => true,
+                    _ => false,
+                };
+                qualifiers && (void || self.is_compatible(&p.unqualified(), &q.unqualified()))
+            }
+            (&CType::Pointer(_), _) | (&CType::BlockPointer(_), _) | (&CType::Nullptr, _) => {
+                self.is_null_pointer(from, n)
+            }
+            (&CType::Integer(IntegerType::Bool), &CType::Pointer(_)) => true,
+            (&CType::Integer(IntegerType::Bool), &CType::Nullptr) => true,
+            _ => false,
+        }
+    }
+
+    /// Test if an expression is a null pointer constant (C11 6.3.2.3, C23 6.3.2.4)
+    fn is_null_pointer(&mut self, ty: &QualType, n: &Node<Expression>) -> bool {
+        match ty.ty {
+            CType::Nullptr => true,
+            ref t if is_integer(t) => match self.resolver.evaluator().evaluate(n) {
+                Ok(v) => v.value == 0,
+                Err(_) => false,
+            },
+            CType::Pointer(ref to) if to.ty == CType::Void && to.qualifiers.is_empty() => {
+                match n.node {
+                    Expression::Cast(ref c) => match self.types.get(&key(&c.node.expression)) {
+                        Some(inner) => {
+                            let inner = inner.clone();
+                            let inner = self.value_type(&inner);
+                            self.is_null_pointer(&inner, &c.node.expression)
+                        }
+                        None => false,
+                    },
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn is_modifiable(&mut self, e: &ExpressionType) -> bool {
+        let ty = self.canonical(&e.ty);
+        e.is_lvalue
+            && !ty.qualifiers.is_const
+            && match ty.ty {
+                CType::Array(_) | CType::Function(_) | CType::Void => false,
+                _ => true,
+            }
+    }
+
+    fn condition(&mut self, n: &Node<Expression>) {
+        if let Some(e) = self.operand(n) {
+            let ty = self.value_type(&e);
+            if !is_scalar(&self.canonical(&ty).ty) {
+                self.error(TypeErrorKind::NotScalar, n.span);
+            }
+        }
+    }
+
+    fn operand(&mut self, n: &Node<Expression>) -> Option<ExpressionType> {
+        self.expression(&n.node, n.span)
+    }
+
+    /// Canonical type of the value of an operand
+    fn operand_value(&mut self, n: &Node<Expression>) -> Option<QualType> {
+        let e = otry!(self.operand(n));
+        let ty = self.value_type(&e);
+        Some(self.canonical(&ty))
+    }
+
+    fn expression(&mut self, e: &Expression, span: Span) -> Option<ExpressionType> {
+        let ty = self.expression_type(e, span);
+        if let Some(ref ty) = ty {
+            self.types.insert((span.start, span.end), ty.clone());
+        }
+        ty
+    }
+
+    fn expression_type(&mut self, e: &Expression, span: Span) -> Option<ExpressionType> {
+        match *e {
+            Expression::Identifier(ref i) => match self.lookup(&i.node.name) {
+                Some(ty) => ty.clone(),
+                None => self.undeclared(&i.node.name, i.span),
+            },
+            Expression::Constant(ref c) => self.constant(&c.node, c.span),
+            Expression::StringLiteral(ref s) => self.string_literal(s),
+            Expression::GenericSelection(ref g) => self.generic_selection(&g.node, g.span),
+            Expression::Member(ref m) => self.member_expression(&m.node, m.span),
+            Expression::Call(ref c) => self.call_expression(&c.node),
+            Expression::CompoundLiteral(ref c) => {
+                let ty = otry!(self.type_name(&c.node.type_name));
+                let list = Node::new(Initializer::List(c.node.initializer_list.clone()), c.span);
+                let ty = self.complete_array(ty, &list);
+                self.initializer(&ty, &list);
+                Some(ExpressionType::lvalue(ty))
+            }
+            Expression::SizeOfTy(ref s) => {
+                self.type_name(&s.node.0);
+                Some(self.size_type())
+            }
+            Expression::SizeOfVal(ref s) => {
+                self.operand(&s.node.0);
+                Some(self.size_type())
+            }
+            Expression::AlignOf(ref a) => {
+                self.type_name(&a.node.0);
+                Some(self.size_type())
+            }
+            Expression::UnaryOperator(ref u) => self.unary_operator(&u.node, span),
+            Expression::Cast(ref c) => {
+                let to = self.type_name(&c.node.type_name);
+                let from = self.operand_value(&c.node.expression);
+                let to = otry!(to);
+                let from = otry!(from);
+                let canonical = self.canonical(&to);
+                if !self.is_valid_cast(&canonical.ty, &from.ty) {
+                    self.error(TypeErrorKind::InvalidCast, span);
+                }
+                Some(ExpressionType::rvalue(to.unqualified()))
+            }
+            Expression::BinaryOperator(ref b) => self.binary_operator(&b.node, span),
+            Expression::Conditional(ref c) => self.conditional_expression(&c.node, span),
+            Expression::Comma(ref es) => {
+                let mut ty = None;
+                for e in es.iter() {
+                    ty = self.operand(e);
+                }
+                let ty = otry!(ty);
+                Some(ExpressionType::rvalue(self.value_type(&ty)))
+            }
+            Expression::OffsetOf(ref o) => {
+                self.type_name(&o.node.type_name);
+                self.visit_offset_designator(&o.node.designator.node, &o.node.designator.span);
+                Some(self.size_type())
+            }
+            Expression::VaArg(ref v) => {
+                self.operand(&v.node.va_list);
+                let ty = otry!(self.type_name(&v.node.type_name));
+                Some(ExpressionType::rvalue(ty))
+            }
+            Expression::Statement(ref s) => {
+                self.visit_statement(&s.node, &s.span);
+                let last = match s.node {
+                    Statement::Compound(ref items) => items.last(),
+                    _ => None,
+                };
+                match last.map(|i| &i.node) {
+                    Some(&BlockItem::Statement(Node {
+                        node: Statement::Expression(Some(ref e)),
+                        ..
+                    })) => {
+                        let ty = otry!(self.types.get(&key(e)).cloned());
+                        Some(ExpressionType::rvalue(self.value_type(&ty)))
+                    }
+                    _ => Some(ExpressionType::rvalue(QualType::new(CType::Void))),
+                }
+            }
+            Expression::LabelAddress(_) => Some(ExpressionType::rvalue(QualType::new(
+                CType::Pointer(Box::new(QualType::new(CType::Void))),
+            ))),
+            Expression::TypesCompatible(ref t) => {
+                self.type_name(&t.node.type_name_1);
+                self.type_name(&t.node.type_name_2);
+                Some(int())
+            }
+            Expression::ChooseExpr(ref c) => {
+                self.operand(&c.node.condition);
+                let then_type = self.operand(&c.node.then_expression);
+                let else_type = self.operand(&c.node.else_expression);
+                match self.resolver.evaluator().evaluate(&c.node.condition) {
+                    Ok(v) if v.is_true() => then_type,
+                    Ok(_) => else_type,
+                    Err(e) => {
+                        self.error(TypeErrorKind::Evaluation(e), c.node.condition.span);
+                        None
+                    }
+                }
+            }
+            Expression::ConvertVector(ref c) => {
+                self.operand(&c.node.vector);
+                self.type_name(&c.node.type_name);
+                None
+            }
+            Expression::ShuffleVector(ref s) => {
+                self.visit_shuffle_vector_expression(&s.node, &s.span);
+                None
+            }
+        }
+    }
+
+    fn undeclared(&mut self, name: &str, span: Span) -> Option<ExpressionType> {
+        match name {
+            "__func__" | "__FUNCTION__" | "__PRETTY_FUNCTION__" => {
+                let char_type = CType::Integer(IntegerType::Char);
+                let element = QualType::new(char_type).qualified(types::Qualifiers {
+                    is_const: true,
+                    ..Default::default()
+                });
+                Some(ExpressionType::lvalue(QualType::new(CType::Array(
+                    types::ArrayType {
+                        element: Box::new(element),
+                        length: ArrayLength::Unknown,
+                        qualifiers: Default::default(),
+                        is_static: false,
+                    },
+                ))))
+            }
+            // Compiler builtins have no declarations
+            _ if name.starts_with("__builtin_") => None,
+            _ => {
+                self.error(TypeErrorKind::Undeclared(name.to_owned()), span);
+                None
+            }
+        }
+    }
+
+    fn size_type(&self) -> ExpressionType {
+        let ty = self.resolver.evaluator().target().size_type();
+        ExpressionType::rvalue(QualType::new(CType::Integer(ty)))
+    }
+
+    /// Type of a constant (C11 6.4.4)
+    fn constant(&mut self, n: &Constant, span: Span) -> Option<ExpressionType> {
+        let ty = match *n {
+            Constant::Integer(ref i) if i.suffix.imaginary => return None,
+            Constant::Integer(ref i) => {
+                let target = *self.resolver.evaluator().target();
+                match IntegerValue::decode(&target, i, span) {
+                    Ok(v) => v.ty,
+                    Err(e) => {
+                        self.error(TypeErrorKind::InvalidLiteral(e.kind), e.span);
+                        return None;
+                    }
+                }
+            }
+            Constant::Character(ref c) if self.character_error(c, span) => return None,
+            Constant::Character(_) => {
+                let constant = Node::new(
+                    Expression::Constant(Box::new(Node::new(n.clone(), span))),
+                    span,
+                );
+                match self.resolver.evaluator().evaluate(&constant) {
+                    Ok(v) => CType::Integer(v.ty),
+                    Err(e) => {
+                        self.error(TypeErrorKind::Evaluation(e), span);
+                        return None;
+                    }
+                }
+            }
+            Constant::Float(ref f) => {
+                let ty = match f.suffix.format {
+                    FloatFormat::Float => FloatType::Float,
+                    FloatFormat::Double => FloatType::Double,
+                    FloatFormat::LongDouble => FloatType::LongDouble,
+                    FloatFormat::TS18661Format(t) => FloatType::TS18661(t),
+                };
+                match f.suffix.imaginary {
+                    true => CType::Complex(ty),
+                    false => CType::Float(ty),
+                }
+            }
+            Constant::Bool(_) => CType::Integer(IntegerType::Bool),
+            Constant::Nullptr => CType::Nullptr,
+        };
+        Some(ExpressionType::rvalue(QualType::new(ty)))
+    }
+
+    /// Report malformed character constants at the offending escape sequence
+    fn character_error(&mut self, c: &str, span: Span) -> bool {
+        let target = *self.resolver.evaluator().target();
+        match CharacterValue::decode(&target, c, span) {
+            Ok(_) => false,
+            Err(e) => {
+                self.error(TypeErrorKind::InvalidLiteral(e.kind), e.span);
+                true
+            }
+        }
+    }
+
+    /// Type of a string literal, an array of characters (C11 6.4.5)
+    fn string_literal(&mut self, n: &Node<StringLiteral>) -> Option<ExpressionType> {
+        let target = *self.resolver.evaluator().target();
+        let value = match StringValue::decode(&target, n, None) {
+            Ok(value) => value,
+            Err(e) => {
+                self.error(TypeErrorKind::InvalidLiteral(e.kind), e.span);
+                return None;
+            }
+        };
+        let unit = value.encoding.unit_type(&target);
+        Some(ExpressionType::lvalue(QualType::new(CType::Array(
+            types::ArrayType {
+                element: Box::new(QualType::new(CType::Integer(unit))),
+                length: ArrayLength::Fixed(value.units.len() as u64 + 1),
+                qualifiers: Default::default(),
+                is_static: false,
+            },
+        ))))
+    }
+
+    /// Type of the selected association (C11 6.5.1.1)
+    fn generic_selection(&mut self, n: &GenericSelection, span: Span) -> Option<ExpressionType> {
+        let control = self.operand_value(&n.expression);
+        let mut selected = None;
+        let mut default = None;
+        for a in &n.associations {
+            match a.node {
+                GenericAssociation::Type(ref t) => {
+                    let ty = self.type_name(&t.node.type_name);
+                    let e = self.operand(&t.node.expression);
+                    if let (Some(ty), Some(control)) = (ty, control.as_ref()) {
+                        if self.is_compatible(&ty, control) {
+                            selected = Some(e);
+                        }
+                    }
+                }
+                GenericAssociation::Default(ref e) => default = Some(self.operand(e)),
+            }
+        }
+        let control = otry!(control);
+        match selected.or(default) {
+            Some(ty) => ty,
+            None => {
+                self.error(TypeErrorKind::NoGenericMatch(control), span);
+                None
+            }
+        }
+    }
+
+    /// Type of a structure or union member (C11 6.5.2.3)
+    fn member_expression(&mut self, n: &MemberExpression, span: Span) -> Option<ExpressionType> {
+        let object = otry!(self.operand(&n.expression));
+        let (record, is_lvalue) = match n.operator.node {
+            MemberOperator::Direct => (self.canonical(&object.ty), object.is_lvalue),
+            MemberOperator::Indirect => match self.value_type(&object).ty {
+                CType::Pointer(to) => (self.canonical(&to), true),
+                _ => {
+                    self.error(TypeErrorKind::NotPointer, n.expression.span);
+                    return None;
+                }
+            },
+        };
+        let tag = match record.ty {
+            CType::Struct(_, ref tag) => tag,
+            _ => {
+                self.error(TypeErrorKind::NotStructure, n.expression.span);
+                return None;
+            }
+        };
+        let name = &n.identifier.node.name;
+        match self.member(tag, name) {
+            Some(Some(ty)) => Some(ExpressionType {
+                ty: ty.qualified(record.qualifiers),
+                is_lvalue,
+            }),
+            Some(None) => {
+                let kind = TypeErrorKind::UnknownMember(name.clone());
+                self.error(kind, n.identifier.span);
+                None
+            }
+            None => {
+                self.error(TypeErrorKind::IncompleteType, span);
+                None
+            }
+        }
+    }
+
+    /// Type of a function call (C11 6.5.2.2)
+    fn call_expression(&mut self, n: &CallExpression) -> Option<ExpressionType> {
+        let callee = self.operand_value(&n.callee);
+        let arguments: Vec<_> = n.arguments.iter().map(|a| self.operand(a)).collect();
+        let callee = otry!(callee);
+
+        let function = match callee.ty {
+            CType::Pointer(ref to) | CType::BlockPointer(ref to) => self.canonical(to),
+            _ => callee.clone(),
+        };
+        let function = match function.ty {
+            CType::Function(f) => f,
+            _ => {
+                self.error(TypeErrorKind::NotFunction, n.callee.span);
+                return None;
+            }
+        };
+
+        if function.prototype {
+            let expected = function.parameters.len();
+            let found = n.arguments.len();
+            if found < expected || (found > expected && !function.variadic) {
+                let kind = TypeErrorKind::ArgumentCount { expected, found };
+                self.error(kind, n.callee.span);
+            }
+            for ((p, a), t) in function.parameters.iter().zip(&n.arguments).zip(&arguments) {
+                if let Some(ref t) = *t {
+                    self.check_assignment(&p.ty.adjusted(), a, t);
+                }
+            }
+        }
+        Some(ExpressionType::rvalue(function.return_type.unqualified()))
+    }
+
+    fn unary_operator(
+        &mut self,
+        n: &UnaryOperatorExpression,
+        span: Span,
+    ) -> Option<ExpressionType> {
+        let operand = otry!(self.operand(&n.operand));
+        let value = self.value_type(&operand);
+        let canonical = self.canonical(&value);
+        let invalid = |checker: &mut TypeChecker| {
+            checker.error(TypeErrorKind::InvalidOperands, span);
+            None
+        };
+        match n.operator.node {
+            UnaryOperator::PostIncrement
+            | UnaryOperator::PostDecrement
+            | UnaryOperator::PreIncrement
+            | UnaryOperator::PreDecrement => {
+                if !self.is_modifiable(&operand) {
+                    self.error(TypeErrorKind::NotModifiable, n.operand.span);
+                    return None;
+                }
+                match canonical.ty {
+                    CType::Pointer(_) => {}
+                    ref t if is_real(t) => {}
+                    _ => return invalid(self),
+                }
+                Some(ExpressionType::rvalue(value))
+            }
+            UnaryOperator::Address => {
+                let function = match self.canonical(&operand.ty).ty {
+                    CType::Function(_) => true,
+                    _ => false,
+                };
+                if !operand.is_lvalue && !function {
+                    self.error(TypeErrorKind::NotLvalue, n.operand.span);
+                    return None;
+                }
+                Some(ExpressionType::rvalue(QualType::new(CType::Pointer(
+                    Box::new(operand.ty),
+                ))))
+            }
+            UnaryOperator::Indirection => match canonical.ty {
+                CType::Pointer(to) => {
+                    let is_lvalue = match self.canonical(&to).ty {
+                        CType::Function(_) | CType::Void => false,
+                        _ => true,
+                    };
+                    Some(ExpressionType { ty: *to, is_lvalue })
+                }
+                _ => {
+                    self.error(TypeErrorKind::NotPointer, n.operand.span);
+                    None
+                }
+            },
+            UnaryOperator::Plus | UnaryOperator::Minus => match canonical.ty {
+                ref t if is_arithmetic(t) => Some(ExpressionType::rvalue(self.promote(&canonical))),
+                _ => invalid(self),
+            },
+            UnaryOperator::Complement => match canonical.ty {
+                // Complex conjugate is a GNU extension
+                CType::Complex(_) => Some(ExpressionType::rvalue(canonical)),
+                ref t if is_integer(t) => Some(ExpressionType::rvalue(self.promote(&canonical))),
+                _ => invalid(self),
+            },
+            UnaryOperator::Negate => match canonical.ty {
+                ref t if is_scalar(t) => Some(int()),
+                _ => invalid(self),
+            },
+            UnaryOperator::Real | UnaryOperator::Imag => {
+                let ty = match canonical.ty {
+                    CType::Complex(f) => QualType::new(CType::Float(f)),
+                    ref t if is_arithmetic(t) => canonical.clone(),
+                    _ => return invalid(self),
+                };
+                Some(ExpressionType {
+                    ty: ty.qualified(self.canonical(&operand.ty).qualifiers),
+                    is_lvalue: operand.is_lvalue,
+                })
+            }
+        }
+    }
+
+    fn binary_operator(
+        &mut self,
+        n: &BinaryOperatorExpression,
+        span: Span,
+    ) -> Option<ExpressionType> {
+        use ast::BinaryOperator::*;
+
+        let lhs = self.operand(&n.lhs);
+        let rhs = self.operand(&n.rhs);
+        let (lhs, rhs) = match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return None,
+        };
+        let op = n.operator.node;
+        let plain = match op {
+            AssignMultiply => Multiply,
+            AssignDivide => Divide,
+            AssignModulo => Modulo,
+            AssignPlus => Plus,
+            AssignMinus => Minus,
+            AssignShiftLeft => ShiftLeft,
+            AssignShiftRight => ShiftRight,
+            AssignBitwiseAnd => BitwiseAnd,
+            AssignBitwiseXor => BitwiseXor,
+            AssignBitwiseOr => BitwiseOr,
+            _ => op,
+        };
+
+        if plain == Index {
+            let l = self.value_type(&lhs);
+            let l = self.canonical(&l);
+            let r = self.value_type(&rhs);
+            let r = self.canonical(&r);
+            let element = match (l.ty, r.ty) {
+                (CType::Pointer(to), ref i) | (ref i, CType::Pointer(to)) if is_integer(i) => to,
+                _ => {
+                    self.error(TypeErrorKind::InvalidOperands, span);
+                    return None;
+                }
+            };
+            return Some(ExpressionType::lvalue(*element));
+        }
+
+        if plain != op || op == Assign {
+            if !self.is_modifiable(&lhs) {
+                self.error(TypeErrorKind::NotModifiable, n.lhs.span);
+                return None;
+            }
+            let result = self.value_type(&lhs);
+            if op == Assign {
+                self.check_assignment(&lhs.ty, &n.rhs, &rhs);
+            } else {
+                let l = self.canonical(&result);
+                otry!(self.arithmetic(plain, &l, &n.lhs, &rhs, &n.rhs, span));
+            }
+            return Some(ExpressionType::rvalue(result));
+        }
+
+        let l = self.value_type(&lhs);
+        let l = self.canonical(&l);
+        let ty = otry!(self.arithmetic(op, &l, &n.lhs, &rhs, &n.rhs, span));
+        Some(ExpressionType::rvalue(ty))
+    }
+
+    /// Result type of an arithmetic, relational or logical operator (C11 6.5.5 -- 6.5.14)
+    fn arithmetic(
+        &mut self,
+        op: BinaryOperator,
+        l: &QualType,
+        lhs: &Node<Expression>,
+        rhs: &ExpressionType,
+        rhs_node: &Node<Expression>,
+        span: Span,
+    ) -> Option<QualType> {
+        use ast::BinaryOperator::*;
+
+        let r = self.value_type(rhs);
+        let r = self.canonical(&r);
+        let int = QualType::new(CType::Integer(IntegerType::Int));
+        let result = match op {
+            Multiply | Divide if is_arithmetic(&l.ty) && is_arithmetic(&r.ty) => {
+                Some(self.common_type(l, &r))
+            }
+            Modulo | BitwiseAnd | BitwiseXor | BitwiseOr
+                if is_integer(&l.ty) && is_integer(&r.ty) =>
+            {
+                Some(self.common_type(l, &r))
+            }
+            ShiftLeft | ShiftRight if is_integer(&l.ty) && is_integer(&r.ty) => {
+                Some(self.promote(l))
+            }
+            Plus | Minus if is_arithmetic(&l.ty) && is_arithmetic(&r.ty) => {
+                Some(self.common_type(l, &r))
+            }
+            Plus => match (&l.ty, &r.ty) {
+                (&CType::Pointer(_), t) if is_integer(t) => Some(l.clone()),
+                (t, &CType::Pointer(_)) if is_integer(t) => Some(r.clone()),
+                _ => None,
+            },
+            Minus => match (&l.ty, &r.ty) {
+                (&CType::Pointer(_), t) if is_integer(t) => Some(l.clone()),
+                (CType::Pointer(p), CType::Pointer(q)) => {
+                    let p = self.canonical(p).unqualified();
+                    let q = self.canonical(q).unqualified();
+                    if self.is_compatible(&p, &q) {
+                        let ptrdiff = self.resolver.evaluator().target().ptrdiff_type();
+                        Some(QualType::new(CType::Integer(ptrdiff)))
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            },
+            Less | Greater | LessOrEqual | GreaterOrEqual => {
+                if is_real(&l.ty) && is_real(&r.ty)
+                    || self.is_pointer_comparison(l, lhs, &r, rhs_node, false)
+                {
+                    Some(int)
+                } else {
+                    None
+                }
+            }
+            Equals | NotEquals => {
+                if is_arithmetic(&l.ty) && is_arithmetic(&r.ty)
+                    || self.is_pointer_comparison(l, lhs, &r, rhs_node, true)
+                {
+                    Some(int)
+                } else {
+                    None
+                }
+            }
+            LogicalAnd | LogicalOr if is_scalar(&l.ty) && is_scalar(&r.ty) => Some(int),
+            _ => None,
+        };
+        if result.is_none() {
+            self.error(TypeErrorKind::InvalidOperands, span);
+        }
+        result
+    }
+
+    /// Test if operands are valid for a pointer comparison (C11 6.5.8, 6.5.9)
+    fn is_pointer_comparison(
+        &mut self,
+        l: &QualType,
+        lhs: &Node<Expression>,
+        r: &QualType,
+        rhs: &Node<Expression>,
+        equality: bool,
+    ) -> bool {
+        match (&l.ty, &r.ty) {
+            (&CType::Pointer(ref p), &CType::Pointer(ref q))
+            | (&CType::BlockPointer(ref p), &CType::BlockPointer(ref q)) => {
+                let p = self.canonical(p).unqualified();
+                let q = self.canonical(q).unqualified();
+                let void = equality && (p.ty == CType::Void || q.ty == CType::Void);
+                void || self.is_compatible(&p, &q)
+            }
+            (&CType::Pointer(_), _) | (&CType::BlockPointer(_), _) | (&CType::Nullptr, _) => {
+                self.is_null_pointer(r, rhs)
+            }
+            (_, &CType::Pointer(_)) | (_, &CType::BlockPointer(_)) | (_, &CType::Nullptr) => {
+                self.is_null_pointer(l, lhs)
+            }
+            _ => false,
+        }
+    }
+
+    /// Result type of the conditional operator (C11 6.5.15)
+    fn conditional_expression(
+        &mut self,
+        n: &ConditionalExpression,
+        span: Span,
+    ) -> Option<ExpressionType> {
+        self.condition(&n.condition);
+        let then_node = match n.then_expression {
+            Some(ref e) => e,
+            None => &n.condition,
+        };
+        let then_type = match n.then_expression {
+            Some(ref e) => self.operand(e),
+            None => self.types.get(&key(&n.condition)).cloned(),
+        };
+        let else_type = self.operand(&n.else_expression);
+        let (then_type, else_type) = match (then_type, else_type) {
+            (Some(t), Some(e)) => (t, e),
+            _ => return None,
+        };
+        let t = self.value_type(&then_type);
+        let t = self.canonical(&t);
+        let e = self.value_type(&else_type);
+        let e = self.canonical(&e);
+
+        let ty = match (&t.ty, &e.ty) {
+            (a, b) if is_arithmetic(a) && is_arithmetic(b) => Some(self.common_type(&t, &e)),
+            (&CType::Void, &CType::Void) => Some(t.clone()),
+            (&CType::Struct(..), &CType::Struct(..)) if self.is_compatible(&t, &e) => {
+                Some(t.clone())
+            }
+            (&CType::Nullptr, &CType::Nullptr) => Some(t.clone()),
+            (CType::Pointer(p), CType::Pointer(q)) => {
+                let p = self.canonical(p);
+                let q = self.canonical(q);
+                let qualifiers = p.qualifiers.union(q.qualifiers);
+                if p.ty == CType::Void || q.ty == CType::Void {
+                    let void = QualType::new(CType::Void).qualified(qualifiers);
+                    Some(QualType::new(CType::Pointer(Box::new(void))))
+                } else if self.is_compatible(&p.unqualified(), &q.unqualified()) {
+                    let to = p.qualified(qualifiers);
+                    Some(QualType::new(CType::Pointer(Box::new(to))))
+                } else if self.is_null_pointer(&e, &n.else_expression) {
+                    Some(t.clone())
+                } else if self.is_null_pointer(&t, then_node) {
+                    Some(e.clone())
+                } else {
+                    None
+                }
+            }
+            (&CType::Pointer(_), _) | (&CType::BlockPointer(_), _) | (&CType::Nullptr, _)
+                if self.is_null_pointer(&e, &n.else_expression) =>
+            {
+                Some(t.clone())
+            }
+            (_, &CType::Pointer(_)) | (_, &CType::BlockPointer(_)) | (_, &CType::Nullptr)
+                if self.is_null_pointer(&t, then_node) =>
+            {
+                Some(e.clone())
+            }
+            (&CType::BlockPointer(_), &CType::BlockPointer(_)) if self.is_compatible(&t, &e) => {
+                Some(t.clone())
+            }
+            _ => None,
+        };
+        match ty {
+            Some(ty) => Some(ExpressionType::rvalue(ty)),
+            None => {
+                self.error(TypeErrorKind::InvalidOperands, span);
+                None
+            }
+        }
+    }
+
+    /// Test if a value of type `from` can be cast to `to` (C11 6.5.4)
+    fn is_valid_cast(&mut self, to: &CType, from: &CType) -> bool {
+        let pointer = |t: &CType| match *t {
+            CType::Pointer(_) | CType::BlockPointer(_) | CType::Nullptr => true,
+            _ => false,
+        };
+        let float = |t: &CType| match *t {
+            CType::Float(_) | CType::Complex(_) => true,
+            _ => false,
+        };
+        match *to {
+            CType::Void => true,
+            // Cast to a union type is a GNU extension
+            CType::Struct(StructKind::Union, _) => true,
+            CType::Nullptr => *from == CType::Nullptr,
+            ref t if is_scalar(t) => {
+                is_scalar(from) && !(pointer(to) && float(from)) && !(float(to) && pointer(from))
+            }
+            _ => false,
+        }
+    }
+
+    /// Apply integer promotions (C11 6.3.1.1)
+    fn promote(&self, ty: &QualType) -> QualType {
+        match ty.ty {
+            CType::Integer(i) if i.rank() < IntegerType::Int.rank() => {
+                QualType::new(CType::Integer(IntegerType::Int))
+            }
+            CType::Enum(_) => QualType::new(CType::Integer(IntegerType::Int)),
+            _ => ty.unqualified(),
+        }
+    }
+
+    /// Common real type of usual arithmetic conversions (C11 6.3.1.8)
+    fn common_type(&self, a: &QualType, b: &QualType) -> QualType {
+        let float = |t: &CType| match *t {
+            CType::Float(f) | CType::Complex(f) => Some(f),
+            _ => None,
+        };
+        let complex = |t: &CType| match *t {
+            CType::Complex(_) => true,
+            _ => false,
+        };
+        let real = match (float(&a.ty), float(&b.ty)) {
+            (Some(x), Some(y)) if float_rank(y) > float_rank(x) => Some(y),
+            (Some(x), _) | (_, Some(x)) => Some(x),
+            (None, None) => None,
+        };
+        if let Some(real) = real {
+            return QualType::new(match complex(&a.ty) || complex(&b.ty) {
+                true => CType::Complex(real),
+                false => CType::Float(real),
+            });
+        }
+
+        let a = self.promote(a).ty;
+        let b = self.promote(b).ty;
+        if a == b {
+            return QualType::new(a);
+        }
+        let (ra, sa) = self.integer_rank(&a);
+        let (rb, sb) = self.integer_rank(&b);
+        if sa == sb {
+            return QualType::new(if ra >= rb { a } else { b });
+        }
+        let ((signed, rs), (unsigned, ru)) = match sa {
+            true => ((a, ra), (b, rb)),
+            false => ((b, rb), (a, ra)),
+        };
+        if ru >= rs {
+            QualType::new(unsigned)
+        } else if rs.0 > ru.0 {
+            QualType::new(signed)
+        } else {
+            QualType::new(match signed {
+                CType::Integer(i) => CType::Integer(i.to_unsigned()),
+                CType::BitInt(b) => CType::BitInt(types::BitIntType {
+                    signed: false,
+                    width: b.width,
+                }),
+                t => t,
+            })
+        }
+    }
+
+    /// Width and rank of a promoted integer type, and whether it is signed
+    ///
+    /// Bit-precise integer types rank below standard types of the same width (C23 6.3.1.1).
+    fn integer_rank(&self, ty: &CType) -> ((u64, u8), bool) {
+        let target = self.resolver.evaluator().target();
+        match *ty {
+            CType::Integer(i) => (
+                (target.integer_size(i) * 8, i.rank() + 1),
+                target.is_signed(i),
+            ),
+            CType::BitInt(b) => ((b.width, 0), b.signed),
+            _ => ((0, 0), true),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for TypeChecker {
+    fn visit_expression(&mut self, expression: &'ast Expression, span: &'ast Span) {
+        self.expression(expression, *span);
+    }
+
+    fn visit_extension(&mut self, _extension: &'ast Extension, _span: &'ast Span) {}
+
+    fn visit_external_declaration(
+        &mut self,
+        external_declaration: &'ast ExternalDeclaration,
+        span: &'ast Span,
+    ) {
+        match *external_declaration {
+            ExternalDeclaration::Declaration(ref d) => self.declaration(d),
+            ExternalDeclaration::FunctionDefinition(ref f) => self.function_definition(f),
+            _ => visit::visit_external_declaration(self, external_declaration, span),
+        }
+    }
+
+    fn visit_block_item(&mut self, block_item: &'ast BlockItem, span: &'ast Span) {
+        match *block_item {
+            BlockItem::Declaration(ref d) => self.declaration(d),
+            BlockItem::FunctionDefinition(ref f) => self.function_definition(f),
+            _ => visit::visit_block_item(self, block_item, span),
+        }
+    }
+
+    fn visit_for_initializer(&mut self, for_initializer: &'ast ForInitializer, span: &'ast Span) {
+        match *for_initializer {
+            ForInitializer::Declaration(ref d) => self.declaration(d),
+            _ => visit::visit_for_initializer(self, for_initializer, span),
+        }
+    }
+
+    fn visit_struct_type(&mut self, struct_type: &'ast StructType, span: &'ast Span) {
+        visit::visit_struct_type(self, struct_type, span);
+        if let Some(ref declarations) = struct_type.declarations {
+            let mut members = Vec::new();
+            for d in declarations {
+                if let StructDeclaration::Field(ref f) = d.node {
+                    match self.resolver.field_types(f) {
+                        Ok(fields) => members.extend(
+                            fields
+                                .into_iter()
+                                .map(|(name, ty)| (name.map(|n| n.node.name.clone()), ty)),
+                        ),
+                        Err(e) => self.error(TypeErrorKind::Evaluation(e), f.span),
+                    }
+                }
+            }
+            self.records.insert(span.start, members);
+            if let Some(ref i) = struct_type.identifier {
+                let scope = self.scopes.last_mut().unwrap();
+                scope.tags.insert(i.node.name.clone(), span.start);
+            }
+        }
+    }
+
+    fn visit_enum_type(&mut self, enum_type: &'ast EnumType, span: &'ast Span) {
+        visit::visit_enum_type(self, enum_type, span);
+        if let Err(e) = self.resolver.evaluator_mut().add_enum(enum_type) {
+            self.error(TypeErrorKind::Evaluation(e), *span);
+        }
+        for enumerator in &enum_type.enumerators {
+            let name = &enumerator.node.identifier.node.name;
+            self.bind(name, Some(int()));
+        }
+    }
+
+    fn visit_function_declarator(
+        &mut self,
+        function_declarator: &'ast FunctionDeclarator,
+        span: &'ast Span,
+    ) {
+        if self.parameters == Some(function_declarator as *const _) {
+            visit::visit_function_declarator(self, function_declarator, span);
+        } else {
+            self.enter_scope();
+            visit::visit_function_declarator(self, function_declarator, span);
+            self.leave_scope();
+        }
+    }
+
+    fn visit_parameter_declaration(
+        &mut self,
+        parameter_declaration: &'ast ParameterDeclaration,
+        span: &'ast Span,
+    ) {
+        visit::visit_parameter_declaration(self, parameter_declaration, span);
+        if let Some(ref d) = parameter_declaration.declarator {
+            if let Some(name) = declarator_identifier(&d.node) {
+                let specifiers = &parameter_declaration.specifiers;
+                match self.resolver.declarator_type(specifiers, d) {
+                    Ok(ty) => self.add_name(&name.node.name, ty.adjusted()),
+                    Err(_) => self.bind(&name.node.name, None),
+                }
+            }
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &'ast Statement, span: &'ast Span) {
+        match *statement {
+            Statement::Compound(_) | Statement::For(_) => {
+                self.enter_scope();
+                visit::visit_statement(self, statement, span);
+                self.leave_scope();
+            }
+            Statement::Return(ref e) => self.return_statement(e.as_ref().map(|e| &**e), *span),
+            _ => visit::visit_statement(self, statement, span),
+        }
+    }
+
+    fn visit_if_statement(&mut self, if_statement: &'ast IfStatement, _span: &'ast Span) {
+        self.condition(&if_statement.condition);
+        let s = &if_statement.then_statement;
+        self.visit_statement(&s.node, &s.span);
+        if let Some(ref s) = if_statement.else_statement {
+            self.visit_statement(&s.node, &s.span);
+        }
+    }
+
+    fn visit_while_statement(&mut self, while_statement: &'ast WhileStatement, _span: &'ast Span) {
+        self.condition(&while_statement.expression);
+        let s = &while_statement.statement;
+        self.visit_statement(&s.node, &s.span);
+    }
+
+    fn visit_do_while_statement(
+        &mut self,
+        do_while_statement: &'ast DoWhileStatement,
+        _span: &'ast Span,
+    ) {
+        let s = &do_while_statement.statement;
+        self.visit_statement(&s.node, &s.span);
+        self.condition(&do_while_statement.expression);
+    }
+
+    fn visit_for_statement(&mut self, for_statement: &'ast ForStatement, _span: &'ast Span) {
+        let i = &for_statement.initializer;
+        self.visit_for_initializer(&i.node, &i.span);
+        if let Some(ref c) = for_statement.condition {
+            self.condition(c);
+        }
+        if let Some(ref s) = for_statement.step {
+            self.operand(s);
+        }
+        let s = &for_statement.statement;
+        self.visit_statement(&s.node, &s.span);
+    }
+
+    fn visit_switch_statement(
+        &mut self,
+        switch_statement: &'ast SwitchStatement,
+        _span: &'ast Span,
+    ) {
+        let e = &switch_statement.expression;
+        if let Some(ty) = self.operand_value(e) {
+            if !is_integer(&ty.ty) {
+                self.error(TypeErrorKind::NotInteger, e.span);
+            }
+        }
+        let s = &switch_statement.statement;
+        self.visit_statement(&s.node, &s.span);
+    }
+}
+
+impl TypeChecker {
+    /// Check the value of a `return` statement (C11 6.8.6.4)
+    fn return_statement(&mut self, e: Option<&Node<Expression>>, span: Span) {
+        let found = match e {
+            Some(e) => self.operand(e),
+            None => None,
+        };
+        let expected = match self.return_type.clone() {
+            Some(ty) => ty,
+            None => return,
+        };
+        let void = self.canonical(&expected).ty == CType::Void;
+        match (e, found) {
+            (Some(_), _) | (None, None) if void == e.is_none() => {}
+            (Some(e), Some(found)) => {
+                let value_void = self.value_type(&found).ty == CType::Void;
+                // Returning a void expression from a void function is a GNU extension
+                if !(void && value_void) {
+                    self.error(TypeErrorKind::InvalidReturn, e.span);
+                }
+            }
+            (Some(_), None) => {}
+            (None, _) => self.error(TypeErrorKind::InvalidReturn, span),
+        }
+        if let (false, Some(e)) = (void, e) {
+            if let Some(found) = self.types.get(&key(e)).cloned() {
+                self.check_assignment(&expected, e, &found);
+            }
+        }
+    }
+}
+
+fn int() -> ExpressionType {
+    ExpressionType::rvalue(QualType::new(CType::Integer(IntegerType::Int)))
+}
+
+fn key(n: &Node<Expression>) -> (usize, usize) {
+    (n.span.start, n.span.end)
+}
+
+fn same_tag(a: &Tag, b: &Tag) -> bool {
+    match (&a.name, &b.name) {
+        (Some(x), Some(y)) => x == y,
+        (&None, &None) => a.span == b.span,
+        _ => false,
+    }
+}
+
+fn is_integer(ty: &CType) -> bool {
+    match *ty {
+        CType::Integer(_) | CType::BitInt(_) | CType::Enum(_) => true,
+        _ => false,
+    }
+}
+
+fn is_real(ty: &CType) -> bool {
+    match *ty {
+        CType::Float(_) => true,
+        ref t => is_integer(t),
+    }
+}
+
+fn is_arithmetic(ty: &CType) -> bool {
+    match *ty {
+        CType::Complex(_) => true,
+        ref t => is_real(t),
+    }
+}
+
+fn is_scalar(ty: &CType) -> bool {
+    match *ty {
+        CType::Pointer(_) | CType::BlockPointer(_) | CType::Nullptr => true,
+        ref t => is_arithmetic(t),
+    }
+}
+
+/// Order of real floating types by range, for usual arithmetic conversions
+fn float_rank(ty: FloatType) -> u64 {
+    match ty {
+        FloatType::Float => 32,
+        FloatType::Double => 64,
+        FloatType::LongDouble => 80,
+        FloatType::TS18661(t) => match t.format {
+            TS18661FloatFormat::BinaryExtended | TS18661FloatFormat::DecimalExtended => {
+                t.width as u64 + 1
+            }
+            _ => t.width as u64,
+        },
+    }
+}
+
+fn declarator_identifier(n: &Declarator) -> Option<&Node<Identifier>> {
+    match n.kind.node {
+        DeclaratorKind::Abstract => None,
+        DeclaratorKind::Identifier(ref i) => Some(i),
+        DeclaratorKind::Declarator(ref d) => declarator_identifier(&d.node),
+    }
+}
+
+#[cfg(test)]
+fn checked(source: &str) -> Typing {
+    use driver::{parse_preprocessed, Config};
+    use layout::DataModel;
+
+    let parse = parse_preprocessed(&Config::with_gcc(), source.into()).unwrap();
+    check(Target::new(DataModel::LP64), &parse.unit)
+}
+
+/// Type of the last occurrence of `expression` in the function body
+#[cfg(test)]
+fn type_of(source: &str, expression: &str) -> Option<String> {
+    let source = format!("{} void test(void) {{ {}; }}", source, expression);
+    let typing = checked(&source);
+    assert_eq!(typing.errors, vec![]);
+    let start = source.rfind(expression).unwrap();
+    typing
+        .expression_type(start, start + expression.len())
+        .map(|e| e.ty.to_string())
+}
+
+#[cfg(test)]
+fn errors(source: &str) -> Vec<(TypeErrorKind, String)> {
+    let typing = checked(source);
+    typing
+        .errors
+        .into_iter()
+        .map(|e| (e.kind, source[e.span.start..e.span.end].to_owned()))
+        .collect()
+}
+
+#[test]
+fn test_promotions() {
+    let t = |e| type_of("char c; short s; unsigned u; long l; float f; double d;", e).unwrap();
+    assert_eq!(t("c + c"), "int");
+    assert_eq!(t("-s"), "int");
+    assert_eq!(t("~c"), "int");
+    assert_eq!(t("c << l"), "int");
+    assert_eq!(t("u + 1"), "unsigned int");
+    assert_eq!(t("u + l"), "long");
+    assert_eq!(t("u * 1ul"), "unsigned long");
+    assert_eq!(t("f * 2"), "float");
+    assert_eq!(t("f + d"), "double");
+    assert_eq!(t("c < d"), "int");
+    assert_eq!(t("u ? c : 1.0f"), "float");
+    assert_eq!(t("c += d"), "char");
+    assert_eq!(t("'a'"), "int");
+    assert_eq!(t("sizeof c"), "unsigned long");
+}
+
+#[test]
+fn test_pointers() {
+    let t = |e| type_of("int a[4]; const int *p; void *v; int f(int);", e).unwrap();
+    assert_eq!(t("a"), "int [4]");
+    assert_eq!(t("a + 1"), "int *");
+    assert_eq!(t("2 + p"), "const int *");
+    assert_eq!(t("p - a"), "long");
+    assert_eq!(t("a[1]"), "int");
+    assert_eq!(t("1[p]"), "const int");
+    assert_eq!(t("*p"), "const int");
+    assert_eq!(t("&a"), "int (*)[4]");
+    assert_eq!(t("f"), "int (int)");
+    assert_eq!(t("f(1)"), "int");
+    assert_eq!(t("(*f)(1)"), "int");
+    assert_eq!(t("&f"), "int (*)(int)");
+    assert_eq!(t("p == 0"), "int");
+    assert_eq!(t("1 ? p : v"), "const void *");
+    assert_eq!(t("1 ? p : 0"), "const int *");
+    assert_eq!(t("\"abc\""), "char [4]");
+    assert_eq!(t("L\"abc\""), "int [4]");
+}
+
+#[test]
+fn test_members() {
+    let s = "struct s { int x; struct { char y; }; const double z[2]; } v, *p;
+             const struct s c; union u { long l; } w;";
+    let t = |e| type_of(s, e).unwrap();
+    assert_eq!(t("v.x"), "int");
+    assert_eq!(t("p->x"), "int");
+    assert_eq!(t("p->y"), "char");
+    assert_eq!(t("v.z"), "const double [2]");
+    assert_eq!(t("c.x"), "const int");
+    assert_eq!(t("w.l"), "long");
+    assert_eq!(t("(&v)->z[1]"), "const double");
+
+    let s = format!("{} void f(void) {{ p.x; v->x; v.q; c.x = 1; }}", s);
+    assert_eq!(
+        errors(&s),
+        vec![
+            (TypeErrorKind::NotStructure, "p".to_owned()),
+            (TypeErrorKind::NotPointer, "v".to_owned()),
+            (TypeErrorKind::UnknownMember("q".to_owned()), "q".to_owned()),
+            (TypeErrorKind::NotModifiable, "c.x".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_generic() {
+    let s = "typedef int T; const char *s; T i;";
+    let t = |e| type_of(s, e).unwrap();
+    assert_eq!(t("_Generic(i, int: 1.0, default: s)"), "double");
+    assert_eq!(t("_Generic(s, char *: 1, const char *: 1.0f)"), "float");
+    assert_eq!(t("_Generic(\"x\", char *: 1L, default: 1)"), "long");
+    assert_eq!(t("_Generic(1u, default: s)"), "const char *");
+
+    let s = "void f(void) { _Generic(1.0, int: 1); }";
+    let double = QualType::new(CType::Float(FloatType::Double));
+    assert_eq!(
+        errors(s),
+        vec![(
+            TypeErrorKind::NoGenericMatch(double),
+            "_Generic(1.0, int: 1)".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn test_scopes() {
+    let s = "int x; void f(double x) { x; { char *x; x; } for (long x;;) x; } void g(void) { x; }";
+    let typing = checked(s);
+    assert_eq!(typing.errors, vec![]);
+    let types: Vec<_> = typing
+        .expressions
+        .iter()
+        .map(|(_, e)| e.ty.to_string())
+        .collect();
+    assert_eq!(types, vec!["double", "char *", "long", "int"]);
+}
+
+#[test]
+fn test_diagnostics() {
+    let s = "struct s { int x; } v; int f(int, char *); const int c = 0;
+             void g(void) {
+                 y; f(1); f(1, 2.0); v(); 1 = 2; c++; &1; *v; -v; (int *) 1.0;
+                 if (v) ; switch (1.0) ; v + 1; return 1;
+             }
+             int h(void) { int *p = 1.0; return; }";
+    let e = |kind, text: &str| (kind, text.to_owned());
+    let double = QualType::new(CType::Float(FloatType::Double));
+    let char_ptr = QualType::new(CType::Pointer(Box::new(QualType::new(CType::Integer(
+        IntegerType::Char,
+    )))));
+    let int_ptr = QualType::new(CType::Pointer(Box::new(QualType::new(CType::Integer(
+        IntegerType::Int,
+    )))));
+    assert_eq!(
+        errors(s),
+        vec![
+            e(TypeErrorKind::Undeclared("y".to_owned()), "y"),
+            e(
+                TypeErrorKind::ArgumentCount {
+                    expected: 2,
+                    found: 1,
+                },
+                "f",
+            ),
+            e(
+                TypeErrorKind::IncompatibleTypes {
+                    expected: char_ptr,
+                    found: double.clone(),
+                },
+                "2.0",
+            ),
+            e(TypeErrorKind::NotFunction, "v"),
+            e(TypeErrorKind::NotModifiable, "1 "),
+            e(TypeErrorKind::NotModifiable, "c"),
+            e(TypeErrorKind::NotLvalue, "1"),
+            e(TypeErrorKind::NotPointer, "v"),
+            e(TypeErrorKind::InvalidOperands, "-v"),
+            e(TypeErrorKind::InvalidCast, "(int *) 1.0"),
+            e(TypeErrorKind::NotScalar, "v"),
+            e(TypeErrorKind::NotInteger, "1.0"),
+            e(TypeErrorKind::InvalidOperands, "v + 1"),
+            e(TypeErrorKind::InvalidReturn, "1"),
+            e(
+                TypeErrorKind::IncompatibleTypes {
+                    expected: int_ptr,
+                    found: double,
+                },
+                "1.0",
+            ),
+            e(TypeErrorKind::InvalidReturn, "return;"),
+        ]
+    );
+}
@@ -0,0 +1,2064 @@
+//! Built-in C preprocessor
+//!
+//! This is an alternative to running an external `cpp` program. It implements macro definition
+//! and expansion, `#include` search, conditional compilation and `#line` directives (C11 6.10),
+//! and produces the same line markers as `gcc -E`, so that locations can be found using
+//! `loc::get_location_for_offset` in the output.
+//!
+//! ```no_run
+//! # use lang_c::cpp::Preprocessor;
+//! # use lang_c::driver::Flavor;
+//! # use std::path::Path;
+//! let mut cpp = Preprocessor::new(Flavor::GnuC11);
+//! cpp.add_include_path("include");
+//! cpp.define("NDEBUG").unwrap();
+//! let source = cpp.preprocess_file(Path::new("example.c")).unwrap();
+//! ```
+//!
+//! Only a small set of macros is predefined (`__STDC__`, `__STDC_VERSION__`, `__STDC_HOSTED__`
+//! and compiler identification macros for GNU and Clang flavors). Target-specific macros must be
+//! defined by the user.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use driver::Flavor;
+
+/// Maximum depth of nested `#include` directives
+const MAX_INCLUDE_DEPTH: usize = 200;
+
+/// Output jumps over at most this many lines with newlines, instead of a line marker
+const MAX_NEWLINES: usize = 8;
+
+const PUNCTUATORS: &[&str] = &[
+    "%:%:", "...", "<<=", ">>=", "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||",
+    "*=", "/=", "%=", "+=", "-=", "&=", "^=", "|=", "##", "<:", ":>", "<%", "%>", "%:", "[", "]",
+    "(", ")", "{", "}", ".", "&", "*", "+", "-", "~", "!", "/", "%", "<", ">", "^", "|", "?", ":",
+    ";", "=", ",", "#",
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Kind {
+    Identifier,
+    Number,
+    Character,
+    String,
+    Punctuator,
+    Other,
+    /// Start of an included file
+    Enter,
+    /// Return from an included file to the given line of the including file
+    Leave(usize),
+}
+
+type HideSet = Rc<Vec<Rc<str>>>;
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    text: Rc<str>,
+    /// Token is preceded by whitespace
+    space: bool,
+    /// Token is the first on a source line
+    bol: bool,
+    file: usize,
+    line: usize,
+    /// Byte offset of the token in its source line, starting from one, or zero for tokens that
+    /// do not come directly from the source
+    column: usize,
+    /// Macros that may not be expanded in this token
+    hide: HideSet,
+}
+
+impl Token {
+    fn is(&self, text: &str) -> bool {
+        match self.kind {
+            Kind::Punctuator | Kind::Identifier => &*self.text == text,
+            _ => false,
+        }
+    }
+
+    fn is_marker(&self) -> bool {
+        match self.kind {
+            Kind::Enter | Kind::Leave(_) => true,
+            _ => false,
+        }
+    }
+
+    fn is_hidden(&self, name: &str) -> bool {
+        self.hide.iter().any(|h| &**h == name)
+    }
+}
+
+fn hide_union(a: &HideSet, b: &HideSet) -> HideSet {
+    if b.is_empty() {
+        return a.clone();
+    }
+    let mut v = (**a).clone();
+    for h in b.iter() {
+        if !v.contains(h) {
+            v.push(h.clone());
+        }
+    }
+    Rc::new(v)
+}
+
+fn hide_intersection(a: &HideSet, b: &HideSet) -> HideSet {
+    Rc::new(a.iter().filter(|h| b.contains(h)).cloned().collect())
+}
+
+struct Macro {
+    /// Parameter names, `None` for object-like macros
+    params: Option<Vec<Rc<str>>>,
+    /// Last parameter collects variable arguments
+    variadic: bool,
+    body: Vec<Token>,
+}
+
+impl Macro {
+    fn param(&self, t: &Token) -> Option<usize> {
+        match (self.params.as_ref(), t.kind) {
+            (Some(p), Kind::Identifier) => p.iter().position(|p| **p == *t.text),
+            _ => None,
+        }
+    }
+}
+
+struct File {
+    /// Name used in line markers and `__FILE__`
+    name: String,
+    /// Actual location of the file
+    path: PathBuf,
+    /// Index of the include directory where the file was found, for `#include_next`
+    dir: Option<usize>,
+    /// File was found in a system include directory
+    system: bool,
+    /// Difference between the presumed (set by `#line`) and real line number
+    line_delta: isize,
+}
+
+impl File {
+    fn line(&self, line: usize) -> usize {
+        (line as isize + self.line_delta) as usize
+    }
+}
+
+struct Cond {
+    file: usize,
+    /// Some branch of this conditional has been included
+    taken: bool,
+    /// `#else` has been seen
+    in_else: bool,
+}
+
+/// C preprocessor
+///
+/// Macro definitions persist between calls to `preprocess_file`, as if all processed files were
+/// concatenated.
+pub struct Preprocessor {
+    quote_paths: Vec<PathBuf>,
+    include_paths: Vec<PathBuf>,
+    system_paths: Vec<PathBuf>,
+    macros: HashMap<Rc<str>, Rc<Macro>>,
+    files: Vec<File>,
+    pragma_once: HashSet<PathBuf>,
+    include_stack: Vec<usize>,
+    conds: Vec<Cond>,
+    counter: usize,
+    date: String,
+    time: String,
+    out: Writer,
+}
+
+impl Preprocessor {
+    /// Create a preprocessor with predefined macros for a given language flavor
+    pub fn new(flavor: Flavor) -> Preprocessor {
+        let (date, time) = date_time();
+        let mut cpp = Preprocessor {
+            quote_paths: Vec::new(),
+            include_paths: Vec::new(),
+            system_paths: Vec::new(),
+            macros: HashMap::new(),
+            files: Vec::new(),
+            pragma_once: HashSet::new(),
+            include_stack: Vec::new(),
+            conds: Vec::new(),
+            counter: 0,
+            date,
+            time,
+            out: Writer::new(),
+        };
+
+        let mut predefined = vec!["__STDC__=1", "__STDC_HOSTED__=1"];
+        match flavor {
+            Flavor::StdC23 | Flavor::GnuC23 => predefined.push("__STDC_VERSION__=202311L"),
+            _ => predefined.push("__STDC_VERSION__=201112L"),
+        }
+        match flavor {
+            Flavor::StdC11 | Flavor::StdC23 => {}
+            Flavor::GnuC11 | Flavor::GnuC23 => {
+                predefined.extend(&["__GNUC__=4", "__GNUC_MINOR__=2", "__GNUC_PATCHLEVEL__=1"])
+            }
+            Flavor::ClangC11 => predefined.extend(&[
+                "__GNUC__=4",
+                "__GNUC_MINOR__=2",
+                "__GNUC_PATCHLEVEL__=1",
+                "__clang__=1",
+            ]),
+            Flavor::MsvcC11 => predefined.extend(&["_MSC_VER=1930", "_WIN32=1"]),
+        }
+        for d in predefined {
+            cpp.define(d).expect("invalid predefined macro");
+        }
+
+        cpp
+    }
+
+    /// Add a directory to search for `#include "..."` files only (`-iquote`)
+    pub fn add_quote_include_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.quote_paths.push(path.into());
+    }
+
+    /// Add a directory to search for included files (`-I`)
+    pub fn add_include_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.include_paths.push(path.into());
+    }
+
+    /// Add a system directory to search for included files (`-isystem`)
+    ///
+    /// System directories are searched after all directories added with `add_include_path`.
+    pub fn add_system_include_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.system_paths.push(path.into());
+    }
+
+    /// Define a macro from a command line definition (`-D`)
+    ///
+    /// `NAME` defines the macro as `1`, `NAME=value` and `NAME(args)=value` define object-like and
+    /// function-like macros with the given replacement.
+    pub fn define(&mut self, definition: &str) -> io::Result<()> {
+        let line = match definition.find('=') {
+            Some(n) => format!("{} {}", &definition[..n], &definition[n + 1..]),
+            None => format!("{} 1", definition),
+        };
+        let tokens = try!(tokenize(&line, 0));
+        self.define_macro(tokens)
+    }
+
+    /// Remove a macro definition (`-U`)
+    pub fn undefine(&mut self, name: &str) {
+        self.macros.remove(name);
+    }
+
+    /// Configure preprocessor using `cpp` command line options
+    ///
+    /// Options `-I`, `-iquote`, `-isystem`, `-D` and `-U` are supported, either with the value
+    /// attached or as a separate argument. Other options are ignored.
+    pub fn apply_options(&mut self, options: &[String]) -> io::Result<()> {
+        let mut iter = options.iter();
+        while let Some(opt) = iter.next() {
+            let prefix = ["-iquote", "-isystem", "-I", "-D", "-U"]
+                .iter()
+                .find(|p| opt.starts_with(*p));
+            let prefix = match prefix {
+                Some(p) => *p,
+                None => continue,
+            };
+            let value = if opt.len() > prefix.len() {
+                &opt[prefix.len()..]
+            } else {
+                match iter.next() {
+                    Some(v) => &v[..],
+                    None => return Err(error(format!("missing argument to {}", opt))),
+                }
+            };
+            match prefix {
+                "-iquote" => self.add_quote_include_path(value),
+                "-isystem" => self.add_system_include_path(value),
+                "-I" => self.add_include_path(value),
+                "-D" => try!(self.define(value)),
+                _ => self.undefine(value),
+            }
+        }
+        Ok(())
+    }
+
+    /// Preprocess a file
+    pub fn preprocess_file(&mut self, path: &Path) -> io::Result<String> {
+        let source = try!(read_file(path));
+        self.run(&path.to_string_lossy(), path, &source)
+    }
+
+    /// Preprocess source text
+    ///
+    /// Relative `#include "..."` directives are resolved relative to the current directory.
+    pub fn preprocess_str(&mut self, name: &str, source: &str) -> io::Result<String> {
+        self.run(name, Path::new(name), source)
+    }
+
+    fn run(&mut self, name: &str, path: &Path, source: &str) -> io::Result<String> {
+        let file = self.add_file(name.to_owned(), path.to_owned(), None, false);
+        let mut input = try!(tokenize(source, file));
+        input.reverse();
+
+        self.out = Writer::new();
+        self.out.start(&self.files, file);
+        self.include_stack = vec![file];
+        self.conds.clear();
+
+        while let Some(tok) = input.pop() {
+            match tok.kind {
+                Kind::Enter => {
+                    self.out.enter(&self.files, tok.file);
+                    self.include_stack.push(tok.file);
+                    continue;
+                }
+                Kind::Leave(line) => {
+                    let done = self.include_stack.pop().expect("include stack underflow");
+                    try!(self.check_conds(done));
+                    self.out.leave(&self.files, tok.file, line);
+                    continue;
+                }
+                _ => {}
+            }
+
+            if tok.bol && tok.is("#") {
+                try!(self.directive(tok, &mut input));
+                continue;
+            }
+
+            if tok.is("_Pragma") && input.last().map_or(false, |t| t.is("(")) {
+                try!(self.pragma_operator(&tok, &mut input));
+                continue;
+            }
+
+            if try!(self.expand(&tok, &mut input)) {
+                continue;
+            }
+
+            self.out.token(&self.files, &tok);
+        }
+
+        try!(self.check_conds(file));
+
+        Ok(self.out.finish())
+    }
+
+    fn add_file(&mut self, name: String, path: PathBuf, dir: Option<usize>, system: bool) -> usize {
+        self.files.push(File {
+            name,
+            path,
+            dir,
+            system,
+            line_delta: 0,
+        });
+        self.files.len() - 1
+    }
+
+    fn error_at(&self, tok: &Token, msg: String) -> io::Error {
+        match self.files.get(tok.file) {
+            Some(file) => error(format!("{}:{}: {}", file.name, file.line(tok.line), msg)),
+            None => error(format!("<command line>: {}", msg)),
+        }
+    }
+
+    fn check_conds(&mut self, file: usize) -> io::Result<()> {
+        match self.conds.last() {
+            Some(c) if c.file == file => {}
+            _ => return Ok(()),
+        }
+        Err(error(format!(
+            "{}: unterminated conditional directive",
+            self.files[file].name
+        )))
+    }
+
+    ////
+    // Directives
+    ////
+
+    fn directive(&mut self, hash: Token, input: &mut Vec<Token>) -> io::Result<()> {
+        let mut line = read_line(input);
+        if line.is_empty() {
+            return Ok(());
+        }
+        let name = line.remove(0);
+
+        if name.kind == Kind::Number {
+            // GNU line marker: `# 33 "file" 2`
+            line.insert(0, name);
+            return self.line_directive(&hash, line);
+        }
+
+        match &*name.text {
+            "include" => self.include(&name, line, input, false),
+            "include_next" => self.include(&name, line, input, true),
+            "define" => self.define_macro(line),
+            "undef" => match line.first() {
+                Some(t) if t.kind == Kind::Identifier => {
+                    self.macros.remove(&t.text);
+                    Ok(())
+                }
+                _ => Err(self.error_at(&name, "macro name must be an identifier".into())),
+            },
+            "if" => {
+                let value = try!(self.eval_condition(&name, line));
+                self.conditional(&name, value, input)
+            }
+            "ifdef" | "ifndef" => {
+                let value = try!(self.is_defined_directive(&name, &line));
+                self.conditional(&name, value == (&*name.text == "ifdef"), input)
+            }
+            "elif" | "elifdef" | "elifndef" | "else" => self.alternative(&name, line, input),
+            "endif" => match self.conds.pop() {
+                Some(ref c) if c.file == name.file => Ok(()),
+                _ => Err(self.error_at(&name, "#endif without #if".into())),
+            },
+            "line" => self.line_directive(&name, line),
+            "error" => Err(self.error_at(&name, format!("#error {}", join_tokens(&line)))),
+            "pragma" => self.pragma(&name, line),
+            "warning" | "ident" | "sccs" => Ok(()),
+            _ => Err(self.error_at(
+                &name,
+                format!("invalid preprocessing directive #{}", name.text),
+            )),
+        }
+    }
+
+    fn define_macro(&mut self, mut line: Vec<Token>) -> io::Result<()> {
+        if line.is_empty() || line[0].kind != Kind::Identifier {
+            return Err(match line.first() {
+                Some(t) => self.error_at(t, "macro name must be an identifier".into()),
+                None => error("macro name missing".into()),
+            });
+        }
+        let name = line.remove(0);
+        if &*name.text == "defined" {
+            return Err(self.error_at(&name, "\"defined\" cannot be used as a macro name".into()));
+        }
+
+        let mut params = None;
+        let mut variadic = false;
+        if line.first().map_or(false, |t| t.is("(") && !t.space) {
+            let mut list = Vec::new();
+            let mut rest = line.into_iter();
+            rest.next();
+            loop {
+                let t = match rest.next() {
+                    Some(t) => t,
+                    None => {
+                        return Err(self.error_at(&name, "missing ')' in macro parameters".into()))
+                    }
+                };
+                if t.is(")") && list.is_empty() {
+                    break;
+                }
+                if t.is("...") {
+                    variadic = true;
+                    list.push(Rc::from("__VA_ARGS__"));
+                } else if t.kind == Kind::Identifier {
+                    list.push(t.text.clone());
+                    if rest.as_slice().first().map_or(false, |t| t.is("...")) {
+                        rest.next();
+                        variadic = true;
+                    }
+                } else {
+                    return Err(self.error_at(&t, "expected parameter name".into()));
+                }
+                match rest.next() {
+                    Some(ref t) if t.is(")") => break,
+                    Some(ref t) if t.is(",") && !variadic => continue,
+                    Some(t) => return Err(self.error_at(&t, "expected ',' or ')'".into())),
+                    None => {
+                        return Err(self.error_at(&name, "missing ')' in macro parameters".into()))
+                    }
+                }
+            }
+            params = Some(list);
+            line = rest.collect();
+        }
+
+        if let Some(first) = line.first_mut() {
+            first.space = false;
+        }
+        if line.first().map_or(false, |t| t.is("##")) || line.last().map_or(false, |t| t.is("##")) {
+            return Err(self.error_at(
+                &line[0],
+                "'##' cannot appear at either end of a macro expansion".into(),
+            ));
+        }
+
+        self.macros.insert(
+            name.text.clone(),
+            Rc::new(Macro {
+                params,
+                variadic,
+                body: line,
+            }),
+        );
+        Ok(())
+    }
+
+    fn include(
+        &mut self,
+        directive: &Token,
+        line: Vec<Token>,
+        input: &mut Vec<Token>,
+        next: bool,
+    ) -> io::Result<()> {
+        let line = if line
+            .first()
+            .map_or(false, |t| t.kind == Kind::String || t.is("<"))
+        {
+            line
+        } else {
+            try!(self.expand_all(line))
+        };
+        let (name, quoted) = match self.header_name(&line) {
+            Some(h) => h,
+            None => {
+                return Err(self.error_at(directive, "expected \"FILENAME\" or <FILENAME>".into()))
+            }
+        };
+
+        if self.include_stack.len() > MAX_INCLUDE_DEPTH {
+            return Err(self.error_at(directive, "#include nested too deeply".into()));
+        }
+
+        let current = *self
+            .include_stack
+            .last()
+            .expect("include outside of a file");
+        let (path, dir, system) = match self.find_include(&name, quoted, current, next) {
+            Some(f) => f,
+            None => return Err(self.error_at(directive, format!("'{}' file not found", name))),
+        };
+
+        if self.pragma_once.contains(&canonical(&path)) {
+            return Ok(());
+        }
+
+        let source = try!(read_file(&path));
+        let display = path.to_string_lossy().into_owned();
+        let file = self.add_file(display, path, dir, system);
+        let mut tokens = try!(tokenize(&source, file));
+
+        let parent = directive.file;
+        input.push(marker(Kind::Leave(directive.line + 1), parent));
+        tokens.reverse();
+        input.extend(tokens);
+        input.push(marker(Kind::Enter, file));
+        Ok(())
+    }
+
+    fn header_name(&self, line: &[Token]) -> Option<(String, bool)> {
+        let first = line.first()?;
+        if first.kind == Kind::String && first.text.starts_with('"') {
+            let t = &first.text;
+            return Some((t[1..t.len() - 1].to_owned(), true));
+        }
+        if first.is("<") {
+            let end = line.iter().position(|t| t.is(">"))?;
+            return Some((join_tokens(&line[1..end]), false));
+        }
+        None
+    }
+
+    fn find_include(
+        &self,
+        name: &str,
+        quoted: bool,
+        current: usize,
+        next: bool,
+    ) -> Option<(PathBuf, Option<usize>, bool)> {
+        if Path::new(name).is_absolute() {
+            let path = PathBuf::from(name);
+            return if path.is_file() {
+                Some((path, None, false))
+            } else {
+                None
+            };
+        }
+
+        let current = &self.files[current];
+        if quoted && !next {
+            let dir = current.path.parent().unwrap_or(Path::new(""));
+            let path = dir.join(name);
+            if path.is_file() {
+                return Some((path, None, current.system));
+            }
+        }
+
+        let mut dirs = Vec::new();
+        if quoted {
+            dirs.extend(self.quote_paths.iter().map(|d| (d, false)));
+        }
+        dirs.extend(self.include_paths.iter().map(|d| (d, false)));
+        dirs.extend(self.system_paths.iter().map(|d| (d, true)));
+        let quote_dirs = if quoted { self.quote_paths.len() } else { 0 };
+
+        // Directory indices are counted from the first non-quote directory, so that
+        // `#include_next` works the same for both forms of `#include`.
+        let skip = match (next, current.dir) {
+            (true, Some(d)) => d + 1 + quote_dirs,
+            _ => 0,
+        };
+        for (i, &(dir, system)) in dirs.iter().enumerate().skip(skip) {
+            let path = dir.join(name);
+            if path.is_file() {
+                let index = if i >= quote_dirs {
+                    Some(i - quote_dirs)
+                } else {
+                    None
+                };
+                return Some((path, index, system));
+            }
+        }
+        None
+    }
+
+    fn line_directive(&mut self, directive: &Token, line: Vec<Token>) -> io::Result<()> {
+        let line = try!(self.expand_all(line));
+        let number = match line.first() {
+            Some(t) if t.kind == Kind::Number => t.text.parse::<usize>().ok(),
+            _ => None,
+        };
+        let number = match number {
+            Some(n) => n,
+            None => {
+                return Err(self.error_at(directive, "#line directive requires a number".into()))
+            }
+        };
+        let file = &mut self.files[directive.file];
+        match line.get(1) {
+            Some(t) if t.kind == Kind::String && t.text.starts_with('"') => {
+                file.name = unescape_string(&t.text[1..t.text.len() - 1]);
+            }
+            Some(t) => return Err(self.error_at(t, "invalid filename in #line directive".into())),
+            None => {}
+        }
+        // The line after the directive gets the specified number.
+        file.line_delta = number as isize - (directive.line as isize + 1);
+        self.out.resync();
+        Ok(())
+    }
+
+    fn pragma(&mut self, directive: &Token, line: Vec<Token>) -> io::Result<()> {
+        if line.len() == 1 && line[0].is("once") {
+            let path = canonical(&self.files[directive.file].path);
+            self.pragma_once.insert(path);
+            return Ok(());
+        }
+        self.out.pragma(
+            &self.files,
+            directive.file,
+            directive.line,
+            &join_tokens(&line),
+        );
+        Ok(())
+    }
+
+    /// `_Pragma("...")` operator (C11 6.10.9)
+    fn pragma_operator(&mut self, tok: &Token, input: &mut Vec<Token>) -> io::Result<()> {
+        input.pop();
+        let s = match input.pop() {
+            Some(ref s) if s.kind == Kind::String => s.clone(),
+            _ => {
+                return Err(
+                    self.error_at(tok, "_Pragma takes a parenthesized string literal".into())
+                )
+            }
+        };
+        match input.pop() {
+            Some(ref t) if t.is(")") => {}
+            _ => {
+                return Err(
+                    self.error_at(tok, "_Pragma takes a parenthesized string literal".into())
+                )
+            }
+        }
+        let start = s.text.find('"').unwrap_or(0);
+        let text = unescape_string(&s.text[start + 1..s.text.len() - 1]);
+        let mut line = try!(tokenize(&text, tok.file));
+        for t in &mut line {
+            t.line = tok.line;
+        }
+        self.pragma(tok, line)
+    }
+
+    ////
+    // Conditional inclusion
+    ////
+
+    fn conditional(
+        &mut self,
+        directive: &Token,
+        value: bool,
+        input: &mut Vec<Token>,
+    ) -> io::Result<()> {
+        self.conds.push(Cond {
+            file: directive.file,
+            taken: value,
+            in_else: false,
+        });
+        if !value {
+            self.skip_group(input);
+        }
+        Ok(())
+    }
+
+    fn alternative(
+        &mut self,
+        directive: &Token,
+        line: Vec<Token>,
+        input: &mut Vec<Token>,
+    ) -> io::Result<()> {
+        let (taken, in_else) = match self.conds.last() {
+            Some(c) if c.file == directive.file => (c.taken, c.in_else),
+            _ => return Err(self.error_at(directive, format!("#{} without #if", directive.text))),
+        };
+        if in_else {
+            return Err(self.error_at(directive, format!("#{} after #else", directive.text)));
+        }
+
+        let value = if taken {
+            false
+        } else {
+            match &*directive.text {
+                "else" => true,
+                "elif" => try!(self.eval_condition(directive, line)),
+                "elifdef" => try!(self.is_defined_directive(directive, &line)),
+                _ => !try!(self.is_defined_directive(directive, &line)),
+            }
+        };
+
+        {
+            let cond = self.conds.last_mut().unwrap();
+            cond.taken = taken || value;
+            cond.in_else = &*directive.text == "else";
+        }
+        if !value {
+            self.skip_group(input);
+        }
+        Ok(())
+    }
+
+    /// Skip tokens until the next `#elif`, `#else` or `#endif` of the current conditional
+    fn skip_group(&mut self, input: &mut Vec<Token>) {
+        let mut depth = 0;
+        while let Some(tok) = input.pop() {
+            if !(tok.bol && tok.is("#")) || tok.is_marker() {
+                continue;
+            }
+            let name = match input.last() {
+                Some(t) if !t.bol => t.text.clone(),
+                _ => continue,
+            };
+            match &*name {
+                "if" | "ifdef" | "ifndef" => depth += 1,
+                "elif" | "elifdef" | "elifndef" | "else" if depth == 0 => {
+                    input.push(tok);
+                    return;
+                }
+                "endif" if depth == 0 => {
+                    input.push(tok);
+                    return;
+                }
+                "endif" => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+
+    fn is_defined_directive(&self, directive: &Token, line: &[Token]) -> io::Result<bool> {
+        match line.first() {
+            Some(t) if t.kind == Kind::Identifier => Ok(self.is_defined(&t.text)),
+            _ => Err(self.error_at(directive, "macro name must be an identifier".into())),
+        }
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name) || is_builtin(name)
+    }
+
+    fn eval_condition(&mut self, directive: &Token, line: Vec<Token>) -> io::Result<bool> {
+        let line = try!(self.replace_defined(line));
+        let line = try!(self.expand_all(line));
+        let line = try!(self.replace_defined(line));
+
+        let mut expr = Vec::new();
+        for t in line {
+            let value = match t.kind {
+                Kind::Identifier => {
+                    if &*t.text == "true" {
+                        "1"
+                    } else {
+                        "0"
+                    }
+                }
+                _ => {
+                    expr.push(t);
+                    continue;
+                }
+            };
+            expr.push(Token {
+                kind: Kind::Number,
+                text: Rc::from(value),
+                ..t
+            });
+        }
+
+        if expr.is_empty() {
+            return Err(self.error_at(directive, "#if with no expression".into()));
+        }
+
+        let mut parser = ExprParser {
+            tokens: &expr,
+            pos: 0,
+        };
+        let value = match parser.conditional() {
+            Ok(v) => v,
+            Err(msg) => return Err(self.error_at(parser.current(directive), msg)),
+        };
+        if parser.pos < expr.len() {
+            return Err(self.error_at(&expr[parser.pos], "missing binary operator".into()));
+        }
+        Ok(value.value != 0)
+    }
+
+    /// Replace `defined`, `__has_include` and other feature test operators in `#if`
+    fn replace_defined(&mut self, line: Vec<Token>) -> io::Result<Vec<Token>> {
+        let mut out = Vec::new();
+        let mut iter = line.into_iter();
+        while let Some(t) = iter.next() {
+            if t.kind != Kind::Identifier {
+                out.push(t);
+                continue;
+            }
+
+            let value = match &*t.text {
+                "defined" => {
+                    let mut name = iter.next();
+                    let paren = name.as_ref().map_or(false, |n| n.is("("));
+                    if paren {
+                        name = iter.next();
+                    }
+                    let name = match name {
+                        Some(ref n) if n.kind == Kind::Identifier => n.text.clone(),
+                        _ => {
+                            return Err(self.error_at(&t, "macro name must be an identifier".into()))
+                        }
+                    };
+                    if paren && !iter.next().map_or(false, |t| t.is(")")) {
+                        return Err(self.error_at(&t, "missing ')' after \"defined\"".into()));
+                    }
+                    self.is_defined(&name)
+                }
+                "__has_include" | "__has_include_next" => {
+                    let args = try!(self.feature_arguments(&t, &mut iter));
+                    let next = &*t.text == "__has_include_next";
+                    let args = if args
+                        .first()
+                        .map_or(false, |a| a.kind == Kind::String || a.is("<"))
+                    {
+                        args
+                    } else {
+                        try!(self.expand_all(args))
+                    };
+                    let current = *self.include_stack.last().unwrap_or(&t.file);
+                    match self.header_name(&args) {
+                        Some((name, quoted)) => {
+                            self.find_include(&name, quoted, current, next).is_some()
+                        }
+                        None => {
+                            return Err(
+                                self.error_at(&t, "expected \"FILENAME\" or <FILENAME>".into())
+                            )
+                        }
+                    }
+                }
+                "__has_attribute"
+                | "__has_c_attribute"
+                | "__has_builtin"
+                | "__has_feature"
+                | "__has_extension"
+                | "__has_declspec_attribute" => {
+                    try!(self.feature_arguments(&t, &mut iter));
+                    false
+                }
+                _ => {
+                    out.push(t);
+                    continue;
+                }
+            };
+
+            out.push(Token {
+                kind: Kind::Number,
+                text: Rc::from(if value { "1" } else { "0" }),
+                ..t
+            });
+        }
+        Ok(out)
+    }
+
+    fn feature_arguments<I: Iterator<Item = Token>>(
+        &self,
+        op: &Token,
+        iter: &mut I,
+    ) -> io::Result<Vec<Token>> {
+        if !iter.next().map_or(false, |t| t.is("(")) {
+            return Err(self.error_at(op, format!("missing '(' after {}", op.text)));
+        }
+        let mut depth = 0;
+        let mut args = Vec::new();
+        for t in iter {
+            if t.is("(") {
+                depth += 1;
+            } else if t.is(")") {
+                if depth == 0 {
+                    return Ok(args);
+                }
+                depth -= 1;
+            }
+            args.push(t);
+        }
+        Err(self.error_at(op, format!("missing ')' after {}", op.text)))
+    }
+
+    ////
+    // Macro expansion
+    ////
+
+    fn expand_all(&mut self, tokens: Vec<Token>) -> io::Result<Vec<Token>> {
+        let mut input: Vec<Token> = tokens.into_iter().rev().collect();
+        let mut out = Vec::new();
+        while let Some(tok) = input.pop() {
+            if !try!(self.expand(&tok, &mut input)) {
+                out.push(tok);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Expand a macro invocation starting with `tok`, pushing the result back to `input`
+    ///
+    /// Returns `false` if `tok` is not an expandable macro name.
+    fn expand(&mut self, tok: &Token, input: &mut Vec<Token>) -> io::Result<bool> {
+        if tok.kind != Kind::Identifier || tok.is_hidden(&tok.text) {
+            return Ok(false);
+        }
+
+        let m = match self.macros.get(&tok.text) {
+            Some(m) => m.clone(),
+            None => return self.expand_builtin(tok, input),
+        };
+
+        let (args, hide) = if m.params.is_some() {
+            if !input.last().map_or(false, |t| t.is("(")) {
+                return Ok(false);
+            }
+            let (args, rparen) = try!(self.collect_args(tok, &m, input));
+            (Some(args), hide_intersection(&tok.hide, &rparen.hide))
+        } else {
+            (None, tok.hide.clone())
+        };
+        let hide = hide_union(&hide, &Rc::new(vec![tok.text.clone()]));
+
+        let body = try!(self.substitute(tok, &m, &m.body, args.as_ref().map(|a| &a[..])));
+
+        for (i, mut t) in body.into_iter().enumerate().rev() {
+            t.hide = hide_union(&t.hide, &hide);
+            t.file = tok.file;
+            t.line = tok.line;
+            t.column = 0;
+            t.bol = false;
+            if i == 0 {
+                t.space = tok.space;
+                t.column = tok.column;
+            }
+            input.push(t);
+        }
+        Ok(true)
+    }
+
+    fn expand_builtin(&mut self, tok: &Token, input: &mut Vec<Token>) -> io::Result<bool> {
+        let (kind, text) = match &*tok.text {
+            "__FILE__" => {
+                let file = &self.files[tok.file];
+                (Kind::String, quote_string(&file.name))
+            }
+            "__LINE__" => (
+                Kind::Number,
+                self.files[tok.file].line(tok.line).to_string(),
+            ),
+            "__COUNTER__" => {
+                self.counter += 1;
+                (Kind::Number, (self.counter - 1).to_string())
+            }
+            "__INCLUDE_LEVEL__" => (Kind::Number, (self.include_stack.len() - 1).to_string()),
+            "__DATE__" => (Kind::String, quote_string(&self.date)),
+            "__TIME__" => (Kind::String, quote_string(&self.time)),
+            _ => return Ok(false),
+        };
+        input.push(Token {
+            kind,
+            text: Rc::from(text),
+            ..tok.clone()
+        });
+        Ok(true)
+    }
+
+    /// Read macro arguments, returns the arguments and the closing parenthesis
+    fn collect_args(
+        &self,
+        name: &Token,
+        m: &Macro,
+        input: &mut Vec<Token>,
+    ) -> io::Result<(Vec<Vec<Token>>, Token)> {
+        let nparams = m.params.as_ref().map_or(0, |p| p.len());
+        input.pop();
+
+        let mut args = vec![Vec::new()];
+        let mut depth = 0;
+        let rparen = loop {
+            let t = match input.pop() {
+                Some(t) => t,
+                None => {
+                    return Err(self.error_at(
+                        name,
+                        format!(
+                            "unterminated argument list invoking macro \"{}\"",
+                            name.text
+                        ),
+                    ))
+                }
+            };
+            if t.is_marker() {
+                return Err(self.error_at(
+                    name,
+                    format!(
+                        "unterminated argument list invoking macro \"{}\"",
+                        name.text
+                    ),
+                ));
+            }
+            if t.is("(") {
+                depth += 1;
+            } else if t.is(")") {
+                if depth == 0 {
+                    break t;
+                }
+                depth -= 1;
+            } else if t.is(",") && depth == 0 && !(m.variadic && args.len() == nparams) {
+                args.push(Vec::new());
+                continue;
+            }
+            args.last_mut().unwrap().push(t);
+        };
+
+        if nparams == 0 && args.len() == 1 && args[0].is_empty() {
+            args.clear();
+        }
+        if m.variadic && args.len() == nparams - 1 {
+            args.push(Vec::new());
+        }
+        if args.len() != nparams {
+            return Err(self.error_at(
+                name,
+                format!(
+                    "macro \"{}\" passed {} arguments, but takes {}",
+                    name.text,
+                    args.len(),
+                    nparams
+                ),
+            ));
+        }
+        Ok((args, rparen))
+    }
+
+    /// Replace parameters in a macro body, apply `#` and `##` operators (C11 6.10.3.1 -- 6.10.3.3)
+    fn substitute(
+        &mut self,
+        name: &Token,
+        m: &Macro,
+        body: &[Token],
+        args: Option<&[Vec<Token>]>,
+    ) -> io::Result<Vec<Token>> {
+        let args = args.unwrap_or(&[]);
+        let mut expanded: Vec<Option<Vec<Token>>> = vec![None; args.len()];
+        let variadic_empty = m.variadic && args.last().map_or(true, |a| a.is_empty());
+        let param = |t: &Token| m.param(t);
+
+        let mut out: Vec<Token> = Vec::new();
+        let mut i = 0;
+        while i < body.len() {
+            let t = &body[i];
+            let next = body.get(i + 1);
+
+            // Stringification
+            if t.is("#") && m.params.is_some() {
+                if let Some(p) = next.and_then(&param) {
+                    let mut s = stringize(&args[p]);
+                    s.space = t.space;
+                    out.push(s);
+                    i += 2;
+                    continue;
+                }
+            }
+
+            // GNU comma elision: `, ## __VA_ARGS__`
+            if t.is(",") && next.map_or(false, |n| n.is("##")) && m.variadic {
+                if let Some(p) = body.get(i + 2).and_then(&param) {
+                    if p == args.len() - 1 {
+                        if variadic_empty {
+                            i += 3;
+                        } else {
+                            out.push(t.clone());
+                            i += 2;
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // `__VA_OPT__(content)`
+            if t.is("__VA_OPT__") && m.variadic && next.map_or(false, |n| n.is("(")) {
+                let end = match matching_paren(body, i + 1) {
+                    Some(end) => end,
+                    None => return Err(self.error_at(name, "unterminated __VA_OPT__".into())),
+                };
+                if !variadic_empty {
+                    let inner = try!(self.substitute(name, m, &body[i + 2..end], Some(args)));
+                    out.extend(inner);
+                }
+                i = end + 1;
+                continue;
+            }
+
+            // Token pasting
+            if t.is("##") {
+                let rhs = match next {
+                    Some(rhs) => rhs,
+                    None => {
+                        return Err(self.error_at(
+                            name,
+                            "'##' cannot appear at either end of a macro expansion".into(),
+                        ))
+                    }
+                };
+                let rhs: Vec<Token> = match param(rhs) {
+                    Some(p) => args[p].clone(),
+                    None => vec![rhs.clone()],
+                };
+                i += 2;
+                let mut rhs = rhs.into_iter();
+                if let Some(first) = rhs.next() {
+                    match out.pop() {
+                        Some(lhs) => out.push(try!(self.paste(name, &lhs, &first))),
+                        None => out.push(first),
+                    }
+                }
+                out.extend(rhs);
+                continue;
+            }
+
+            if let Some(p) = param(t) {
+                if next.map_or(false, |n| n.is("##")) {
+                    // Operand of `##` is not macro-expanded
+                    let mut arg = args[p].clone();
+                    if let Some(first) = arg.first_mut() {
+                        first.space = t.space;
+                    }
+                    out.extend(arg);
+                    i += 1;
+                    continue;
+                }
+
+                if expanded[p].is_none() {
+                    expanded[p] = Some(try!(self.expand_all(args[p].clone())));
+                }
+                let mut arg = expanded[p].clone().unwrap();
+                if let Some(first) = arg.first_mut() {
+                    first.space = t.space;
+                }
+                out.extend(arg);
+                i += 1;
+                continue;
+            }
+
+            out.push(t.clone());
+            i += 1;
+        }
+
+        Ok(out)
+    }
+
+    fn paste(&self, name: &Token, lhs: &Token, rhs: &Token) -> io::Result<Token> {
+        let text = format!("{}{}", lhs.text, rhs.text);
+        let tokens = try!(tokenize(&text, lhs.file));
+        if tokens.len() != 1 {
+            return Err(self.error_at(
+                name,
+                format!(
+                    "pasting \"{}\" and \"{}\" does not give a valid preprocessing token",
+                    lhs.text, rhs.text
+                ),
+            ));
+        }
+        let t = tokens.into_iter().next().unwrap();
+        Ok(Token {
+            kind: t.kind,
+            text: t.text,
+            ..lhs.clone()
+        })
+    }
+}
+
+fn error(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, msg)
+}
+
+fn read_file(path: &Path) -> io::Result<String> {
+    let bytes = try!(fs::read(path));
+    match String::from_utf8(bytes) {
+        Ok(s) => Ok(s),
+        Err(e) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+fn is_builtin(name: &str) -> bool {
+    match name {
+        "__FILE__" | "__LINE__" | "__COUNTER__" | "__INCLUDE_LEVEL__" | "__DATE__" | "__TIME__"
+        | "__has_include" | "__has_include_next" => true,
+        _ => false,
+    }
+}
+
+fn marker(kind: Kind, file: usize) -> Token {
+    Token {
+        kind,
+        text: Rc::from(""),
+        space: false,
+        bol: true,
+        file,
+        line: 0,
+        column: 0,
+        hide: Rc::new(Vec::new()),
+    }
+}
+
+/// Remove tokens up to the end of the current line
+fn read_line(input: &mut Vec<Token>) -> Vec<Token> {
+    let mut line = Vec::new();
+    while input.last().map_or(false, |t| !t.bol) {
+        line.push(input.pop().unwrap());
+    }
+    line
+}
+
+fn matching_paren(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, t) in tokens.iter().enumerate().skip(open) {
+        if t.is("(") {
+            depth += 1;
+        } else if t.is(")") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+fn join_tokens(tokens: &[Token]) -> String {
+    let mut s = String::new();
+    for (i, t) in tokens.iter().enumerate() {
+        if i > 0 && t.space {
+            s.push(' ');
+        }
+        s.push_str(&t.text);
+    }
+    s
+}
+
+fn stringize(arg: &[Token]) -> Token {
+    let mut s = String::from("\"");
+    for (i, t) in arg.iter().enumerate() {
+        if i > 0 && t.space {
+            s.push(' ');
+        }
+        match t.kind {
+            Kind::String | Kind::Character => {
+                for c in t.text.chars() {
+                    if c == '"' || c == '\\' {
+                        s.push('\\');
+                    }
+                    s.push(c);
+                }
+            }
+            _ => s.push_str(&t.text),
+        }
+    }
+    s.push('"');
+    let mut t = arg
+        .first()
+        .map_or_else(|| marker(Kind::String, 0), |t| t.clone());
+    t.kind = Kind::String;
+    t.text = Rc::from(s);
+    t
+}
+
+fn quote_string(s: &str) -> String {
+    let mut q = String::from("\"");
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            q.push('\\');
+        }
+        q.push(c);
+    }
+    q.push('"');
+    q
+}
+
+fn unescape_string(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(c) = chars.next() {
+                out.push(c);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Current date and time in `__DATE__` and `__TIME__` format
+fn date_time() -> (String, String) {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86400, secs % 86400);
+
+    // Civil from days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (
+        format!("{} {:2} {}", MONTHS[month as usize - 1], day, year),
+        format!("{:02}:{:02}:{:02}", rem / 3600, rem / 60 % 60, rem % 60),
+    )
+}
+
+// Tokenizer
+
+/// Split source text into preprocessing tokens (C11 6.4)
+fn tokenize(source: &str, file: usize) -> io::Result<Vec<Token>> {
+    // Translation phases 1-2: join lines ending with a backslash, remembering original line
+    // numbers and columns for every character.
+    let mut chars = Vec::with_capacity(source.len());
+    let mut lines = Vec::with_capacity(source.len());
+    let mut columns = Vec::with_capacity(source.len());
+    let mut line = 1;
+    let mut column = 1;
+    let mut iter = source.chars().peekable();
+    while let Some(c) = iter.next() {
+        if c == '\r' && iter.peek() == Some(&'\n') {
+            continue;
+        }
+        if c == '\\' {
+            let mut ahead = iter.clone();
+            if ahead.peek() == Some(&'\r') {
+                ahead.next();
+            }
+            if ahead.peek() == Some(&'\n') {
+                ahead.next();
+                iter = ahead;
+                line += 1;
+                column = 1;
+                continue;
+            }
+        }
+        chars.push(c);
+        lines.push(line);
+        columns.push(column);
+        column += c.len_utf8();
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut bol = true;
+    let mut space = false;
+    let mut p = 0;
+    let at = |p: usize| chars.get(p).cloned().unwrap_or('\0');
+
+    while p < chars.len() {
+        let c = chars[p];
+
+        if c == '\n' {
+            bol = true;
+            space = false;
+            p += 1;
+            continue;
+        }
+        if c == ' ' || c == '\t' || c == '\x0b' || c == '\x0c' || c == '\r' {
+            space = true;
+            p += 1;
+            continue;
+        }
+        if c == '/' && at(p + 1) == '/' {
+            while p < chars.len() && chars[p] != '\n' {
+                p += 1;
+            }
+            space = true;
+            continue;
+        }
+        if c == '/' && at(p + 1) == '*' {
+            let start = p;
+            p += 2;
+            while p < chars.len() && !(chars[p] == '*' && at(p + 1) == '/') {
+                p += 1;
+            }
+            if p >= chars.len() {
+                return Err(error(format!("{}: unterminated comment", lines[start])));
+            }
+            p += 2;
+            space = true;
+            continue;
+        }
+
+        let start = p;
+        let kind = if c.is_ascii_digit() || (c == '.' && at(p + 1).is_ascii_digit()) {
+            p += 1;
+            loop {
+                let c = at(p);
+                if (c == '+' || c == '-') && "eEpP".contains(chars[p - 1]) {
+                    p += 1;
+                } else if c == '\'' && (at(p + 1).is_ascii_alphanumeric() || at(p + 1) == '_') {
+                    p += 2;
+                } else if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                    p += 1;
+                } else {
+                    break;
+                }
+            }
+            Kind::Number
+        } else if c == '"' || c == '\'' || is_literal_prefix(&chars[p..]) {
+            while chars[p] != '"' && chars[p] != '\'' {
+                p += 1;
+            }
+            let quote = chars[p];
+            p += 1;
+            while p < chars.len() && chars[p] != quote && chars[p] != '\n' {
+                if chars[p] == '\\' {
+                    p += 1;
+                }
+                p += 1;
+            }
+            p = if p < chars.len() && chars[p] == quote {
+                p + 1
+            } else {
+                p.min(chars.len())
+            };
+            if quote == '"' {
+                Kind::String
+            } else {
+                Kind::Character
+            }
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            while at(p).is_alphanumeric() || at(p) == '_' || at(p) == '$' {
+                p += 1;
+            }
+            Kind::Identifier
+        } else {
+            let punct = PUNCTUATORS
+                .iter()
+                .find(|s| s.chars().enumerate().all(|(i, sc)| at(p + i) == sc));
+            match punct {
+                Some(s) => {
+                    p += s.len();
+                    Kind::Punctuator
+                }
+                None => {
+                    p += 1;
+                    Kind::Other
+                }
+            }
+        };
+
+        let text: String = chars[start..p].iter().collect();
+        let text = match &text[..] {
+            "%:" => "#".to_owned(),
+            "%:%:" => "##".to_owned(),
+            _ => text,
+        };
+        tokens.push(Token {
+            kind,
+            text: Rc::from(text),
+            space,
+            bol,
+            file,
+            line: lines[start],
+            column: columns[start],
+            hide: Rc::new(Vec::new()),
+        });
+        bol = false;
+        space = false;
+    }
+
+    Ok(tokens)
+}
+
+/// Encoding prefix followed by a string or character literal
+fn is_literal_prefix(s: &[char]) -> bool {
+    let quote = |c: Option<&char>| c == Some(&'"') || c == Some(&'\'');
+    match s.first() {
+        Some(&'L') | Some(&'U') => quote(s.get(1)),
+        Some(&'u') if s.get(1) == Some(&'8') => quote(s.get(2)),
+        Some(&'u') => quote(s.get(1)),
+        _ => false,
+    }
+}
+
+// Output
+
+/// Builds preprocessed text with `gcc -E` style line markers
+struct Writer {
+    out: String,
+    file: Option<usize>,
+    /// Presumed line number of the current output line
+    line: usize,
+    /// Nothing has been written on the current output line
+    bol: bool,
+    /// Number of bytes written on the current output line
+    column: usize,
+    /// Emit a line marker before the next token
+    resync: bool,
+    last: Option<char>,
+}
+
+impl Writer {
+    fn new() -> Writer {
+        Writer {
+            out: String::new(),
+            file: None,
+            line: 1,
+            bol: true,
+            column: 0,
+            resync: false,
+            last: None,
+        }
+    }
+
+    fn start(&mut self, files: &[File], file: usize) {
+        self.marker(files, file, 1, "");
+    }
+
+    fn enter(&mut self, files: &[File], file: usize) {
+        let flags = if files[file].system { " 1 3" } else { " 1" };
+        self.marker(files, file, 1, flags);
+    }
+
+    /// Return to `file` after an include directive on `line`
+    fn leave(&mut self, files: &[File], file: usize, line: usize) {
+        let flags = if files[file].system { " 2 3" } else { " 2" };
+        let line = files[file].line(line);
+        self.marker(files, file, line, flags);
+    }
+
+    fn resync(&mut self) {
+        self.resync = true;
+    }
+
+    fn marker(&mut self, files: &[File], file: usize, line: usize, flags: &str) {
+        self.newline();
+        self.out.push_str(&format!(
+            "# {} {}{}\n",
+            line,
+            quote_string(&files[file].name),
+            flags
+        ));
+        self.line = line;
+        self.file = Some(file);
+        self.resync = false;
+    }
+
+    fn newline(&mut self) {
+        if !self.bol {
+            self.out.push('\n');
+            self.line += 1;
+            self.bol = true;
+            self.column = 0;
+            self.last = None;
+        }
+    }
+
+    /// Move to the given line of a file
+    fn goto(&mut self, files: &[File], file: usize, line: usize) {
+        let line = files[file].line(line);
+        if self.resync
+            || self.file != Some(file)
+            || line < self.line
+            || line > self.line + MAX_NEWLINES
+        {
+            self.marker(files, file, line, "");
+        }
+        while self.line < line {
+            self.out.push('\n');
+            self.line += 1;
+            self.bol = true;
+            self.column = 0;
+            self.last = None;
+        }
+    }
+
+    fn token(&mut self, files: &[File], tok: &Token) {
+        self.goto(files, tok.file, tok.line);
+        let first = tok.text.chars().next();
+        if tok.column > self.column + 1 {
+            // Keep the token at its original column, so that locations in the output point to
+            // the right place in the source.
+            while self.column + 1 < tok.column {
+                self.out.push(' ');
+                self.column += 1;
+            }
+        } else if !self.bol && (tok.space || would_paste(self.last, first)) {
+            self.out.push(' ');
+            self.column += 1;
+        }
+        self.out.push_str(&tok.text);
+        self.bol = false;
+        self.column += tok.text.len();
+        self.last = tok.text.chars().last();
+    }
+
+    fn pragma(&mut self, files: &[File], file: usize, line: usize, text: &str) {
+        if self.bol {
+            self.goto(files, file, line);
+        } else {
+            self.newline();
+            self.resync = true;
+        }
+        self.out.push_str("#pragma ");
+        self.out.push_str(text);
+        self.out.push('\n');
+        self.line += 1;
+        self.last = None;
+    }
+
+    fn finish(&mut self) -> String {
+        self.newline();
+        ::std::mem::replace(&mut self.out, String::new())
+    }
+}
+
+/// Adjacent characters would be read as a single token
+fn would_paste(a: Option<char>, b: Option<char>) -> bool {
+    let (a, b) = match (a, b) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return false,
+    };
+    let word = |c: char| c.is_alphanumeric() || c == '_' || c == '$' || c == '.';
+    let punct = |c: char| "+-*/%<>=!&|^#.:".contains(c);
+    (word(a) && word(b)) || (punct(a) && punct(b))
+}
+
+// Expressions in `#if`
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Value {
+    value: u64,
+    unsigned: bool,
+}
+
+impl Value {
+    fn signed(v: i64) -> Value {
+        Value {
+            value: v as u64,
+            unsigned: false,
+        }
+    }
+
+    fn boolean(b: bool) -> Value {
+        Value::signed(b as i64)
+    }
+}
+
+/// Evaluates integer expressions in `#if` using `intmax_t` and `uintmax_t` (C11 6.10.1 §4)
+struct ExprParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+type ExprResult = Result<Value, String>;
+
+impl<'a> ExprParser<'a> {
+    fn current<'b>(&'b self, default: &'b Token) -> &'b Token {
+        self.tokens.get(self.pos).unwrap_or(default)
+    }
+
+    fn accept(&mut self, op: &str) -> bool {
+        if self.tokens.get(self.pos).map_or(false, |t| t.is(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.accept(op) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}' in preprocessor expression", op))
+        }
+    }
+
+    fn conditional(&mut self) -> ExprResult {
+        let c = try!(self.binary(0));
+        if !self.accept("?") {
+            return Ok(c);
+        }
+        let a = try!(self.expression());
+        try!(self.expect(":"));
+        let b = try!(self.conditional());
+        let unsigned = a.unsigned || b.unsigned;
+        let v = if c.value != 0 { a } else { b };
+        Ok(Value {
+            value: v.value,
+            unsigned,
+        })
+    }
+
+    fn expression(&mut self) -> ExprResult {
+        let mut v = try!(self.conditional());
+        while self.accept(",") {
+            v = try!(self.conditional());
+        }
+        Ok(v)
+    }
+
+    fn binary(&mut self, min_prec: usize) -> ExprResult {
+        const OPS: &[(&str, usize)] = &[
+            ("||", 1),
+            ("&&", 2),
+            ("|", 3),
+            ("^", 4),
+            ("&", 5),
+            ("==", 6),
+            ("!=", 6),
+            ("<", 7),
+            (">", 7),
+            ("<=", 7),
+            (">=", 7),
+            ("<<", 8),
+            (">>", 8),
+            ("+", 9),
+            ("-", 9),
+            ("*", 10),
+            ("/", 10),
+            ("%", 10),
+        ];
+
+        let mut lhs = try!(self.unary());
+        loop {
+            let op = match self.tokens.get(self.pos) {
+                Some(t) if t.kind == Kind::Punctuator => {
+                    OPS.iter().find(|o| o.0 == &*t.text && o.1 > min_prec)
+                }
+                _ => None,
+            };
+            let (op, prec) = match op {
+                Some(&(op, prec)) => (op, prec),
+                None => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = try!(self.binary(prec));
+            lhs = try!(binary_op(op, lhs, rhs));
+        }
+    }
+
+    fn unary(&mut self) -> ExprResult {
+        if self.accept("+") {
+            return self.unary();
+        }
+        if self.accept("-") {
+            let v = try!(self.unary());
+            return Ok(Value {
+                value: v.value.wrapping_neg(),
+                unsigned: v.unsigned,
+            });
+        }
+        if self.accept("~") {
+            let v = try!(self.unary());
+            return Ok(Value {
+                value: !v.value,
+                unsigned: v.unsigned,
+            });
+        }
+        if self.accept("!") {
+            let v = try!(self.unary());
+            return Ok(Value::boolean(v.value == 0));
+        }
+        if self.accept("(") {
+            let v = try!(self.expression());
+            try!(self.expect(")"));
+            return Ok(v);
+        }
+
+        let t = match self.tokens.get(self.pos) {
+            Some(t) => t,
+            None => return Err("expected value in preprocessor expression".into()),
+        };
+        let v = match t.kind {
+            Kind::Number => try!(parse_number(&t.text)),
+            Kind::Character => try!(parse_character(&t.text)),
+            _ => {
+                return Err(format!(
+                    "token \"{}\" is not valid in preprocessor expressions",
+                    t.text
+                ))
+            }
+        };
+        self.pos += 1;
+        Ok(v)
+    }
+}
+
+fn binary_op(op: &str, a: Value, b: Value) -> ExprResult {
+    let unsigned = a.unsigned || b.unsigned;
+    let (x, y) = (a.value, b.value);
+    let value = match op {
+        "||" => return Ok(Value::boolean(x != 0 || y != 0)),
+        "&&" => return Ok(Value::boolean(x != 0 && y != 0)),
+        "==" => return Ok(Value::boolean(x == y)),
+        "!=" => return Ok(Value::boolean(x != y)),
+        "<" | ">" | "<=" | ">=" => {
+            let ord = if unsigned {
+                x.cmp(&y)
+            } else {
+                (x as i64).cmp(&(y as i64))
+            };
+            let r = match op {
+                "<" => ord == ::std::cmp::Ordering::Less,
+                ">" => ord == ::std::cmp::Ordering::Greater,
+                "<=" => ord != ::std::cmp::Ordering::Greater,
+                _ => ord != ::std::cmp::Ordering::Less,
+            };
+            return Ok(Value::boolean(r));
+        }
+        "|" => x | y,
+        "^" => x ^ y,
+        "&" => x & y,
+        "<<" => x.wrapping_shl(y as u32),
+        ">>" if a.unsigned => x.wrapping_shr(y as u32),
+        ">>" => (x as i64).wrapping_shr(y as u32) as u64,
+        "+" => x.wrapping_add(y),
+        "-" => x.wrapping_sub(y),
+        "*" => x.wrapping_mul(y),
+        "/" | "%" if y == 0 => return Err("division by zero in preprocessor expression".into()),
+        "/" if unsigned => x / y,
+        "/" => (x as i64).wrapping_div(y as i64) as u64,
+        "%" if unsigned => x % y,
+        _ => (x as i64).wrapping_rem(y as i64) as u64,
+    };
+    let unsigned = match op {
+        "<<" | ">>" => a.unsigned,
+        _ => unsigned,
+    };
+    Ok(Value { value, unsigned })
+}
+
+fn parse_number(text: &str) -> ExprResult {
+    let text = text.replace('\'', "");
+    let digits = text.trim_end_matches(|c| "uUlL".contains(c));
+    let unsigned = text[digits.len()..].contains(&['u', 'U'][..]);
+    let lower = digits.to_ascii_lowercase();
+    let (radix, digits) = if lower.starts_with("0x") {
+        (16, &digits[2..])
+    } else if lower.starts_with("0b") {
+        (2, &digits[2..])
+    } else if lower.starts_with('0') && lower.len() > 1 {
+        (8, &digits[1..])
+    } else {
+        (10, digits)
+    };
+    match u64::from_str_radix(digits, radix) {
+        Ok(v) => Ok(Value {
+            value: v,
+            unsigned: unsigned || v > i64::max_value() as u64,
+        }),
+        Err(_) => Err(format!(
+            "invalid integer constant \"{}\" in preprocessor expression",
+            text
+        )),
+    }
+}
+
+fn parse_character(text: &str) -> ExprResult {
+    let start = text.find('\'').unwrap_or(0);
+    let body: Vec<char> = text[start + 1..text.len() - 1].chars().collect();
+    let value = match body.first() {
+        Some(&'\\') => match body.get(1) {
+            Some(&'n') => 10,
+            Some(&'t') => 9,
+            Some(&'r') => 13,
+            Some(&'a') => 7,
+            Some(&'b') => 8,
+            Some(&'f') => 12,
+            Some(&'v') => 11,
+            Some(&'x') => {
+                let s: String = body[2..].iter().collect();
+                try!(u64::from_str_radix(&s, 16)
+                    .map_err(|_| format!("invalid character constant {}", text)))
+            }
+            Some(c) if c.is_digit(8) => {
+                let s: String = body[1..].iter().collect();
+                try!(u64::from_str_radix(&s, 8)
+                    .map_err(|_| format!("invalid character constant {}", text)))
+            }
+            Some(&c) => c as u64,
+            None => return Err(format!("invalid character constant {}", text)),
+        },
+        Some(&c) => c as u64,
+        None => return Err(format!("empty character constant {}", text)),
+    };
+    // Plain `char` is signed on most targets.
+    let value = if start == 0 {
+        value as u8 as i8 as i64
+    } else {
+        value as i64
+    };
+    Ok(Value::signed(value))
+}
+
+#[cfg(test)]
+fn pp(source: &str) -> String {
+    let mut cpp = Preprocessor::new(Flavor::StdC11);
+    cpp.preprocess_str("test.c", source).unwrap()
+}
+
+#[test]
+fn test_object_macros() {
+    assert_eq!(
+        pp("#define A 1 + B\n#define B 2\nint x = A;\n"),
+        "# 1 \"test.c\"\n\n\nint x = 1 + 2;\n"
+    );
+    assert_eq!(
+        pp("#define A A B\n#define B A\nA\n"),
+        "# 1 \"test.c\"\n\n\nA A\n"
+    );
+}
+
+#[test]
+fn test_function_macros() {
+    assert_eq!(
+        pp("#define F(a, b) a * (b)\nF(x + 1, (y, z));\n"),
+        "# 1 \"test.c\"\n\nx + 1 * ((y, z));\n"
+    );
+    assert_eq!(
+        pp("#define S(x) #x\n#define C(a, b) a ## b\nS(\"a\\n\" 'b') C(foo, 12) C(,x)\n"),
+        "# 1 \"test.c\"\n\n\n\"\\\"a\\\\n\\\" 'b'\" foo12    x\n"
+    );
+    assert_eq!(
+        pp("#define P(fmt, ...) printf(fmt, ## __VA_ARGS__)\nP(\"a\"); P(\"%d\", 1, 2);\n"),
+        "# 1 \"test.c\"\n\nprintf(\"a\"); printf(\"%d\", 1, 2);\n"
+    );
+    assert_eq!(
+        pp("#define F(x) x\nF(\n1\n)\nF\n"),
+        "# 1 \"test.c\"\n\n1\n\n\nF\n"
+    );
+}
+
+#[test]
+fn test_conditionals() {
+    let src = r#"#define A 2
+#if A > 1 && defined(A) && !defined B
+yes
+#elif 1
+no
+#else
+no
+#endif
+#ifdef B
+no
+#else
+#if (-1 < 0u) || __STDC_VERSION__ < 201112L
+no
+#endif
+yes
+#endif
+"#;
+    assert_eq!(pp(src), "# 1 \"test.c\"\n\n\nyes\n# 15 \"test.c\"\nyes\n");
+}
+
+#[test]
+fn test_columns() {
+    assert_eq!(
+        pp("int main() {\n    return  0;\n}\n"),
+        "# 1 \"test.c\"\nint main() {\n    return  0;\n}\n"
+    );
+    // Expanded tokens take the column of the macro name, if there is room for them.
+    assert_eq!(
+        pp("#define N 1000000\n\tx = N+N;\n"),
+        "# 1 \"test.c\"\n\n x = 1000000+1000000;\n"
+    );
+}
+
+#[test]
+fn test_line_markers() {
+    let src = "a\n#line 100 \"other.c\"\nb __LINE__ __FILE__\n\n\n\n\n\n\n\n\n\n\nc\n";
+    assert_eq!(
+        pp(src),
+        "# 1 \"test.c\"\na\n# 100 \"other.c\"\nb 100      \"other.c\"\n# 111 \"other.c\"\nc\n"
+    );
+}
+
+#[test]
+fn test_errors() {
+    let mut cpp = Preprocessor::new(Flavor::StdC11);
+    let e = cpp
+        .preprocess_str("t.c", "\n#error stop here\n")
+        .unwrap_err();
+    assert_eq!(e.to_string(), "t.c:2: #error stop here");
+    let e = cpp.preprocess_str("t.c", "#if 1\n").unwrap_err();
+    assert_eq!(e.to_string(), "t.c: unterminated conditional directive");
+    let e = cpp
+        .preprocess_str("t.c", "#include \"missing.h\"\n")
+        .unwrap_err();
+    assert_eq!(e.to_string(), "t.c:1: 'missing.h' file not found");
+}
+
+#[test]
+fn test_c23_version() {
+    let mut cpp = Preprocessor::new(Flavor::GnuC23);
+    let src = "#if __STDC_VERSION__ >= 202311L && defined __GNUC__\nok\n#endif\n";
+    assert_eq!(
+        cpp.preprocess_str("t.c", src).unwrap(),
+        "# 1 \"t.c\"\n\nok\n"
+    );
+}
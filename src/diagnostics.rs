@@ -0,0 +1,426 @@
+//! Rendering of errors with source snippets
+//!
+//! Errors are converted to `Diagnostic` values and rendered either as text, in the style of C
+//! compilers, or as JSON for editor integration.
+//!
+//! ```
+//! # use lang_c::diagnostics::{Diagnostic, Emitter};
+//! # use lang_c::loc::SourceMap;
+//! # use lang_c::span::Span;
+//! let source = "# 1 \"main.c\"\nint x = count;\n";
+//! let map = SourceMap::new(source);
+//! let start = source.find("count").unwrap();
+//! let diagnostic = Diagnostic::error("'count' is not declared", Span::span(start, start + 5));
+//! assert_eq!(
+//!     Emitter::new(source, &map).render(&diagnostic),
+//!     "main.c:1:9: error: 'count' is not declared\n   \
+//!         1 | int x = count;\n     \
+//!           |         ^~~~~\n"
+//! );
+//! ```
+//!
+//! Column numbers count bytes from the start of the line, as line numbers they start from one.
+
+use std::fmt::Write;
+
+use check::TypeError;
+use driver::SyntaxError;
+use eval::EvalError;
+use literal::LiteralError;
+use loc::SourceMap;
+use span::Span;
+
+/// How serious a diagnostic is
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+
+    /// Terminal colour of the severity label
+    fn color(self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;35m",
+            Severity::Note => "\x1b[1;36m",
+        }
+    }
+}
+
+/// Message about a location in the source
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Offending part of the preprocessed source
+    pub span: Span,
+    /// Additional messages, rendered after this one
+    pub notes: Vec<Diagnostic>,
+}
+
+impl Diagnostic {
+    pub fn new<S: Into<String>>(severity: Severity, message: S, span: Span) -> Diagnostic {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            span,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn error<S: Into<String>>(message: S, span: Span) -> Diagnostic {
+        Diagnostic::new(Severity::Error, message, span)
+    }
+
+    /// Add a note
+    pub fn with_note<S: Into<String>>(mut self, message: S, span: Span) -> Diagnostic {
+        self.notes
+            .push(Diagnostic::new(Severity::Note, message, span));
+        self
+    }
+}
+
+impl<'a> From<&'a SyntaxError> for Diagnostic {
+    fn from(e: &'a SyntaxError) -> Diagnostic {
+        let mut message = String::from("unexpected token");
+        let mut expected: Vec<_> = e.expected.iter().collect();
+        expected.sort();
+        for (i, t) in expected.iter().enumerate() {
+            message.push_str(if i == 0 { ", expected " } else { ", " });
+            message.push('\'');
+            message.push_str(t);
+            message.push('\'');
+        }
+
+        // Underline the whole word at the error, or a single character
+        let rest = &e.source[e.offset..];
+        let word = |c: char| c.is_alphanumeric() || c == '_';
+        let len = match rest.chars().next() {
+            Some(c) if word(c) => rest.find(|c| !word(c)).unwrap_or(rest.len()),
+            Some(c) => c.len_utf8(),
+            None => 0,
+        };
+        Diagnostic::error(message, Span::span(e.offset, e.offset + len))
+    }
+}
+
+impl<'a> From<&'a TypeError> for Diagnostic {
+    fn from(e: &'a TypeError) -> Diagnostic {
+        Diagnostic::error(e.to_string(), e.span)
+    }
+}
+
+impl<'a> From<&'a EvalError> for Diagnostic {
+    fn from(e: &'a EvalError) -> Diagnostic {
+        Diagnostic::error(e.to_string(), e.span)
+    }
+}
+
+impl<'a> From<&'a LiteralError> for Diagnostic {
+    fn from(e: &'a LiteralError) -> Diagnostic {
+        Diagnostic::error(e.to_string(), e.span)
+    }
+}
+
+/// Renders diagnostics for one preprocessed source
+#[derive(Debug, Clone)]
+pub struct Emitter<'a> {
+    source: &'a str,
+    map: &'a SourceMap,
+    /// Use terminal colours in text output
+    pub color: bool,
+}
+
+const BOLD: &str = "\x1b[1m";
+const CARET: &str = "\x1b[1;32m";
+const RESET: &str = "\x1b[0m";
+
+impl<'a> Emitter<'a> {
+    /// Emitter without colours, `map` must be built from `source`
+    pub fn new(source: &'a str, map: &'a SourceMap) -> Emitter<'a> {
+        Emitter {
+            source,
+            map,
+            color: false,
+        }
+    }
+
+    /// Text with the location, the message, the source line and a caret under the span
+    ///
+    /// Diagnostics in included files are preceded by the chain of includes.
+    pub fn render(&self, d: &Diagnostic) -> String {
+        let mut out = String::new();
+        self.render_to(&mut out, d);
+        out
+    }
+
+    fn render_to(&self, out: &mut String, d: &Diagnostic) {
+        let (bold, label, caret, reset) = match self.color {
+            true => (BOLD, d.severity.color(), CARET, RESET),
+            false => ("", "", "", ""),
+        };
+
+        // Nodes built outside of the parser have no location
+        if d.span.is_none() {
+            writeln!(
+                out,
+                "{}{}:{} {}",
+                label,
+                d.severity.name(),
+                reset,
+                d.message
+            )
+            .unwrap();
+            for note in &d.notes {
+                self.render_to(out, note);
+            }
+            return;
+        }
+
+        let includes = self.map.includes(d.span.start);
+        for (i, l) in includes.iter().rev().enumerate() {
+            let prefix = if i == 0 {
+                "In file included from"
+            } else {
+                "                 from"
+            };
+            let end = if i + 1 == includes.len() { ":" } else { "," };
+            writeln!(
+                out,
+                "{} {}{}:{}{}{}",
+                prefix, bold, l.file, l.line, reset, end
+            )
+            .unwrap();
+        }
+
+        let start = self.map.location(d.span.start);
+        writeln!(
+            out,
+            "{}{}:{}:{}:{} {}{}:{} {}",
+            bold,
+            start.file,
+            start.line,
+            start.column,
+            reset,
+            label,
+            d.severity.name(),
+            reset,
+            d.message
+        )
+        .unwrap();
+
+        // Source line and the underline, limited to the first line of the span
+        let line_start = d.span.start + 1 - start.column;
+        let line_end = self.source[line_start..]
+            .find('\n')
+            .map_or(self.source.len(), |n| line_start + n);
+        let line = &self.source[line_start..line_end];
+        let number = start.line.to_string();
+        let gutter = " ".repeat(number.len().max(4));
+        writeln!(out, "{:>4} | {}", number, line).unwrap();
+
+        let before = &self.source[line_start..d.span.start];
+        let indent: String = before
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let end = d.span.end.min(line_end).max(d.span.start);
+        let width = self.source[d.span.start..end].chars().count();
+        let mut underline = String::from("^");
+        for _ in 1..width {
+            underline.push('~');
+        }
+        writeln!(
+            out,
+            "{} | {}{}{}{}",
+            gutter, indent, caret, underline, reset
+        )
+        .unwrap();
+
+        for note in &d.notes {
+            self.render_to(out, note);
+        }
+    }
+
+    /// JSON object with the severity, message, file range and include chain
+    ///
+    /// ```json
+    /// {"severity":"error","message":"...","file":"main.c",
+    ///  "start":{"line":1,"column":11,"offset":24},"end":{"line":1,"column":12,"offset":25},
+    ///  "includes":[{"file":"...","line":2}],"notes":[...]}
+    /// ```
+    ///
+    /// `offset` is the byte offset in the preprocessed source. The end position is exclusive.
+    /// The file and positions are left out for diagnostics without a location.
+    pub fn json(&self, d: &Diagnostic) -> String {
+        let mut out = String::new();
+        self.json_to(&mut out, d);
+        out
+    }
+
+    fn json_to(&self, out: &mut String, d: &Diagnostic) {
+        out.push_str("{\"severity\":");
+        json_string(out, d.severity.name());
+        out.push_str(",\"message\":");
+        json_string(out, &d.message);
+        let includes = if d.span.is_none() {
+            Vec::new()
+        } else {
+            let range = self.map.range(d.span);
+            out.push_str(",\"file\":");
+            json_string(out, range.start.file);
+            for &(name, l, offset) in &[
+                ("start", range.start, d.span.start),
+                ("end", range.end, d.span.end),
+            ] {
+                write!(
+                    out,
+                    ",\"{}\":{{\"line\":{},\"column\":{},\"offset\":{}}}",
+                    name, l.line, l.column, offset
+                )
+                .unwrap();
+            }
+            self.map.includes(d.span.start)
+        };
+        out.push_str(",\"includes\":[");
+        for (i, l) in includes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"file\":");
+            json_string(out, l.file);
+            write!(out, ",\"line\":{}}}", l.line).unwrap();
+        }
+        out.push_str("],\"notes\":[");
+        for (i, note) in d.notes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            self.json_to(out, note);
+        }
+        out.push_str("]}");
+    }
+}
+
+/// Write a JSON string literal
+fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+fn rendered(source: &str, d: &Diagnostic, json: bool) -> String {
+    let map = SourceMap::new(source);
+    let emitter = Emitter::new(source, &map);
+    match json {
+        true => emitter.json(d),
+        false => emitter.render(d),
+    }
+}
+
+#[test]
+fn test_syntax_error() {
+    use driver::{parse_preprocessed, Config};
+
+    let source = "int f(void) {\n\treturn 1 foo;\n}\n";
+    let error = parse_preprocessed(&Config::default(), source.into()).unwrap_err();
+    let d = Diagnostic::from(&error);
+    assert_eq!(d.span, Span::span(24, 27));
+    assert!(d.message.starts_with("unexpected token, expected "));
+    assert!(d.message.contains("';'"));
+    let text = rendered(source, &d, false);
+    assert!(text.ends_with("\n   2 | \treturn 1 foo;\n     | \t         ^~~\n"));
+    assert!(text.starts_with(":2:11: error: unexpected token"));
+
+    // error at the end of input
+    let source = "int x";
+    let error = parse_preprocessed(&Config::default(), source.into()).unwrap_err();
+    let d = Diagnostic::from(&error);
+    assert_eq!(d.span, Span::span(5, 5));
+    assert!(rendered(source, &d, false).ends_with("   1 | int x\n     |      ^\n"));
+}
+
+#[test]
+fn test_includes() {
+    let source = "# 1 \"a.c\"\n# 1 \"b.h\" 1\n# 1 \"c.h\" 1\nint \"x\";\n# 3 \"a.c\" 2\n";
+    let start = source.find("\"x\"").unwrap();
+    let d = Diagnostic::new(Severity::Warning, "odd", Span::span(start, start + 3))
+        .with_note("here", Span::span(start + 3, start + 4));
+    assert_eq!(
+        rendered(source, &d, false),
+        concat!(
+            "In file included from b.h:1,\n",
+            "                 from a.c:1:\n",
+            "c.h:1:5: warning: odd\n",
+            "   1 | int \"x\";\n",
+            "     |     ^~~\n",
+            "In file included from b.h:1,\n",
+            "                 from a.c:1:\n",
+            "c.h:1:8: note: here\n",
+            "   1 | int \"x\";\n",
+            "     |        ^\n",
+        )
+    );
+
+    let map = SourceMap::new(source);
+    let mut emitter = Emitter::new(source, &map);
+    emitter.color = true;
+    assert!(emitter
+        .render(&d)
+        .contains("\x1b[1mc.h:1:5:\x1b[0m \x1b[1;35mwarning:\x1b[0m odd\n"));
+
+    assert_eq!(
+        rendered(source, &d, true),
+        concat!(
+            r#"{"severity":"warning","message":"odd","file":"c.h","#,
+            r#""start":{"line":1,"column":5,"offset":38},"#,
+            r#""end":{"line":1,"column":8,"offset":41},"#,
+            r#""includes":[{"file":"a.c","line":1},{"file":"b.h","line":1}],"#,
+            r#""notes":[{"severity":"note","message":"here","file":"c.h","#,
+            r#""start":{"line":1,"column":8,"offset":41},"#,
+            r#""end":{"line":1,"column":9,"offset":42},"#,
+            r#""includes":[{"file":"a.c","line":1},{"file":"b.h","line":1}],"notes":[]}]}"#,
+        )
+    );
+}
+
+#[test]
+fn test_no_location() {
+    let d = Diagnostic::error("bad", Span::none()).with_note("here", Span::span(0, 3));
+    assert_eq!(
+        rendered("int x;\n", &d, false),
+        "error: bad\n:1:1: note: here\n   1 | int x;\n     | ^~~\n"
+    );
+    assert_eq!(
+        rendered("int x;\n", &Diagnostic::error("bad", Span::none()), true),
+        r#"{"severity":"error","message":"bad","includes":[],"notes":[]}"#
+    );
+}
+
+#[test]
+fn test_json_string() {
+    let mut out = String::new();
+    json_string(&mut out, "a\"b\\c\n\u{1}é");
+    assert_eq!(out, r#""a\"b\\c\n\u0001é""#);
+}
@@ -1,16 +1,23 @@
 //! Preprocess and parse C source file into an abstract syntax tree
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::error;
 use std::fmt;
 use std::io;
 use std::path::Path;
 use std::process::Command;
 
-use ast::TranslationUnit;
-use env::Env;
+use ast::{
+    BlockItem, Declaration, Expression, ExternalDeclaration, Statement, TranslationUnit, TypeName,
+};
+use cpp::Preprocessor;
+use env::{Env, Recovery};
 use loc;
-use parser::translation_unit;
+use parser::{self, block_items, translation_unit, ParseError};
+use span::{Node, Span};
+use visit::{self, Visit};
+
+pub use env::Symbol;
 
 /// Parser configuration
 #[derive(Clone, Debug)]
@@ -21,6 +28,10 @@ pub struct Config {
     pub cpp_options: Vec<String>,
     /// Language flavor to parse
     pub flavor: Flavor,
+    /// How to run the preprocessor
+    pub cpp_mode: CppMode,
+    /// Identifiers to treat as typedef names before parsing
+    pub typedef_names: Vec<String>,
 }
 
 impl Config {
@@ -30,6 +41,8 @@ impl Config {
             cpp_command: "gcc".into(),
             cpp_options: vec!["-E".into()],
             flavor: Flavor::GnuC11,
+            cpp_mode: CppMode::External,
+            typedef_names: Vec::new(),
         }
     }
 
@@ -39,6 +52,36 @@ impl Config {
             cpp_command: "clang".into(),
             cpp_options: vec!["-E".into()],
             flavor: Flavor::ClangC11,
+            cpp_mode: CppMode::External,
+            typedef_names: Vec::new(),
+        }
+    }
+
+    /// Use `cl.exe` as a pre-processor and enable MSVC extensions
+    ///
+    /// Any program that accepts the same options and writes preprocessed source to standard
+    /// output can be used instead by changing `cpp_command`.
+    pub fn with_msvc() -> Config {
+        Config {
+            cpp_command: "cl.exe".into(),
+            cpp_options: vec!["/nologo".into(), "/E".into()],
+            flavor: Flavor::MsvcC11,
+            cpp_mode: CppMode::External,
+            typedef_names: Vec::new(),
+        }
+    }
+
+    /// Use the built-in preprocessor and enable gcc extensions
+    ///
+    /// `cpp_command` is not used in this mode. Include paths and macro definitions are taken from
+    /// `cpp_options` (`-I`, `-iquote`, `-isystem`, `-D` and `-U`).
+    pub fn with_builtin() -> Config {
+        Config {
+            cpp_command: String::new(),
+            cpp_options: Vec::new(),
+            flavor: Flavor::GnuC11,
+            cpp_mode: CppMode::Builtin,
+            typedef_names: Vec::new(),
         }
     }
 }
@@ -64,6 +107,21 @@ pub enum Flavor {
     GnuC11,
     /// Standard C11 with Clang extensions
     ClangC11,
+    /// Standard C11 with MSVC extensions
+    MsvcC11,
+    /// Strict standard C23
+    StdC23,
+    /// Standard C23 with GNU extensions
+    GnuC23,
+}
+
+/// Preprocessor implementations
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CppMode {
+    /// Run an external program specified by `cpp_command`
+    External,
+    /// Use the preprocessor from the `cpp` module
+    Builtin,
 }
 
 /// Result of a successful parse
@@ -71,8 +129,50 @@ pub enum Flavor {
 pub struct Parse {
     /// Pre-processed source text
     pub source: String,
+    /// Locations of offsets in `source` in the original files
+    pub source_map: loc::SourceMap,
     /// Root of the abstract syntax tree
     pub unit: TranslationUnit,
+    /// Ordinary identifiers declared at file scope
+    ///
+    /// Includes typedef names from `Config::typedef_names` and names predefined by the language
+    /// flavor.
+    pub symbols: HashMap<String, Symbol>,
+}
+
+impl Parse {
+    /// Typedef names visible at the end of the translation unit, sorted
+    ///
+    /// The result can be added to `Config::typedef_names` to parse code fragments that use types
+    /// declared in this translation unit.
+    pub fn typedef_names(&self) -> Vec<String> {
+        typedef_names(&self.symbols)
+    }
+}
+
+/// Result of a parse with error recovery
+#[derive(Clone, Debug)]
+pub struct RecoveredParse {
+    /// Pre-processed source text
+    pub source: String,
+    /// Locations of offsets in `source` in the original files
+    pub source_map: loc::SourceMap,
+    /// Root of the abstract syntax tree
+    ///
+    /// Parts of the input that failed to parse are replaced with `ExternalDeclaration::Error` and
+    /// `BlockItem::Error` nodes.
+    pub unit: TranslationUnit,
+    /// All syntax errors found, in the order of their appearance in the source
+    pub errors: Vec<SyntaxError>,
+    /// Ordinary identifiers declared at file scope, see `Parse::symbols`
+    pub symbols: HashMap<String, Symbol>,
+}
+
+impl RecoveredParse {
+    /// Typedef names visible at the end of the translation unit, sorted
+    pub fn typedef_names(&self) -> Vec<String> {
+        typedef_names(&self.symbols)
+    }
 }
 
 #[derive(Debug)]
@@ -168,28 +268,231 @@ pub fn parse<P: AsRef<Path>>(config: &Config, source: P) -> Result<Parse, Error>
 }
 
 pub fn parse_preprocessed(config: &Config, source: String) -> Result<Parse, SyntaxError> {
-    let mut env = match config.flavor {
-        Flavor::StdC11 => Env::with_core(),
-        Flavor::GnuC11 => Env::with_gnu(),
-        Flavor::ClangC11 => Env::with_clang(),
-    };
+    let mut env = make_env(config);
 
     match translation_unit(&source, &mut env) {
         Ok(unit) => Ok(Parse {
+            source_map: loc::SourceMap::new(&source),
             source: source,
             unit: unit,
+            symbols: file_symbols(env),
         }),
-        Err(err) => Err(SyntaxError {
-            source: source,
-            line: err.line,
-            column: err.column,
-            offset: err.offset,
-            expected: err.expected,
-        }),
+        Err(err) => Err(syntax_error(source, err)),
+    }
+}
+
+/// Parse an expression
+///
+/// Source text is not preprocessed. Typedef names used in the expression must be listed in
+/// `config.typedef_names`.
+pub fn parse_expression(config: &Config, source: &str) -> Result<Node<Expression>, SyntaxError> {
+    match parser::expression_fragment(source, &mut make_env(config)) {
+        Ok(e) => Ok(*e),
+        Err(err) => Err(syntax_error(source.to_owned(), err)),
+    }
+}
+
+/// Parse a statement
+///
+/// Source text is not preprocessed. Typedef names used in the statement must be listed in
+/// `config.typedef_names`.
+pub fn parse_statement(config: &Config, source: &str) -> Result<Node<Statement>, SyntaxError> {
+    match parser::statement_fragment(source, &mut make_env(config)) {
+        Ok(s) => Ok(*s),
+        Err(err) => Err(syntax_error(source.to_owned(), err)),
+    }
+}
+
+/// Parse a declaration, including the terminating semicolon
+///
+/// Source text is not preprocessed. Typedef names used in the declaration must be listed in
+/// `config.typedef_names`.
+pub fn parse_declaration(config: &Config, source: &str) -> Result<Node<Declaration>, SyntaxError> {
+    match parser::declaration_fragment(source, &mut make_env(config)) {
+        Ok(d) => Ok(d),
+        Err(err) => Err(syntax_error(source.to_owned(), err)),
+    }
+}
+
+/// Parse a type name, as used in casts and `sizeof`
+///
+/// Source text is not preprocessed. Typedef names used in the type name must be listed in
+/// `config.typedef_names`.
+pub fn parse_type_name(config: &Config, source: &str) -> Result<Node<TypeName>, SyntaxError> {
+    match parser::type_name_fragment(source, &mut make_env(config)) {
+        Ok(t) => Ok(t),
+        Err(err) => Err(syntax_error(source.to_owned(), err)),
+    }
+}
+
+fn syntax_error(source: String, err: ParseError) -> SyntaxError {
+    SyntaxError {
+        source,
+        line: err.line,
+        column: err.column,
+        offset: err.offset,
+        expected: err.expected,
+    }
+}
+
+/// Parse a C file, recovering from syntax errors
+///
+/// After a syntax error, parser skips input up to the next `;` or to the end of a `{ ... }` block
+/// and continues with the next external declaration or block item. Only preprocessor errors
+/// are returned as `Err`.
+pub fn parse_recovering<P: AsRef<Path>>(
+    config: &Config,
+    source: P,
+) -> Result<RecoveredParse, Error> {
+    let processed = match preprocess(config, source.as_ref()) {
+        Ok(s) => s,
+        Err(e) => return Err(Error::PreprocessorError(e)),
+    };
+
+    Ok(parse_preprocessed_recovering(config, processed))
+}
+
+pub fn parse_preprocessed_recovering(config: &Config, source: String) -> RecoveredParse {
+    let mut env = make_env(config);
+    env.recovery = true;
+
+    let unit = match translation_unit(&source, &mut env) {
+        Ok(unit) => unit,
+        Err(err) => {
+            let error = syntax_error(source.clone(), err);
+            return RecoveredParse {
+                source_map: loc::SourceMap::new(&source),
+                source,
+                unit: TranslationUnit(Vec::new()),
+                errors: vec![error],
+                symbols: file_symbols(env),
+            };
+        }
+    };
+
+    // Parser may have backtracked over some of the recovered input, keep only the parts that
+    // ended up in the tree.
+    let mut starts = HashSet::new();
+    ErrorNodes(&mut starts).visit_translation_unit(&unit);
+
+    let mut recoveries: Vec<Recovery> = Vec::new();
+    for r in env.recoveries.drain(..) {
+        if !starts.contains(&r.span.start) {
+            continue;
+        }
+        recoveries.retain(|p| p.span.start != r.span.start);
+        recoveries.push(r);
+    }
+    recoveries.sort_by_key(|r| r.span.start);
+
+    let errors = recoveries
+        .into_iter()
+        .map(|r| recovery_error(config, &source, r))
+        .collect();
+
+    RecoveredParse {
+        source_map: loc::SourceMap::new(&source),
+        source,
+        unit,
+        errors,
+        symbols: file_symbols(env),
+    }
+}
+
+/// Find the exact error location by parsing the skipped input again, without recovery
+fn recovery_error(config: &Config, source: &str, recovery: Recovery) -> SyntaxError {
+    let mut env = make_env(config);
+    env.symbols = recovery.symbols;
+
+    let start = recovery.span.start;
+    let text = &source[start..recovery.span.end];
+    let result = if recovery.block_item {
+        block_items(text, &mut env).map(|_| ())
+    } else {
+        translation_unit(text, &mut env).map(|_| ())
+    };
+    let (offset, expected) = match result {
+        Ok(()) => (start, HashSet::new()),
+        Err(err) => (start + err.offset, err.expected),
+    };
+
+    let before = &source[..offset];
+    SyntaxError {
+        source: source.to_owned(),
+        line: before.matches('\n').count() + 1,
+        column: before.chars().rev().take_while(|&c| c != '\n').count() + 1,
+        offset,
+        expected,
+    }
+}
+
+fn make_env(config: &Config) -> Env {
+    let mut env = match config.flavor {
+        Flavor::StdC11 => Env::with_core(),
+        Flavor::GnuC11 => Env::with_gnu(),
+        Flavor::ClangC11 => Env::with_clang(),
+        Flavor::MsvcC11 => Env::with_msvc(),
+        Flavor::StdC23 => Env::with_core(),
+        Flavor::GnuC23 => Env::with_gnu(),
+    };
+    match config.flavor {
+        Flavor::StdC23 | Flavor::GnuC23 => env.enable_c23(),
+        _ => {}
+    }
+    for name in &config.typedef_names {
+        env.add_symbol(name, Symbol::Typename);
+    }
+    env
+}
+
+fn file_symbols(mut env: Env) -> HashMap<String, Symbol> {
+    env.symbols.truncate(1);
+    env.symbols.pop().unwrap_or_default()
+}
+
+fn typedef_names(symbols: &HashMap<String, Symbol>) -> Vec<String> {
+    let mut names: Vec<String> = symbols
+        .iter()
+        .filter(|&(_, &s)| s == Symbol::Typename)
+        .map(|(n, _)| n.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Collects start offsets of error nodes
+struct ErrorNodes<'a>(&'a mut HashSet<usize>);
+
+impl<'a, 'ast> Visit<'ast> for ErrorNodes<'a> {
+    fn visit_external_declaration(&mut self, n: &'ast ExternalDeclaration, span: &'ast Span) {
+        if let ExternalDeclaration::Error = *n {
+            self.0.insert(span.start);
+        }
+        visit::visit_external_declaration(self, n, span);
+    }
+
+    fn visit_block_item(&mut self, n: &'ast BlockItem, span: &'ast Span) {
+        if let BlockItem::Error = *n {
+            self.0.insert(span.start);
+        }
+        visit::visit_block_item(self, n, span);
     }
 }
 
 fn preprocess(config: &Config, source: &Path) -> io::Result<String> {
+    match config.cpp_mode {
+        CppMode::External => preprocess_external(config, source),
+        CppMode::Builtin => preprocess_builtin(config, source),
+    }
+}
+
+fn preprocess_builtin(config: &Config, source: &Path) -> io::Result<String> {
+    let mut cpp = Preprocessor::new(config.flavor);
+    try!(cpp.apply_options(&config.cpp_options));
+    cpp.preprocess_file(source)
+}
+
+fn preprocess_external(config: &Config, source: &Path) -> io::Result<String> {
     let mut cmd = Command::new(&config.cpp_command);
 
     for item in &config.cpp_options {
@@ -215,3 +518,118 @@ fn preprocess(config: &Config, source: &Path) -> io::Result<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery() {
+        let config = Config::with_gcc();
+        let source = "int a;\nint b = ;\nvoid f() {\n  x = 1 +;\n  int c;\n  {\n}\n}\nstruct s { int x y; };\nint d;\n";
+        let parse = parse_preprocessed_recovering(&config, source.into());
+
+        let kinds: Vec<_> = parse
+            .unit
+            .0
+            .iter()
+            .map(|d| match d.node {
+                ExternalDeclaration::Declaration(_) => "decl",
+                ExternalDeclaration::StaticAssert(_) => "assert",
+                ExternalDeclaration::FunctionDefinition(_) => "fn",
+                ExternalDeclaration::Asm(_) => "asm",
+                ExternalDeclaration::Pragma(_) => "pragma",
+                ExternalDeclaration::Error => "error",
+            })
+            .collect();
+        assert_eq!(kinds, ["decl", "error", "fn", "error", "decl"]);
+
+        let errors: Vec<_> = parse.errors.iter().map(|e| (e.line, e.column)).collect();
+        assert_eq!(errors, [(2, 9), (4, 10), (9, 18)]);
+        assert!(parse.errors[1].expected.contains("("));
+
+        let parse = parse_preprocessed_recovering(&config, "int a;\n".into());
+        assert!(parse.errors.is_empty());
+    }
+
+    #[test]
+    fn test_fragments() {
+        let mut config = Config::with_gcc();
+        assert!(parse_declaration(&config, "size_t x;").is_err());
+
+        config.typedef_names.push("size_t".into());
+        let d = parse_declaration(&config, " size_t x;\n").unwrap();
+        assert_eq!(d.span.start, 1);
+        assert_eq!(d.node.declarators.len(), 1);
+
+        let t = parse_type_name(&config, "const size_t *").unwrap();
+        assert_eq!(t.node.specifiers.len(), 2);
+
+        let e = parse_expression(&config, "(size_t) 1 + sizeof(x)").unwrap();
+        match e.node {
+            Expression::BinaryOperator(_) => {}
+            _ => panic!("expected binary operator"),
+        }
+
+        assert!(parse_statement(&config, "if (a) return b;").is_ok());
+
+        let err = parse_expression(&config, "a +\n  ;").unwrap_err();
+        assert_eq!((err.line, err.column, err.offset), (2, 3, 6));
+    }
+
+    #[test]
+    fn test_symbols() {
+        let mut config = Config::with_gcc();
+        config.typedef_names.push("FILE".into());
+        let source = "typedef unsigned int uint32_t; FILE *f; int main(void) { typedef int T; }";
+        let parse = parse_preprocessed(&config, source.into()).unwrap();
+        assert_eq!(parse.symbols.get("f"), Some(&Symbol::Identifier));
+        assert_eq!(parse.symbols.get("main"), Some(&Symbol::Identifier));
+        assert_eq!(parse.symbols.get("T"), None);
+        assert_eq!(
+            parse.typedef_names(),
+            ["FILE", "__builtin_va_list", "uint32_t"]
+        );
+
+        config.typedef_names = parse.typedef_names();
+        assert!(parse_expression(&config, "(uint32_t) y").is_ok());
+
+        let source = "typedef int T; int T(void) { return 0; } int x = T;";
+        let parse = parse_preprocessed_recovering(&config, source.into());
+        assert!(parse.errors.is_empty());
+        assert_eq!(parse.symbols.get("T"), Some(&Symbol::Identifier));
+    }
+
+    #[test]
+    fn test_pragma_spans() {
+        let source = "# 2 \"a.h\"\n#pragma pack(push, 1)\n_Pragma(\"a \\\"b\\\"\")\n";
+        let parse = parse_preprocessed(&Config::with_gcc(), source.into()).unwrap();
+        let pragmas: Vec<_> = parse
+            .unit
+            .0
+            .iter()
+            .map(|d| match d.node {
+                ExternalDeclaration::Pragma(ref p) => p,
+                ref d => panic!("not a pragma: {:?}", d),
+            })
+            .collect();
+        let text = |i: usize| -> Vec<&str> {
+            pragmas[i]
+                .node
+                .tokens
+                .iter()
+                .map(|t| &source[t.span.start..t.span.end])
+                .collect()
+        };
+
+        assert_eq!(text(0), ["pack", "(", "push", ",", "1", ")"]);
+        let loc = parse
+            .source_map
+            .location(pragmas[0].node.tokens[2].span.start);
+        assert_eq!((loc.file, loc.line, loc.column), ("a.h", 2, 14));
+
+        // Tokens of the operator point inside the string literal
+        assert_eq!(text(1), ["a", "\\\"b\\\""]);
+        assert_eq!(pragmas[1].node.tokens[1].node, "\"b\"");
+    }
+}
@@ -1,20 +1,36 @@
 use std::collections::{HashMap, HashSet};
 
 use ast::*;
-use span::Node;
+use span::{Node, Span};
 use strings;
 
-#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+/// Kind of an ordinary identifier, as far as the parser is concerned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Symbol {
+    /// Typedef name
     Typename,
+    /// Object, function or enumeration constant
     Identifier,
 }
 
+/// Part of the input skipped during error recovery
+pub struct Recovery {
+    pub span: Span,
+    /// Symbol table at the start of the skipped input
+    pub symbols: Vec<HashMap<String, Symbol>>,
+    /// Input was skipped inside a compound statement
+    pub block_item: bool,
+}
+
 pub struct Env {
     pub symbols: Vec<HashMap<String, Symbol>>,
     pub extensions_gnu: bool,
     pub extensions_clang: bool,
+    pub extensions_msvc: bool,
+    pub c23: bool,
     pub reserved: HashSet<&'static str>,
+    pub recovery: bool,
+    pub recoveries: Vec<Recovery>,
 }
 
 impl Env {
@@ -24,8 +40,12 @@ impl Env {
         Env {
             extensions_gnu: false,
             extensions_clang: false,
+            extensions_msvc: false,
+            c23: false,
             symbols: vec![HashMap::default()],
             reserved: reserved,
+            recovery: false,
+            recoveries: Vec::new(),
         }
     }
 
@@ -38,8 +58,12 @@ impl Env {
         Env {
             extensions_gnu: true,
             extensions_clang: false,
+            extensions_msvc: false,
+            c23: false,
             symbols: vec![symbols],
             reserved: reserved,
+            recovery: false,
+            recoveries: Vec::new(),
         }
     }
 
@@ -53,11 +77,37 @@ impl Env {
         Env {
             extensions_gnu: true,
             extensions_clang: true,
+            extensions_msvc: false,
+            c23: false,
             symbols: vec![symbols],
             reserved: reserved,
+            recovery: false,
+            recoveries: Vec::new(),
+        }
+    }
+
+    pub fn with_msvc() -> Env {
+        let mut reserved = HashSet::default();
+        reserved.extend(strings::RESERVED_C11.iter());
+        reserved.extend(strings::RESERVED_MSVC.iter());
+        Env {
+            extensions_gnu: false,
+            extensions_clang: false,
+            extensions_msvc: true,
+            c23: false,
+            symbols: vec![HashMap::default()],
+            reserved,
+            recovery: false,
+            recoveries: Vec::new(),
         }
     }
 
+    /// Enable C23 keywords and syntax
+    pub fn enable_c23(&mut self) {
+        self.c23 = true;
+        self.reserved.extend(strings::RESERVED_C23.iter());
+    }
+
     pub fn enter_scope(&mut self) {
         self.symbols.push(HashMap::new());
     }
@@ -89,6 +139,19 @@ impl Env {
         scope.insert(s.to_string(), symbol);
     }
 
+    pub fn add_recovery(
+        &mut self,
+        span: Span,
+        symbols: Vec<HashMap<String, Symbol>>,
+        block_item: bool,
+    ) {
+        self.recoveries.push(Recovery {
+            span,
+            symbols,
+            block_item,
+        });
+    }
+
     #[cfg(test)]
     pub fn add_typename(&mut self, s: &str) {
         self.add_symbol(s, Symbol::Typename)
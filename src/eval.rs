@@ -0,0 +1,1028 @@
+//! Integer constant expression evaluation
+//!
+//! Computes values of integer constant expressions (C11 6.6) such as enumerator values, array
+//! sizes and `case` labels.
+//!
+//! ```
+//! # use lang_c::driver::{parse_expression, Config};
+//! # use lang_c::eval::{Evaluator, IntegerType};
+//! # use lang_c::layout::{DataModel, Target};
+//! let expr = parse_expression(&Config::default(), "sizeof(long) * 8 - 1u").unwrap();
+//! let value = Evaluator::new(Target::new(DataModel::LP64)).evaluate(&expr).unwrap();
+//! assert_eq!(value.ty, IntegerType::UnsignedLong);
+//! assert_eq!(value.value, 63);
+//! ```
+//!
+//! Sizes of types are computed by the `layout` module for the selected target. Structure tags
+//! and typedefs used in `sizeof` and `_Alignof` must be registered with
+//! `Evaluator::add_declaration` first.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::rc::Rc;
+
+use ast::*;
+use layout::{self, Layout, Target};
+use literal::{BinaryFormat, CharacterValue, Encoding, FloatValue, IntegerValue, StringValue};
+use span::{Node, Span};
+use types::CType;
+
+/// Standard integer types
+///
+/// (C11 6.2.5)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum IntegerType {
+    Bool,
+    Char,
+    SignedChar,
+    UnsignedChar,
+    Short,
+    UnsignedShort,
+    Int,
+    UnsignedInt,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+}
+
+impl IntegerType {
+    /// Integer conversion rank (C11 6.3.1.1)
+    pub fn rank(&self) -> u8 {
+        match *self {
+            IntegerType::Bool => 0,
+            IntegerType::Char | IntegerType::SignedChar | IntegerType::UnsignedChar => 1,
+            IntegerType::Short | IntegerType::UnsignedShort => 2,
+            IntegerType::Int | IntegerType::UnsignedInt => 3,
+            IntegerType::Long | IntegerType::UnsignedLong => 4,
+            IntegerType::LongLong | IntegerType::UnsignedLongLong => 5,
+        }
+    }
+
+    /// Unsigned type with the same rank
+    pub fn to_unsigned(&self) -> IntegerType {
+        match *self {
+            IntegerType::Char | IntegerType::SignedChar => IntegerType::UnsignedChar,
+            IntegerType::Short => IntegerType::UnsignedShort,
+            IntegerType::Int => IntegerType::UnsignedInt,
+            IntegerType::Long => IntegerType::UnsignedLong,
+            IntegerType::LongLong => IntegerType::UnsignedLongLong,
+            ty => ty,
+        }
+    }
+}
+
+/// Value of an integer constant expression
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Value {
+    /// Type of the expression
+    pub ty: IntegerType,
+    /// Mathematical value, always within the range of `ty`
+    pub value: i128,
+}
+
+impl Value {
+    /// Create a value of type `int`
+    pub fn int(value: i128) -> Value {
+        Value {
+            ty: IntegerType::Int,
+            value,
+        }
+    }
+
+    /// Test if value compares unequal to zero
+    pub fn is_true(&self) -> bool {
+        self.value != 0
+    }
+}
+
+/// Reason why an expression could not be evaluated
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum EvalErrorKind {
+    /// Expression is not allowed in an integer constant expression
+    NotConstant,
+    /// Identifier does not name an enumeration constant
+    UnknownIdentifier(String),
+    /// Operand does not have integer type
+    NotInteger,
+    /// Literal is too large for any integer type or is malformed
+    InvalidLiteral,
+    /// Division or remainder by zero
+    DivisionByZero,
+    /// Result of a signed operation or conversion does not fit in its type
+    Overflow,
+    /// Shift count is negative or not less than the width of the type, or a negative value is
+    /// shifted left
+    InvalidShift,
+    /// Size or alignment of this type can not be computed
+    UnsupportedType,
+    /// Type is incomplete or names an unknown tag or typedef
+    IncompleteType,
+    /// Bit-field width is negative or exceeds the width of its type
+    InvalidBitWidth,
+    /// Alignment is not a non-negative power of two
+    InvalidAlignment,
+    /// Size of an array type in bits does not fit in 64 bits
+    TooLarge,
+}
+
+/// Error evaluating a constant expression
+#[derive(Debug, PartialEq, Clone)]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    /// Offending sub-expression or type
+    pub span: Span,
+}
+
+impl EvalError {
+    pub fn new(kind: EvalErrorKind, span: Span) -> EvalError {
+        EvalError { kind, span }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            EvalErrorKind::NotConstant => write!(fmt, "not an integer constant expression"),
+            EvalErrorKind::UnknownIdentifier(ref name) => {
+                write!(fmt, "'{}' is not an enumeration constant", name)
+            }
+            EvalErrorKind::NotInteger => write!(fmt, "operand does not have integer type"),
+            EvalErrorKind::InvalidLiteral => write!(fmt, "invalid integer literal"),
+            EvalErrorKind::DivisionByZero => write!(fmt, "division by zero"),
+            EvalErrorKind::Overflow => write!(fmt, "integer overflow"),
+            EvalErrorKind::InvalidShift => write!(fmt, "invalid shift"),
+            EvalErrorKind::UnsupportedType => write!(fmt, "size of type can not be computed"),
+            EvalErrorKind::IncompleteType => write!(fmt, "incomplete type"),
+            EvalErrorKind::InvalidBitWidth => write!(fmt, "invalid bit-field width"),
+            EvalErrorKind::InvalidAlignment => write!(fmt, "invalid alignment"),
+            EvalErrorKind::TooLarge => write!(fmt, "type is too large"),
+        }
+    }
+}
+
+impl error::Error for EvalError {
+    fn description(&self) -> &str {
+        "constant expression error"
+    }
+}
+
+type Result<T> = ::std::result::Result<T, EvalError>;
+
+/// Integer constant expression evaluator
+///
+/// Holds the target description and the enumeration constants, structure tags and typedefs
+/// visible in the current scope.
+#[derive(Debug, Clone)]
+pub struct Evaluator {
+    target: Target,
+    scopes: Vec<Scope>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    constants: HashMap<String, Value>,
+    /// Layout of the tag and the compatible integer type of an enumeration
+    tags: HashMap<String, (Layout, Option<IntegerType>)>,
+    typedefs: HashMap<String, Typedef>,
+}
+
+#[derive(Debug, Clone)]
+enum Typedef {
+    Layout(Layout, Option<IntegerType>),
+    /// Declaration and index of the declarator, the layout is computed on use so that tags
+    /// completed after the typedef are taken into account
+    Declaration(Rc<Node<Declaration>>, usize),
+}
+
+impl Evaluator {
+    pub fn new(target: Target) -> Evaluator {
+        Evaluator {
+            target,
+            scopes: vec![Scope::default()],
+        }
+    }
+
+    pub fn target(&self) -> &Target {
+        &self.target
+    }
+
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    pub fn leave_scope(&mut self) {
+        self.scopes.pop().expect("more scope pops than pushes");
+    }
+
+    /// Define an enumeration constant in the innermost scope
+    pub fn add_constant(&mut self, name: &str, value: Value) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .constants
+            .insert(name.to_owned(), value);
+    }
+
+    /// Find an enumeration constant by name
+    pub fn constant(&self, name: &str) -> Option<Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|s| s.constants.get(name))
+            .next()
+            .cloned()
+    }
+
+    /// Define a structure, union or enumeration tag in the innermost scope
+    pub fn add_tag(&mut self, name: &str, layout: Layout) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .tags
+            .insert(name.to_owned(), (layout, None));
+    }
+
+    /// Find the layout of a tagged type by tag name
+    pub fn tag(&self, name: &str) -> Option<&Layout> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|s| s.tags.get(name))
+            .next()
+            .map(|t| &t.0)
+    }
+
+    /// Find the integer type compatible with an enumerated type by tag name
+    ///
+    /// Returns `None` if the tag is not defined or names a structure or union.
+    pub fn enum_tag(&self, name: &str) -> Option<IntegerType> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|s| s.tags.get(name))
+            .next()
+            .and_then(|t| t.1)
+    }
+
+    /// Define a typedef name in the innermost scope
+    ///
+    /// `integer` is the integer type the name refers to, if any, so that it can be used in casts.
+    pub fn add_typedef(&mut self, name: &str, layout: Layout, integer: Option<IntegerType>) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .typedefs
+            .insert(name.to_owned(), Typedef::Layout(layout, integer));
+    }
+
+    /// Find the layout and integer type of a typedef name
+    ///
+    /// Returns an error if the typedef refers to a type that is still incomplete.
+    pub fn typedef(&self, name: &str) -> Option<Result<(Layout, Option<IntegerType>)>> {
+        let typedef = self
+            .scopes
+            .iter()
+            .rev()
+            .filter_map(|s| s.typedefs.get(name))
+            .next();
+        match typedef {
+            Some(&Typedef::Layout(ref layout, ty)) => Some(Ok((layout.clone(), ty))),
+            Some(&Typedef::Declaration(ref n, index)) => Some(self.typedef_layout(n, index)),
+            None => None,
+        }
+    }
+
+    /// Add tags, enumeration constants and typedef names defined by a declaration
+    ///
+    /// Structures defined inside other structures are added too, like C scoping rules require.
+    /// Declarations of objects and functions are ignored.
+    pub fn add_declaration(&mut self, n: &Node<Declaration>) -> Result<()> {
+        let extensions = declaration_extensions(n);
+        let mut typedef = false;
+        for s in &n.node.specifiers {
+            match s.node {
+                DeclarationSpecifier::StorageClass(ref s) => {
+                    typedef |= s.node == StorageClassSpecifier::Typedef;
+                }
+                DeclarationSpecifier::TypeSpecifier(ref t) => {
+                    try!(self.add_type_specifier(t, &extensions));
+                }
+                _ => {}
+            }
+        }
+        if !typedef {
+            return Ok(());
+        }
+
+        let declaration = Rc::new(n.clone());
+        for (i, d) in n.node.declarators.iter().enumerate() {
+            if let Some(name) = declarator_identifier(&d.node.declarator.node) {
+                self.scopes.last_mut().unwrap().typedefs.insert(
+                    name.node.name.clone(),
+                    Typedef::Declaration(declaration.clone(), i),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Layout of the type declared by a typedef declarator
+    fn typedef_layout(
+        &self,
+        n: &Node<Declaration>,
+        index: usize,
+    ) -> Result<(Layout, Option<IntegerType>)> {
+        let extensions = declaration_extensions(n);
+        let specifiers: Vec<_> = n
+            .node
+            .specifiers
+            .iter()
+            .filter_map(|s| match s.node {
+                DeclarationSpecifier::TypeSpecifier(ref t) => Some(t),
+                _ => None,
+            })
+            .collect();
+        let base = layout::base_type(self, &specifiers, &extensions, n.span);
+        let integer = base.as_ref().ok().and_then(|&(_, ty)| ty);
+
+        let declarator = &n.node.declarators[index].node.declarator;
+        let base = base.map(|(l, _)| l);
+        let mut layout = try!(layout::declarator_layout(self, base, declarator, false));
+        let attributes = try!(layout::attributes(self, declarator.node.extensions.iter()));
+        if let Some(align) = attributes.aligned {
+            layout.align = align;
+        }
+        let integer = match declarator.node.kind.node {
+            DeclaratorKind::Identifier(_) if declarator.node.derived.is_empty() => integer,
+            _ => None,
+        };
+        Ok((layout, integer))
+    }
+
+    /// Add tags and enumeration constants defined in a type specifier
+    fn add_type_specifier(
+        &mut self,
+        n: &Node<TypeSpecifier>,
+        extensions: &[&Node<Extension>],
+    ) -> Result<()> {
+        match n.node {
+            TypeSpecifier::Struct(ref s) => {
+                let declarations = match s.node.declarations {
+                    Some(ref d) => d,
+                    None => return Ok(()),
+                };
+                for d in declarations {
+                    if let StructDeclaration::Field(ref f) = d.node {
+                        for s in &f.node.specifiers {
+                            if let SpecifierQualifier::TypeSpecifier(ref t) = s.node {
+                                try!(self.add_type_specifier(t, &[]));
+                            }
+                        }
+                    }
+                }
+                if let Some(ref i) = s.node.identifier {
+                    let layout = try!(layout::record_layout(self, s, extensions));
+                    self.add_tag(&i.node.name, layout);
+                }
+            }
+            TypeSpecifier::Enum(ref e) if !e.node.enumerators.is_empty() => {
+                try!(self.add_enum(&e.node));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Evaluate enumerators in an `enum` definition and add them to the innermost scope
+    ///
+    /// Enumerators without an initializer get the value of the previous one plus one. Constants
+    /// have type `int` when their value fits, otherwise the type of their initializer (GNU
+    /// extension). The tag, if any, is added with the integer type chosen by `enum_type` for
+    /// the range of all values, which is also returned.
+    pub fn add_enum(&mut self, n: &EnumType) -> Result<IntegerType> {
+        let mut previous = None;
+        let mut values = Vec::with_capacity(n.enumerators.len());
+        for e in &n.enumerators {
+            let value = match (e.node.expression.as_ref(), previous) {
+                (Some(expr), _) => try!(self.evaluate(expr)),
+                (None, Some(p)) => try!(self.arithmetic(
+                    BinaryOperator::Plus,
+                    p,
+                    Value::int(1),
+                    e.node.identifier.span
+                )),
+                (None, None) => Value::int(0),
+            };
+            let value = if fits(&self.target, IntegerType::Int, value.value) {
+                Value::int(value.value)
+            } else {
+                value
+            };
+            self.add_constant(&e.node.identifier.node.name, value);
+            values.push(value.value);
+            previous = Some(value);
+        }
+        let ty = enum_integer_type(&self.target, &values);
+        if let Some(ref i) = n.identifier {
+            self.scopes.last_mut().unwrap().tags.insert(
+                i.node.name.clone(),
+                (self.target.integer_layout(ty), Some(ty)),
+            );
+        }
+        Ok(ty)
+    }
+
+    /// Integer type compatible with the enumerated type defined by `n`
+    ///
+    /// Values of the enumeration constants are looked up by name, so `add_enum` should be called
+    /// for the definition first. Otherwise they are evaluated in a temporary scope.
+    pub fn enum_type(&self, n: &EnumType) -> Result<IntegerType> {
+        let values: Option<Vec<i128>> = n
+            .enumerators
+            .iter()
+            .map(|e| self.constant(&e.node.identifier.node.name).map(|v| v.value))
+            .collect();
+        match values {
+            Some(values) => Ok(enum_integer_type(&self.target, &values)),
+            None => {
+                let mut eval = self.clone();
+                eval.enter_scope();
+                eval.add_enum(n)
+            }
+        }
+    }
+
+    /// Evaluate an integer constant expression
+    pub fn evaluate(&self, n: &Node<Expression>) -> Result<Value> {
+        let span = n.span;
+        match n.node {
+            Expression::Identifier(ref i) => match self.constant(&i.node.name) {
+                Some(v) => Ok(v),
+                None => Err(EvalError::new(
+                    EvalErrorKind::UnknownIdentifier(i.node.name.clone()),
+                    i.span,
+                )),
+            },
+            Expression::Constant(ref c) => self.constant_value(&c.node, c.span),
+            Expression::SizeOfTy(ref s) => {
+                let layout = try!(layout::type_name_layout(self, &s.node.0));
+                Ok(self.size_value(layout.size))
+            }
+            Expression::SizeOfVal(ref s) => {
+                let size = try!(self.expression_size(&s.node.0));
+                Ok(self.size_value(size))
+            }
+            Expression::AlignOf(ref a) => {
+                let layout = try!(layout::type_name_layout(self, &a.node.0));
+                Ok(self.size_value(layout.align))
+            }
+            Expression::UnaryOperator(ref u) => {
+                let operand = &u.node.operand;
+                match u.node.operator.node {
+                    UnaryOperator::Plus => Ok(self.promote(try!(self.evaluate(operand)))),
+                    UnaryOperator::Minus => {
+                        let v = self.promote(try!(self.evaluate(operand)));
+                        self.convert(v.ty, -v.value, true, span)
+                    }
+                    UnaryOperator::Complement => {
+                        let v = self.promote(try!(self.evaluate(operand)));
+                        self.convert(v.ty, !v.value, false, span)
+                    }
+                    UnaryOperator::Negate => {
+                        let v = try!(self.evaluate(operand));
+                        Ok(Value::int(!v.is_true() as i128))
+                    }
+                    _ => Err(EvalError::new(EvalErrorKind::NotConstant, span)),
+                }
+            }
+            Expression::Cast(ref c) => {
+                let ty = try!(self.integer_type_name(&c.node.type_name));
+                let value = match c.node.expression.node {
+                    Expression::Constant(ref k) => match k.node {
+                        Constant::Float(ref f) => try!(float_to_integer(f, k.span)),
+                        _ => try!(self.evaluate(&c.node.expression)).value,
+                    },
+                    _ => try!(self.evaluate(&c.node.expression)).value,
+                };
+                self.convert(ty, value, false, span)
+            }
+            Expression::BinaryOperator(ref b) => {
+                let op = b.node.operator.node;
+                match op {
+                    BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+                        let lhs = try!(self.evaluate(&b.node.lhs)).is_true();
+                        let result = match (op, lhs) {
+                            (BinaryOperator::LogicalAnd, false) => false,
+                            (BinaryOperator::LogicalOr, true) => true,
+                            _ => try!(self.evaluate(&b.node.rhs)).is_true(),
+                        };
+                        Ok(Value::int(result as i128))
+                    }
+                    _ => {
+                        let lhs = try!(self.evaluate(&b.node.lhs));
+                        let rhs = try!(self.evaluate(&b.node.rhs));
+                        self.arithmetic(op, lhs, rhs, span)
+                    }
+                }
+            }
+            Expression::Conditional(ref c) => {
+                let condition = try!(self.evaluate(&c.node.condition));
+                let then_value = match c.node.then_expression {
+                    Some(ref e) if condition.is_true() => Some(try!(self.evaluate(e))),
+                    Some(ref e) => self.evaluate(e).ok(),
+                    None => Some(condition),
+                };
+                let else_value = if condition.is_true() {
+                    self.evaluate(&c.node.else_expression).ok()
+                } else {
+                    Some(try!(self.evaluate(&c.node.else_expression)))
+                };
+                let (selected, other) = if condition.is_true() {
+                    (then_value.unwrap(), else_value)
+                } else {
+                    (else_value.unwrap(), then_value)
+                };
+                let ty = match other {
+                    Some(other) => self.common_type(selected.ty, other.ty),
+                    None => self.promote(selected).ty,
+                };
+                self.convert(ty, selected.value, false, span)
+            }
+            Expression::ChooseExpr(ref c) => {
+                if try!(self.evaluate(&c.node.condition)).is_true() {
+                    self.evaluate(&c.node.then_expression)
+                } else {
+                    self.evaluate(&c.node.else_expression)
+                }
+            }
+            _ => Err(EvalError::new(EvalErrorKind::NotConstant, span)),
+        }
+    }
+
+    fn size_value(&self, size: u64) -> Value {
+        Value {
+            ty: self.target.size_type(),
+            value: size as i128,
+        }
+    }
+
+    fn constant_value(&self, n: &Constant, span: Span) -> Result<Value> {
+        match *n {
+            Constant::Integer(ref i) => self.integer_value(i, span),
+            Constant::Character(ref c) => self.character_value(c, span),
+            Constant::Bool(b) => Ok(Value {
+                ty: IntegerType::Bool,
+                value: b as i128,
+            }),
+            Constant::Float(_) | Constant::Nullptr => {
+                Err(EvalError::new(EvalErrorKind::NotInteger, span))
+            }
+        }
+    }
+
+    /// Value and type of an integer literal (C11 6.4.4.1)
+    fn integer_value(&self, n: &Integer, span: Span) -> Result<Value> {
+        let invalid = || EvalError::new(EvalErrorKind::InvalidLiteral, span);
+        if n.suffix.imaginary {
+            return Err(EvalError::new(EvalErrorKind::NotInteger, span));
+        }
+        if n.suffix.size == IntegerSize::BitInt {
+            return Err(EvalError::new(EvalErrorKind::UnsupportedType, span));
+        }
+        match IntegerValue::decode(&self.target, n, span) {
+            Ok(IntegerValue {
+                value,
+                ty: CType::Integer(ty),
+            }) => Ok(Value {
+                ty,
+                value: value as i128,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Value and type of a character constant (C11 6.4.4.4)
+    fn character_value(&self, s: &str, span: Span) -> Result<Value> {
+        let invalid = || EvalError::new(EvalErrorKind::InvalidLiteral, span);
+        let c = try!(CharacterValue::decode(&self.target, s, span).map_err(|_| invalid()));
+        let units = c.units;
+        let ty = match c.encoding {
+            Encoding::Plain => IntegerType::Int,
+            Encoding::Utf8 => IntegerType::UnsignedChar,
+            e => e.unit_type(&self.target),
+        };
+
+        let plain = c.encoding == Encoding::Plain;
+        match (units.len(), plain) {
+            // Plain character constants are converted from `char`, multi-character constants
+            // are packed into an `int` like GCC does.
+            (1, true) => {
+                let c = try!(self.convert(IntegerType::Char, units[0] as i128, false, span));
+                Ok(Value::int(c.value))
+            }
+            (_, true) => {
+                let packed = units.iter().fold(0u32, |v, &u| (v << 8) | u);
+                Ok(Value::int(packed as i32 as i128))
+            }
+            (1, false) => self.convert(ty, units[0] as i128, false, span),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Apply integer promotions (C11 6.3.1.1)
+    fn promote(&self, v: Value) -> Value {
+        if v.ty.rank() < IntegerType::Int.rank() {
+            Value::int(v.value)
+        } else {
+            v
+        }
+    }
+
+    /// Find the common type for usual arithmetic conversions (C11 6.3.1.8)
+    fn common_type(&self, a: IntegerType, b: IntegerType) -> IntegerType {
+        let a = self.promote(Value { ty: a, value: 0 }).ty;
+        let b = self.promote(Value { ty: b, value: 0 }).ty;
+        if a == b {
+            return a;
+        }
+        let (hi, lo) = if a.rank() >= b.rank() { (a, b) } else { (b, a) };
+        if self.target.is_signed(hi) == self.target.is_signed(lo) {
+            return hi;
+        }
+        let (signed, unsigned) = if self.target.is_signed(a) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        if unsigned.rank() >= signed.rank() {
+            unsigned
+        } else if self.target.integer_size(signed) > self.target.integer_size(unsigned) {
+            signed
+        } else {
+            signed.to_unsigned()
+        }
+    }
+
+    /// Convert a mathematical value to a type
+    ///
+    /// Unsigned types wrap around. Values outside the range of a signed type are an error if
+    /// `checked` is set, otherwise they wrap around too, like GCC does for conversions.
+    fn convert(&self, ty: IntegerType, value: i128, checked: bool, span: Span) -> Result<Value> {
+        if ty == IntegerType::Bool {
+            return Ok(Value {
+                ty,
+                value: (value != 0) as i128,
+            });
+        }
+        if checked && self.target.is_signed(ty) && !fits(&self.target, ty, value) {
+            return Err(EvalError::new(EvalErrorKind::Overflow, span));
+        }
+        let bits = self.target.integer_size(ty) * 8;
+        let modulus = 1i128 << bits;
+        let mut value = value.rem_euclid(modulus);
+        if self.target.is_signed(ty) && value >= modulus / 2 {
+            value -= modulus;
+        }
+        Ok(Value { ty, value })
+    }
+
+    fn arithmetic(&self, op: BinaryOperator, a: Value, b: Value, span: Span) -> Result<Value> {
+        use ast::BinaryOperator::*;
+
+        let shift = |a: Value, b: Value| -> Result<(IntegerType, u32)> {
+            let ty = self.promote(a).ty;
+            let bits = self.target.integer_size(ty) * 8;
+            if b.value < 0 || b.value >= bits as i128 {
+                return Err(EvalError::new(EvalErrorKind::InvalidShift, span));
+            }
+            Ok((ty, b.value as u32))
+        };
+
+        match op {
+            ShiftLeft => {
+                let (ty, count) = try!(shift(a, b));
+                if self.target.is_signed(ty) && a.value < 0 {
+                    return Err(EvalError::new(EvalErrorKind::InvalidShift, span));
+                }
+                self.convert(ty, a.value << count, true, span)
+            }
+            ShiftRight => {
+                let (ty, count) = try!(shift(a, b));
+                self.convert(ty, a.value >> count, false, span)
+            }
+            _ => {
+                let ty = self.common_type(a.ty, b.ty);
+                let a = try!(self.convert(ty, a.value, false, span)).value;
+                let b = try!(self.convert(ty, b.value, false, span)).value;
+                let compare = |r: bool| Ok(Value::int(r as i128));
+                match op {
+                    // Unsigned operands can exceed the range of `i128` when multiplied
+                    Multiply if !self.target.is_signed(ty) => {
+                        self.convert(ty, a.wrapping_mul(b), false, span)
+                    }
+                    Multiply => match a.checked_mul(b) {
+                        Some(r) => self.convert(ty, r, true, span),
+                        None => Err(EvalError::new(EvalErrorKind::Overflow, span)),
+                    },
+                    Divide | Modulo if b == 0 => {
+                        Err(EvalError::new(EvalErrorKind::DivisionByZero, span))
+                    }
+                    Divide => self.convert(ty, a / b, true, span),
+                    Modulo => self.convert(ty, a % b, true, span),
+                    Plus => self.convert(ty, a + b, true, span),
+                    Minus => self.convert(ty, a - b, true, span),
+                    Less => compare(a < b),
+                    Greater => compare(a > b),
+                    LessOrEqual => compare(a <= b),
+                    GreaterOrEqual => compare(a >= b),
+                    Equals => compare(a == b),
+                    NotEquals => compare(a != b),
+                    BitwiseAnd => self.convert(ty, a & b, false, span),
+                    BitwiseXor => self.convert(ty, a ^ b, false, span),
+                    BitwiseOr => self.convert(ty, a | b, false, span),
+                    _ => Err(EvalError::new(EvalErrorKind::NotConstant, span)),
+                }
+            }
+        }
+    }
+
+    /// Size of the type of an expression, for `sizeof expr`
+    fn expression_size(&self, n: &Node<Expression>) -> Result<u64> {
+        match n.node {
+            Expression::StringLiteral(ref s) => self.string_literal_size(s),
+            _ => {
+                let v = try!(self.evaluate(n));
+                Ok(self.target.integer_size(v.ty))
+            }
+        }
+    }
+
+    /// Size of a string literal array in bytes, including the terminating null character
+    pub fn string_literal_size(&self, n: &Node<StringLiteral>) -> Result<u64> {
+        match StringValue::decode(&self.target, n, None) {
+            Ok(value) => Ok(value.size(&self.target)),
+            Err(_) => Err(EvalError::new(EvalErrorKind::InvalidLiteral, n.span)),
+        }
+    }
+
+    /// Integer type named by a type name, for casts
+    fn integer_type_name(&self, n: &Node<TypeName>) -> Result<IntegerType> {
+        if let Some(ref d) = n.node.declarator {
+            if !d.node.derived.is_empty() {
+                return Err(EvalError::new(EvalErrorKind::NotInteger, n.span));
+            }
+        }
+        let specifiers = layout::specifier_types(&n.node.specifiers);
+        match try!(layout::base_type(self, &specifiers, &[], n.span)) {
+            (_, Some(ty)) => Ok(ty),
+            _ => Err(EvalError::new(EvalErrorKind::NotInteger, n.span)),
+        }
+    }
+}
+
+fn declaration_extensions(n: &Node<Declaration>) -> Vec<&Node<Extension>> {
+    n.node
+        .specifiers
+        .iter()
+        .flat_map(|s| match s.node {
+            DeclarationSpecifier::Extension(ref e) => e.iter(),
+            _ => [].iter(),
+        })
+        .collect()
+}
+
+fn declarator_identifier(n: &Declarator) -> Option<&Node<Identifier>> {
+    match n.kind.node {
+        DeclaratorKind::Abstract => None,
+        DeclaratorKind::Identifier(ref i) => Some(i),
+        DeclaratorKind::Declarator(ref d) => declarator_identifier(&d.node),
+    }
+}
+
+/// Test if value is in range of an integer type
+fn fits(target: &Target, ty: IntegerType, value: i128) -> bool {
+    let bits = target.integer_size(ty) * 8;
+    if ty == IntegerType::Bool {
+        value == 0 || value == 1
+    } else if target.is_signed(ty) {
+        value >= -(1 << (bits - 1)) && value < (1 << (bits - 1))
+    } else {
+        value >= 0 && value < (1 << bits)
+    }
+}
+
+/// Integer type of an enumeration with these values
+///
+/// Like GCC, this is `unsigned int` if no value is negative, `int` otherwise, or the first wider
+/// type that can represent all values.
+fn enum_integer_type(target: &Target, values: &[i128]) -> IntegerType {
+    let min = values.iter().cloned().min().unwrap_or(0);
+    let max = values.iter().cloned().max().unwrap_or(0);
+    let candidates = [
+        IntegerType::UnsignedInt,
+        IntegerType::Int,
+        IntegerType::UnsignedLong,
+        IntegerType::Long,
+        IntegerType::UnsignedLongLong,
+    ];
+    candidates
+        .iter()
+        .cloned()
+        .find(|&ty| fits(target, ty, min) && fits(target, ty, max))
+        .unwrap_or(IntegerType::LongLong)
+}
+
+/// Truncate a floating constant operand of a cast
+fn float_to_integer(n: &Float, span: Span) -> Result<i128> {
+    if n.suffix.imaginary {
+        return Err(EvalError::new(EvalErrorKind::NotInteger, span));
+    }
+    let value = match FloatValue::decode(n, BinaryFormat::Binary64, span) {
+        Ok(v) => v.to_f64().unwrap(),
+        Err(_) => return Err(EvalError::new(EvalErrorKind::Overflow, span)),
+    };
+    if value.abs() >= 1e38 {
+        return Err(EvalError::new(EvalErrorKind::Overflow, span));
+    }
+    Ok(value.trunc() as i128)
+}
+
+#[cfg(test)]
+fn eval(model: ::layout::DataModel, source: &str) -> Result<Value> {
+    use driver::{parse_expression, Config};
+
+    let expr = parse_expression(&Config::with_gcc(), source).unwrap();
+    Evaluator::new(Target::new(model)).evaluate(&expr)
+}
+
+#[test]
+fn test_literals() {
+    use layout::DataModel;
+
+    let lp64 = |s| eval(DataModel::LP64, s).unwrap();
+    assert_eq!(lp64("42"), Value::int(42));
+    assert_eq!(lp64("0x7fffffff").ty, IntegerType::Int);
+    assert_eq!(lp64("0xffffffff").ty, IntegerType::UnsignedInt);
+    assert_eq!(lp64("4294967295").ty, IntegerType::Long);
+    assert_eq!(lp64("0b1010").value, 10);
+    assert_eq!(lp64("017ul").ty, IntegerType::UnsignedLong);
+    assert_eq!(lp64("'a'"), Value::int(97));
+    assert_eq!(lp64("'\\377'"), Value::int(-1));
+    assert_eq!(lp64("'ab'"), Value::int(0x6162));
+    assert_eq!(lp64("L'\\x263a'").value, 0x263a);
+    assert_eq!(lp64("u'x'").ty, IntegerType::UnsignedShort);
+
+    let llp64 = |s| eval(DataModel::LLP64, s).unwrap();
+    assert_eq!(llp64("4294967295").ty, IntegerType::LongLong);
+    assert_eq!(llp64("L'a'").ty, IntegerType::UnsignedShort);
+
+    assert_eq!(
+        eval(DataModel::LP64, "0x10000000000000000")
+            .unwrap_err()
+            .kind,
+        EvalErrorKind::InvalidLiteral
+    );
+}
+
+#[test]
+fn test_arithmetic() {
+    use layout::DataModel;
+
+    let lp64 = |s| eval(DataModel::LP64, s).unwrap();
+    assert_eq!(lp64("1 + 2 * 3 - 9 / 2 % 3"), Value::int(6));
+    assert_eq!(lp64("-1 < 0u"), Value::int(0));
+    assert_eq!(lp64("-1 < 0ul"), Value::int(0));
+    assert_eq!(lp64("-1l < 0u"), Value::int(1));
+    assert_eq!(lp64("0u - 1").value, 0xffffffff);
+    assert_eq!(lp64("~0ull >> 60").value, 15);
+    assert_eq!(
+        lp64("0xFFFFFFFFFFFFFFFFull * 0xFFFFFFFFFFFFFFFFull").value,
+        1
+    );
+    assert_eq!(lp64("0x80000000u * 2").value, 0);
+    assert_eq!(
+        lp64("(char)300"),
+        Value {
+            ty: IntegerType::Char,
+            value: 44
+        }
+    );
+    assert_eq!(lp64("(_Bool)5 + (int)2.9"), Value::int(3));
+    assert_eq!(
+        lp64("1 ? 2 : 3u"),
+        Value {
+            ty: IntegerType::UnsignedInt,
+            value: 2
+        }
+    );
+    assert_eq!(lp64("0 && 1 / 0"), Value::int(0));
+    assert_eq!(lp64("5 ?: 1"), Value::int(5));
+
+    let err = |s| eval(DataModel::LP64, s).unwrap_err();
+    assert_eq!(err("2147483647 + 1").kind, EvalErrorKind::Overflow);
+    assert_eq!(err("0x7fffffffffffffffl * 2").kind, EvalErrorKind::Overflow);
+    assert_eq!(err("65536 * 32768").kind, EvalErrorKind::Overflow);
+    assert_eq!(err("1 << 32").kind, EvalErrorKind::InvalidShift);
+    assert_eq!(err("-1 << 1").kind, EvalErrorKind::InvalidShift);
+    assert_eq!(err("1 % 0").kind, EvalErrorKind::DivisionByZero);
+    assert_eq!(err("f()").kind, EvalErrorKind::NotConstant);
+    assert_eq!(err("1.5").kind, EvalErrorKind::NotInteger);
+
+    let e = err("1 + (x * 2)");
+    assert_eq!(e.kind, EvalErrorKind::UnknownIdentifier("x".into()));
+    assert_eq!((e.span.start, e.span.end), (5, 6));
+}
+
+#[test]
+fn test_sizeof() {
+    use layout::DataModel;
+
+    let size = |m, s| eval(m, s).unwrap().value;
+    assert_eq!(size(DataModel::LP64, "sizeof(long)"), 8);
+    assert_eq!(size(DataModel::LLP64, "sizeof(long)"), 4);
+    assert_eq!(size(DataModel::ILP32, "sizeof(void *)"), 4);
+    assert_eq!(size(DataModel::LP64, "sizeof(int *[3][2])"), 48);
+    assert_eq!(size(DataModel::LP64, "sizeof(int (*)[3])"), 8);
+    assert_eq!(size(DataModel::LP64, "sizeof(unsigned short int)"), 2);
+    assert_eq!(size(DataModel::LP64, "sizeof(_Complex double)"), 16);
+    assert_eq!(size(DataModel::LP64, "_Alignof(long double)"), 16);
+    assert_eq!(size(DataModel::LP64, "sizeof 'a'"), 4);
+    assert_eq!(size(DataModel::LP64, "sizeof \"ab\" \"c\""), 4);
+    assert_eq!(size(DataModel::LP64, "sizeof L\"ab\""), 12);
+    assert_eq!(
+        eval(DataModel::LP64, "sizeof(struct s)").unwrap_err().kind,
+        EvalErrorKind::IncompleteType
+    );
+}
+
+#[test]
+fn test_enum() {
+    use driver::{parse_declaration, Config};
+
+    let decl = parse_declaration(
+        &Config::with_gcc(),
+        "enum e { A, B = 10, C, D = C * 2, E = 4294967296 };",
+    )
+    .unwrap();
+    let mut eval = Evaluator::new(Target::new(::layout::DataModel::LP64));
+    eval.add_declaration(&decl).unwrap();
+    assert_eq!(eval.constant("A"), Some(Value::int(0)));
+    assert_eq!(eval.constant("C"), Some(Value::int(11)));
+    assert_eq!(eval.constant("D"), Some(Value::int(22)));
+    assert_eq!(eval.constant("E").unwrap().ty, IntegerType::Long);
+    assert_eq!(eval.tag("e").map(|l| l.size), Some(8));
+    assert_eq!(eval.enum_tag("e"), Some(IntegerType::UnsignedLong));
+
+    for source in &[
+        "enum f { F = -1, G = 0xffffffff };",
+        "enum g { H };",
+        "enum h { I = -1 };",
+    ] {
+        let decl = parse_declaration(&Config::with_gcc(), source).unwrap();
+        eval.add_declaration(&decl).unwrap();
+    }
+    assert_eq!(eval.tag("f").map(|l| l.size), Some(8));
+    assert_eq!(eval.enum_tag("f"), Some(IntegerType::Long));
+    assert_eq!(eval.enum_tag("g"), Some(IntegerType::UnsignedInt));
+    assert_eq!(eval.enum_tag("h"), Some(IntegerType::Int));
+
+    eval.enter_scope();
+    eval.add_constant("A", Value::int(5));
+    let expr = ::driver::parse_expression(&Config::with_gcc(), "A + B").unwrap();
+    assert_eq!(eval.evaluate(&expr), Ok(Value::int(15)));
+    eval.leave_scope();
+    assert_eq!(eval.evaluate(&expr), Ok(Value::int(10)));
+}
+
+#[test]
+fn test_typedef() {
+    use driver::{parse_declaration, parse_expression, Config};
+    use layout::DataModel;
+
+    let mut config = Config::with_gcc();
+    config.typedef_names.push("u32".into());
+    let mut eval = Evaluator::new(Target::new(DataModel::LP64));
+    let decl = parse_declaration(&config, "typedef unsigned int u32;").unwrap();
+    eval.add_declaration(&decl).unwrap();
+
+    let expr = parse_expression(&config, "(u32)-1 + sizeof(u32[3])").unwrap();
+    assert_eq!(
+        eval.evaluate(&expr),
+        Ok(Value {
+            ty: IntegerType::UnsignedLong,
+            value: 0xffffffff + 12
+        })
+    );
+}
+
+#[test]
+fn test_unsigned_char() {
+    use layout::DataModel;
+
+    let mut target = Target::new(DataModel::LP64);
+    target.char_signed = false;
+    let expr = ::driver::parse_expression(&::driver::Config::with_gcc(), "(char)-1").unwrap();
+    assert_eq!(Evaluator::new(target).evaluate(&expr).unwrap().value, 255);
+}
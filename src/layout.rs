@@ -0,0 +1,832 @@
+//! Target data models and memory layout of types
+//!
+//! Sizes, alignments and member offsets are computed the way GCC does on System V targets,
+//! including bit-fields, `__attribute__((packed))`, `__attribute__((aligned(N)))` and
+//! `_Alignas`. Names of structure tags and typedefs are looked up in an `eval::Evaluator`, which
+//! collects them with `Evaluator::add_declaration`.
+//!
+//! ```
+//! # use lang_c::driver::{parse_declaration, Config};
+//! # use lang_c::eval::Evaluator;
+//! # use lang_c::layout::{DataModel, Target};
+//! let source = "struct s { char c; int i : 3; double d; };";
+//! let decl = parse_declaration(&Config::with_gcc(), source).unwrap();
+//! let mut eval = Evaluator::new(Target::new(DataModel::LP64));
+//! eval.add_declaration(&decl).unwrap();
+//!
+//! let s = eval.tag("s").unwrap();
+//! assert_eq!((s.size, s.align), (16, 8));
+//! let fields = &s.record.as_ref().unwrap().fields;
+//! assert_eq!(fields[1].bit_offset, 8);
+//! assert_eq!(fields[2].bit_offset, 64);
+//! ```
+
+use std::rc::Rc;
+
+use ast::*;
+use eval::{EvalError, EvalErrorKind, Evaluator, IntegerType};
+use span::{Node, Span};
+use types::{arithmetic_type, derived_declarators, CType, FloatType};
+
+/// Sizes of the basic integer types and pointers
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum DataModel {
+    /// 32-bit `int`, `long` and pointers (e.g. i386)
+    ILP32,
+    /// 64-bit `long` and pointers (e.g. x86-64 Linux and macOS)
+    LP64,
+    /// 64-bit pointers, 32-bit `long` (e.g. x86-64 Windows)
+    LLP64,
+}
+
+/// Description of the target platform
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Target {
+    pub data_model: DataModel,
+    /// Plain `char` is a signed type
+    pub char_signed: bool,
+    /// Size of `long double` in bytes
+    pub long_double_size: u64,
+    /// Alignment of `long double` in bytes
+    pub long_double_align: u64,
+    /// Largest alignment of integer and floating types other than `long double`
+    ///
+    /// The i386 System V ABI aligns `long long` and `double` to four bytes.
+    pub max_scalar_align: u64,
+    /// Alignment used by `__attribute__((aligned))` without an argument
+    pub biggest_alignment: u64,
+}
+
+impl Target {
+    /// Create a target with the common ABI choices for a data model
+    ///
+    /// Plain `char` is signed. `long double` is 80-bit x87 extended precision on ILP32 and LP64
+    /// targets and the same as `double` on LLP64 targets.
+    pub fn new(data_model: DataModel) -> Target {
+        let (long_double_size, long_double_align) = match data_model {
+            DataModel::ILP32 => (12, 4),
+            DataModel::LP64 => (16, 16),
+            DataModel::LLP64 => (8, 8),
+        };
+        Target {
+            data_model,
+            char_signed: true,
+            long_double_size,
+            long_double_align,
+            max_scalar_align: match data_model {
+                DataModel::ILP32 => 4,
+                DataModel::LP64 | DataModel::LLP64 => 8,
+            },
+            biggest_alignment: 16,
+        }
+    }
+
+    /// Size of a pointer in bytes
+    pub fn pointer_size(&self) -> u64 {
+        match self.data_model {
+            DataModel::ILP32 => 4,
+            DataModel::LP64 | DataModel::LLP64 => 8,
+        }
+    }
+
+    /// Size of an integer type in bytes
+    pub fn integer_size(&self, ty: IntegerType) -> u64 {
+        match ty {
+            IntegerType::Bool
+            | IntegerType::Char
+            | IntegerType::SignedChar
+            | IntegerType::UnsignedChar => 1,
+            IntegerType::Short | IntegerType::UnsignedShort => 2,
+            IntegerType::Int | IntegerType::UnsignedInt => 4,
+            IntegerType::Long | IntegerType::UnsignedLong => match self.data_model {
+                DataModel::LP64 => 8,
+                DataModel::ILP32 | DataModel::LLP64 => 4,
+            },
+            IntegerType::LongLong | IntegerType::UnsignedLongLong => 8,
+        }
+    }
+
+    /// Test if an integer type can represent negative values
+    pub fn is_signed(&self, ty: IntegerType) -> bool {
+        match ty {
+            IntegerType::Char => self.char_signed,
+            IntegerType::SignedChar
+            | IntegerType::Short
+            | IntegerType::Int
+            | IntegerType::Long
+            | IntegerType::LongLong => true,
+            _ => false,
+        }
+    }
+
+    /// Type of `wchar_t`
+    pub fn wchar_type(&self) -> IntegerType {
+        match self.data_model {
+            DataModel::ILP32 | DataModel::LP64 => IntegerType::Int,
+            DataModel::LLP64 => IntegerType::UnsignedShort,
+        }
+    }
+
+    /// Type of `size_t`
+    pub fn size_type(&self) -> IntegerType {
+        match self.data_model {
+            DataModel::ILP32 => IntegerType::UnsignedInt,
+            DataModel::LP64 => IntegerType::UnsignedLong,
+            DataModel::LLP64 => IntegerType::UnsignedLongLong,
+        }
+    }
+
+    /// Type of `ptrdiff_t`
+    pub fn ptrdiff_type(&self) -> IntegerType {
+        match self.data_model {
+            DataModel::ILP32 => IntegerType::Int,
+            DataModel::LP64 => IntegerType::Long,
+            DataModel::LLP64 => IntegerType::LongLong,
+        }
+    }
+
+    /// Size and alignment of an integer type
+    pub fn integer_layout(&self, ty: IntegerType) -> Layout {
+        self.scalar_layout(self.integer_size(ty))
+    }
+
+    fn scalar_layout(&self, size: u64) -> Layout {
+        Layout::scalar(size, ::std::cmp::min(size, self.max_scalar_align))
+    }
+}
+
+/// Size and alignment of a type
+#[derive(Debug, PartialEq, Clone)]
+pub struct Layout {
+    /// Size in bytes
+    pub size: u64,
+    /// Alignment in bytes
+    pub align: u64,
+    /// Member layout of structure and union types
+    pub record: Option<Rc<RecordLayout>>,
+}
+
+impl Layout {
+    fn scalar(size: u64, align: u64) -> Layout {
+        Layout {
+            size,
+            align,
+            record: None,
+        }
+    }
+}
+
+/// Layout of structure or union members
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordLayout {
+    pub kind: StructKind,
+    /// Members in declaration order
+    pub fields: Vec<FieldLayout>,
+}
+
+/// Position of a single structure or union member
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldLayout {
+    /// `None` for unnamed bit-fields and anonymous structure members
+    pub name: Option<String>,
+    /// Offset from the start of the record in bits
+    pub bit_offset: u64,
+    /// Width of a bit-field
+    pub bit_width: Option<u64>,
+    /// Layout of the declared type of the member
+    pub layout: Layout,
+    pub span: Span,
+}
+
+impl FieldLayout {
+    /// Offset from the start of the record in bytes, rounded down for bit-fields
+    pub fn offset(&self) -> u64 {
+        self.bit_offset / 8
+    }
+}
+
+type Result<T> = ::std::result::Result<T, EvalError>;
+
+/// Layout of a type name
+pub fn type_name_layout(eval: &Evaluator, n: &Node<TypeName>) -> Result<Layout> {
+    let specifiers = specifier_types(&n.node.specifiers);
+    let base = base_type(eval, &specifiers, &[], n.span).map(|(l, _)| l);
+    match n.node.declarator {
+        Some(ref d) => declarator_layout(eval, base, d, false),
+        None => base,
+    }
+}
+
+/// Layout of a structure or union definition
+///
+/// `extensions` are attributes that apply to the type but are written outside of the
+/// structure specifier, such as `__attribute__((packed))` after the closing brace.
+pub fn record_layout(
+    eval: &Evaluator,
+    n: &Node<StructType>,
+    extensions: &[&Node<Extension>],
+) -> Result<Layout> {
+    let declarations = match n.node.declarations {
+        Some(ref d) => d,
+        None => return Err(EvalError::new(EvalErrorKind::IncompleteType, n.span)),
+    };
+    let union = n.node.kind.node == StructKind::Union;
+    let record_attributes = try!(attributes(
+        eval,
+        n.node.extensions.iter().chain(extensions.iter().cloned())
+    ));
+
+    // Pragmas and static assertions may follow a flexible array member
+    let last_field = declarations
+        .iter()
+        .rfind(|d| match d.node {
+            StructDeclaration::Field(_) => true,
+            _ => false,
+        })
+        .map(|d| d.span);
+
+    let mut fields = Vec::new();
+    let mut bit_offset = 0;
+    let mut size_bits = 0;
+    let mut align = 1;
+    for declaration in declarations {
+        let field = match declaration.node {
+            StructDeclaration::Field(ref f) => f,
+            StructDeclaration::StaticAssert(_) | StructDeclaration::Pragma(_) => continue,
+        };
+        let specifiers = specifier_types(&field.node.specifiers);
+        let field_extensions = field.node.specifiers.iter().flat_map(|s| match s.node {
+            SpecifierQualifier::Extension(ref e) => e.iter(),
+            _ => [].iter(),
+        });
+        let field_attributes = try!(attributes(eval, field_extensions.clone()));
+        let mut alignas = 0;
+        for s in &field.node.specifiers {
+            if let SpecifierQualifier::Alignment(ref a) = s.node {
+                alignas = ::std::cmp::max(alignas, try!(alignment(eval, a)));
+            }
+        }
+        let base = base_type(eval, &specifiers, &[], field.span).map(|(l, _)| l);
+
+        // Anonymous structure or union member is laid out like a member without a declarator
+        let count = ::std::cmp::max(field.node.declarators.len(), 1);
+        for i in 0..count {
+            let d = field.node.declarators.get(i);
+            let span = d.map_or(field.span, |d| d.span);
+            let last = i + 1 == count;
+            let flexible = last && last_field == Some(declaration.span);
+            let (layout, name, declarator_attributes) =
+                match d.and_then(|d| d.node.declarator.as_ref()) {
+                    Some(d) => (
+                        try!(declarator_layout(eval, base.clone(), d, flexible)),
+                        declarator_name(&d.node),
+                        try!(attributes(
+                            eval,
+                            field_extensions.clone().chain(d.node.extensions.iter())
+                        )),
+                    ),
+                    None => (try!(base.clone()), None, field_attributes.clone()),
+                };
+            let packed = record_attributes.packed || declarator_attributes.packed;
+            let explicit = ::std::cmp::max(alignas, declarator_attributes.aligned.unwrap_or(0));
+            let natural = if packed { 1 } else { layout.align };
+            let field_align = ::std::cmp::max(natural, explicit);
+
+            let width = match d.and_then(|d| d.node.bit_width.as_ref()) {
+                Some(w) => {
+                    let w = try!(eval.evaluate(w));
+                    if w.value < 0 || w.value as u64 > layout.size * 8 {
+                        return Err(EvalError::new(EvalErrorKind::InvalidBitWidth, span));
+                    }
+                    Some(w.value as u64)
+                }
+                None => None,
+            };
+
+            let start = if union {
+                0
+            } else {
+                match width {
+                    // Zero-width bit-field pads to the next unit of its type
+                    Some(0) => align_to(bit_offset, layout.align * 8),
+                    // Packed bit-fields are not aligned at all
+                    Some(_) if packed && explicit == 0 => bit_offset,
+                    Some(w) if !packed && explicit == 0 => {
+                        let unit = layout.align * 8;
+                        if bit_offset % unit + w > layout.size * 8 {
+                            align_to(bit_offset, unit)
+                        } else {
+                            bit_offset
+                        }
+                    }
+                    _ => align_to(bit_offset, field_align * 8),
+                }
+            };
+            let end = start + width.unwrap_or(layout.size * 8);
+            if !union {
+                bit_offset = end;
+            }
+            size_bits = ::std::cmp::max(size_bits, end);
+            // Unnamed bit-fields do not affect alignment of the record
+            if width.is_none() || name.is_some() {
+                align = ::std::cmp::max(align, field_align);
+            }
+
+            fields.push(FieldLayout {
+                name,
+                bit_offset: start,
+                bit_width: width,
+                layout,
+                span,
+            });
+        }
+    }
+
+    if let Some(a) = record_attributes.aligned {
+        align = ::std::cmp::max(align, a);
+    }
+    Ok(Layout {
+        size: align_to((size_bits + 7) / 8, align),
+        align,
+        record: Some(Rc::new(RecordLayout {
+            kind: n.node.kind.node,
+            fields,
+        })),
+    })
+}
+
+/// Type specifiers in a specifier list
+pub fn specifier_types(n: &[Node<SpecifierQualifier>]) -> Vec<&Node<TypeSpecifier>> {
+    n.iter()
+        .filter_map(|s| match s.node {
+            SpecifierQualifier::TypeSpecifier(ref t) => Some(t),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Layout of the type named by type specifiers, and its integer type if it is one
+///
+/// `extensions` apply to a structure defined in the specifiers.
+pub fn base_type(
+    eval: &Evaluator,
+    specifiers: &[&Node<TypeSpecifier>],
+    extensions: &[&Node<Extension>],
+    span: Span,
+) -> Result<(Layout, Option<IntegerType>)> {
+    let target = eval.target();
+    let incomplete = |span| EvalError::new(EvalErrorKind::IncompleteType, span);
+
+    if let Some(s) = specifiers.first() {
+        match s.node {
+            TypeSpecifier::Struct(ref s) => {
+                return match (&s.node.declarations, &s.node.identifier) {
+                    (&Some(_), _) => Ok((try!(record_layout(eval, s, extensions)), None)),
+                    (&None, Some(i)) => match eval.tag(&i.node.name) {
+                        Some(l) if l.record.is_some() => Ok((l.clone(), None)),
+                        _ => Err(incomplete(s.span)),
+                    },
+                    (&None, &None) => Err(incomplete(s.span)),
+                };
+            }
+            TypeSpecifier::Enum(ref e) => {
+                let ty = if !e.node.enumerators.is_empty() {
+                    Some(try!(eval.enum_type(&e.node)))
+                } else {
+                    e.node
+                        .identifier
+                        .as_ref()
+                        .and_then(|i| eval.enum_tag(&i.node.name))
+                };
+                return match ty {
+                    Some(ty) => Ok((target.integer_layout(ty), Some(ty))),
+                    None => Err(incomplete(e.span)),
+                };
+            }
+            TypeSpecifier::TypedefName(ref i) => {
+                return match eval.typedef(&i.node.name) {
+                    Some(Ok(typedef)) => Ok(typedef),
+                    Some(Err(e)) => Err(e),
+                    None => Err(incomplete(i.span)),
+                };
+            }
+            TypeSpecifier::Atomic(ref t) => {
+                let layout = try!(type_name_layout(eval, t));
+                return Ok((layout, None));
+            }
+            TypeSpecifier::TypeOf(ref t) | TypeSpecifier::TypeOfUnqual(ref t) => {
+                return match t.node {
+                    TypeOf::Type(ref t) => Ok((try!(type_name_layout(eval, t)), None)),
+                    TypeOf::Expression(ref e) => {
+                        let v = try!(eval.evaluate(e));
+                        Ok((target.integer_layout(v.ty), Some(v.ty)))
+                    }
+                };
+            }
+            TypeSpecifier::BitInt(ref e) => {
+                let bits = try!(eval.evaluate(e)).value as u64;
+                let size = match bits {
+                    0...8 => 1,
+                    9...16 => 2,
+                    17...32 => 4,
+                    _ => (bits + 63) / 64 * 8,
+                };
+                return Ok((target.scalar_layout(size), None));
+            }
+            _ => {}
+        }
+    }
+
+    match try!(arithmetic_type(specifiers, span)) {
+        CType::Void => Ok((Layout::scalar(1, 1), None)),
+        CType::Integer(ty) => Ok((target.integer_layout(ty), Some(ty))),
+        CType::Float(f) => Ok((try!(float_layout(target, f, span)), None)),
+        CType::Complex(f) => {
+            let layout = try!(float_layout(target, f, span));
+            Ok((Layout::scalar(layout.size * 2, layout.align), None))
+        }
+        _ => Err(EvalError::new(EvalErrorKind::UnsupportedType, span)),
+    }
+}
+
+fn float_layout(target: &Target, f: FloatType, span: Span) -> Result<Layout> {
+    match f {
+        FloatType::Float => Ok(target.scalar_layout(4)),
+        FloatType::Double => Ok(target.scalar_layout(8)),
+        FloatType::LongDouble => Ok(Layout::scalar(
+            target.long_double_size,
+            target.long_double_align,
+        )),
+        FloatType::TS18661(t) => match (t.format, t.width) {
+            (TS18661FloatFormat::BinaryInterchange, 128)
+            | (TS18661FloatFormat::DecimalInterchange, 128) => Ok(Layout::scalar(16, 16)),
+            (TS18661FloatFormat::BinaryInterchange, w)
+            | (TS18661FloatFormat::DecimalInterchange, w) => Ok(target.scalar_layout(w as u64 / 8)),
+            (TS18661FloatFormat::BinaryExtended, 32) => {
+                float_layout(target, FloatType::Double, span)
+            }
+            (TS18661FloatFormat::BinaryExtended, 64) => {
+                float_layout(target, FloatType::LongDouble, span)
+            }
+            _ => Err(EvalError::new(EvalErrorKind::UnsupportedType, span)),
+        },
+    }
+}
+
+/// Apply pointer, array and function declarators to the layout of a base type
+///
+/// The base type may be incomplete if it is only used through a pointer. If `flexible` is set,
+/// the declarator may be a flexible array member.
+pub fn declarator_layout(
+    eval: &Evaluator,
+    base: Result<Layout>,
+    n: &Node<Declarator>,
+    flexible: bool,
+) -> Result<Layout> {
+    let target = eval.target();
+    let nested = match n.node.kind.node {
+        DeclaratorKind::Declarator(ref d) => Some(d),
+        _ => None,
+    };
+    let mut layout = base;
+    let list = derived_declarators(&n.node);
+    for (i, derived) in list.iter().enumerate() {
+        layout = match derived.node {
+            DerivedDeclarator::Pointer(_) | DerivedDeclarator::Block(_) => {
+                let size = target.pointer_size();
+                Ok(Layout::scalar(size, size))
+            }
+            // GNU C gives function types a size of one
+            DerivedDeclarator::Function(_) | DerivedDeclarator::KRFunction(_) => {
+                Ok(Layout::scalar(1, 1))
+            }
+            DerivedDeclarator::Array(ref a) => {
+                let element = try!(layout);
+                match a.node.size {
+                    ArraySize::VariableExpression(ref e) | ArraySize::StaticExpression(ref e) => {
+                        let count = try!(eval.evaluate(e));
+                        if count.value < 0 {
+                            return Err(EvalError::new(EvalErrorKind::UnsupportedType, e.span));
+                        }
+                        // Offsets of members are computed in bits, so the size in bits must fit
+                        let size = element
+                            .size
+                            .checked_mul(count.value as u64)
+                            .and_then(|s| s.checked_mul(8).map(|_| s));
+                        match size {
+                            Some(size) => Ok(Layout::scalar(size, element.align)),
+                            None => Err(EvalError::new(EvalErrorKind::TooLarge, a.span)),
+                        }
+                    }
+                    _ if flexible && nested.is_none() && i + 1 == list.len() => {
+                        Ok(Layout::scalar(0, element.align))
+                    }
+                    _ => return Err(EvalError::new(EvalErrorKind::IncompleteType, a.span)),
+                }
+            }
+        };
+    }
+    match nested {
+        Some(d) => declarator_layout(eval, layout, d, flexible),
+        None => layout,
+    }
+}
+
+/// Alignment requested by an alignment specifier
+pub fn alignment(eval: &Evaluator, n: &Node<AlignmentSpecifier>) -> Result<u64> {
+    match n.node {
+        AlignmentSpecifier::Type(ref t) => Ok(try!(type_name_layout(eval, t)).align),
+        AlignmentSpecifier::Constant(ref e) => {
+            let v = try!(eval.evaluate(e));
+            if v.value < 0 || (v.value as u64 & (v.value as u64).wrapping_sub(1)) != 0 {
+                return Err(EvalError::new(EvalErrorKind::InvalidAlignment, e.span));
+            }
+            Ok(v.value as u64)
+        }
+    }
+}
+
+/// Layout-affecting attributes
+#[derive(Debug, Default, Clone)]
+pub struct Attributes {
+    /// `__attribute__((packed))`
+    pub packed: bool,
+    /// Largest alignment requested with `__attribute__((aligned))` or `__declspec(align)`
+    pub aligned: Option<u64>,
+}
+
+/// Collect layout-affecting attributes from a list of extensions
+pub fn attributes<'a, I>(eval: &Evaluator, extensions: I) -> Result<Attributes>
+where
+    I: Iterator<Item = &'a Node<Extension>>,
+{
+    let mut result = Attributes::default();
+    for e in extensions {
+        let (name, argument) = match e.node {
+            Extension::Attribute(ref a) => (attribute_name(&a.name.node), a.arguments.first()),
+            Extension::Declspec(ref a) if a.name.node == "align" => {
+                ("aligned", a.arguments.first())
+            }
+            Extension::StandardAttribute(ref a) => {
+                match a.node.prefix.as_ref().map(|p| attribute_name(&p.node)) {
+                    Some("gnu") => {}
+                    _ => continue,
+                }
+                let name = attribute_name(&a.node.name.node);
+                let tokens = a.node.arguments.as_ref().map_or(&[][..], |a| &a[..]);
+                match (name, tokens.len()) {
+                    ("packed", _) => result.packed = true,
+                    ("aligned", 0) => result.aligned = Some(eval.target().biggest_alignment),
+                    ("aligned", 1) => match tokens[0].node.parse() {
+                        Ok(a) => result.aligned = Some(a),
+                        Err(_) => {
+                            return Err(EvalError::new(EvalErrorKind::NotConstant, tokens[0].span))
+                        }
+                    },
+                    ("aligned", _) => {
+                        return Err(EvalError::new(EvalErrorKind::NotConstant, e.span));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        match name {
+            "packed" => result.packed = true,
+            "aligned" => {
+                let a = match argument {
+                    Some(a) => try!(eval.evaluate(a)).value as u64,
+                    None => eval.target().biggest_alignment,
+                };
+                result.aligned = Some(::std::cmp::max(result.aligned.unwrap_or(0), a));
+            }
+            _ => {}
+        }
+    }
+    Ok(result)
+}
+
+/// Attribute name without the optional leading and trailing double underscores
+fn attribute_name(name: &str) -> &str {
+    if name.len() > 4 && name.starts_with("__") && name.ends_with("__") {
+        &name[2..name.len() - 2]
+    } else {
+        name
+    }
+}
+
+fn declarator_name(n: &Declarator) -> Option<String> {
+    match n.kind.node {
+        DeclaratorKind::Abstract => None,
+        DeclaratorKind::Identifier(ref i) => Some(i.node.name.clone()),
+        DeclaratorKind::Declarator(ref d) => declarator_name(&d.node),
+    }
+}
+
+fn align_to(n: u64, align: u64) -> u64 {
+    if align <= 1 {
+        n
+    } else {
+        (n + align - 1) / align * align
+    }
+}
+
+#[cfg(test)]
+fn layout(model: DataModel, source: &str) -> Layout {
+    use driver::{parse_declaration, Config};
+
+    let decl = parse_declaration(&Config::with_gcc(), source).unwrap();
+    let mut eval = Evaluator::new(Target::new(model));
+    eval.add_declaration(&decl).unwrap();
+    eval.tag("s").unwrap().clone()
+}
+
+#[cfg(test)]
+fn offsets(layout: &Layout) -> Vec<u64> {
+    let record = layout.record.as_ref().unwrap();
+    record.fields.iter().map(|f| f.bit_offset).collect()
+}
+
+#[test]
+fn test_struct() {
+    let s = layout(DataModel::LP64, "struct s { char c; short s; long l; };");
+    assert_eq!((s.size, s.align), (16, 8));
+    assert_eq!(offsets(&s), vec![0, 16, 64]);
+
+    let s = layout(
+        DataModel::ILP32,
+        "struct s { char c; long long l; long double d; };",
+    );
+    assert_eq!((s.size, s.align), (24, 4));
+    assert_eq!(offsets(&s), vec![0, 32, 96]);
+
+    let s = layout(DataModel::LLP64, "struct s { long l; void *p; };");
+    assert_eq!((s.size, s.align), (16, 8));
+
+    let s = layout(DataModel::LP64, "struct s { int n; char data[]; };");
+    assert_eq!((s.size, s.align), (4, 4));
+    assert_eq!(offsets(&s), vec![0, 32]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { int n; char data[];\n#pragma foo\n_Static_assert(1, \"\"); };",
+    );
+    assert_eq!((s.size, s.align), (4, 4));
+    assert_eq!(offsets(&s), vec![0, 32]);
+
+    let s = layout(DataModel::LP64, "struct s { char c; double m[][2]; };");
+    assert_eq!((s.size, s.align), (8, 8));
+    assert_eq!(offsets(&s), vec![0, 64]);
+
+    let s = layout(DataModel::LP64, "union s { char c[5]; int i; };");
+    assert_eq!((s.size, s.align), (8, 4));
+    assert_eq!(offsets(&s), vec![0, 0]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; struct t { short s; } t; };",
+    );
+    assert_eq!((s.size, s.align), (4, 2));
+
+    let s = layout(DataModel::LP64, "struct s { int a; struct { int b; }; };");
+    assert_eq!((s.size, s.align), (8, 4));
+    assert_eq!(offsets(&s), vec![0, 32]);
+
+    let s = layout(DataModel::LP64, "struct s { char c; union { short s; }; };");
+    assert_eq!((s.size, s.align), (4, 2));
+    assert_eq!(offsets(&s), vec![0, 16]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; enum { A = 0x1ffffffff } e; };",
+    );
+    assert_eq!((s.size, s.align), (16, 8));
+    assert_eq!(offsets(&s), vec![0, 64]);
+}
+
+#[test]
+fn test_bit_fields() {
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; int x : 30; int y : 5; };",
+    );
+    assert_eq!((s.size, s.align), (12, 4));
+    assert_eq!(offsets(&s), vec![0, 32, 64]);
+
+    let s = layout(DataModel::LP64, "struct s { char c; int : 0; char d; };");
+    assert_eq!((s.size, s.align), (5, 1));
+    assert_eq!(offsets(&s), vec![0, 32, 32]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char a; char : 4; char b : 6; };",
+    );
+    assert_eq!((s.size, s.align), (3, 1));
+    assert_eq!(offsets(&s), vec![0, 8, 16]);
+
+    let s = layout(DataModel::LP64, "struct s { char c; long long x : 33; };");
+    assert_eq!((s.size, s.align), (8, 8));
+    assert_eq!(offsets(&s), vec![0, 8]);
+}
+
+#[test]
+fn test_attributes() {
+    let s = layout(
+        DataModel::LP64,
+        "struct __attribute__((packed)) s { char c; int i; short s; };",
+    );
+    assert_eq!((s.size, s.align), (7, 1));
+    assert_eq!(offsets(&s), vec![0, 8, 40]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; int i : 4; } __attribute__((__packed__));",
+    );
+    assert_eq!((s.size, s.align), (2, 1));
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; int i __attribute__((aligned(16))); };",
+    );
+    assert_eq!((s.size, s.align), (32, 16));
+    assert_eq!(offsets(&s), vec![0, 128]);
+
+    let s = layout(DataModel::LP64, "struct s { char c; _Alignas(8) char d; };");
+    assert_eq!((s.size, s.align), (16, 8));
+    assert_eq!(offsets(&s), vec![0, 64]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; struct { short s; }; long l; } __attribute__((aligned(32)));",
+    );
+    assert_eq!((s.size, s.align), (32, 32));
+    assert_eq!(offsets(&s), vec![0, 16, 64]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct __attribute__((packed)) s { char a : 3; int b : 7; };",
+    );
+    assert_eq!((s.size, s.align), (2, 1));
+    assert_eq!(offsets(&s), vec![0, 3]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct __attribute__((packed)) s { char c; struct { int i; }; };",
+    );
+    assert_eq!((s.size, s.align), (5, 1));
+    assert_eq!(offsets(&s), vec![0, 8]);
+
+    let s = layout(
+        DataModel::LP64,
+        "struct s { char c; __attribute__((aligned(8))) struct { int i; }; };",
+    );
+    assert_eq!((s.size, s.align), (16, 8));
+    assert_eq!(offsets(&s), vec![0, 64]);
+}
+
+#[test]
+fn test_too_large() {
+    use driver::{parse_declaration, Config};
+
+    let decl =
+        parse_declaration(&Config::with_gcc(), "struct s { char a[1ULL << 62][8]; };").unwrap();
+    let mut eval = Evaluator::new(Target::new(DataModel::LP64));
+    assert_eq!(
+        eval.add_declaration(&decl).unwrap_err().kind,
+        EvalErrorKind::TooLarge
+    );
+}
+
+#[test]
+fn test_typedef() {
+    use driver::{parse_declaration, Config};
+
+    let mut config = Config::with_gcc();
+    config.typedef_names.push("pair".into());
+    let mut eval = Evaluator::new(Target::new(DataModel::LP64));
+    let decl = parse_declaration(&config, "typedef struct s { int a, b; } pair[2];").unwrap();
+    eval.add_declaration(&decl).unwrap();
+    let decl = parse_declaration(&config, "struct t { char c; pair p; };").unwrap();
+    eval.add_declaration(&decl).unwrap();
+
+    let (pair, integer) = eval.typedef("pair").unwrap().unwrap();
+    assert_eq!((pair.size, pair.align, integer), (16, 4, None));
+    let t = eval.tag("t").unwrap();
+    assert_eq!((t.size, t.align), (20, 4));
+
+    let decl = parse_declaration(&config, "struct u { struct v *p; struct v v; };").unwrap();
+    assert_eq!(
+        eval.add_declaration(&decl).unwrap_err().kind,
+        EvalErrorKind::IncompleteType
+    );
+
+    // Typedef of a structure that is completed later
+    config.typedef_names.push("node".into());
+    let decl = parse_declaration(&config, "typedef struct node node;").unwrap();
+    eval.add_declaration(&decl).unwrap();
+    let decl = parse_declaration(&config, "struct node { node *next; int value; };").unwrap();
+    eval.add_declaration(&decl).unwrap();
+    let (node, _) = eval.typedef("node").unwrap().unwrap();
+    assert_eq!((node.size, node.align), (16, 8));
+}
@@ -13,10 +13,19 @@
 #![allow(ellipsis_inclusive_range_patterns)]
 
 pub mod ast;
+pub mod check;
+pub mod cpp;
+pub mod diagnostics;
 pub mod driver;
+pub mod eval;
+pub mod layout;
+pub mod literal;
 pub mod loc;
 pub mod print;
+pub mod resolve;
 pub mod span;
+pub mod types;
+pub mod unparse;
 pub mod visit;
 
 mod astutil;
@@ -0,0 +1,1173 @@
+//! Values of constants and string literals
+//!
+//! The syntax tree keeps literals as they are written in the source, since their values depend
+//! on the target, for example on the width of `wchar_t`. This module computes
+//! values and types of integer constants (C11 6.4.4.1), converts floating constants to binary
+//! formats (C11 6.4.4.2), and decodes escape sequences of character constants and string
+//! literals, concatenating string literal pieces (C11 6.4.4.4, 6.4.5).
+//!
+//! ```
+//! # use lang_c::driver::{parse_expression, Config};
+//! # use lang_c::ast::Expression;
+//! # use lang_c::layout::{DataModel, Target};
+//! # use lang_c::literal::{Encoding, StringValue};
+//! let source = r#"u"café" "\x21""#;
+//! let expr = parse_expression(&Config::default(), source).unwrap();
+//! let literal = match expr.node {
+//!     Expression::StringLiteral(s) => s,
+//!     _ => unreachable!(),
+//! };
+//! let target = Target::new(DataModel::LP64);
+//! let value = StringValue::decode(&target, &literal, Some(source)).unwrap();
+//! assert_eq!(value.encoding, Encoding::Utf16);
+//! assert_eq!(value.units, vec![0x63, 0x61, 0x66, 0xe9, 0x21]);
+//! assert_eq!(value.code_points(&target).unwrap(), "café!");
+//! ```
+
+use std::error;
+use std::fmt;
+
+use ast::*;
+use eval::IntegerType;
+use layout::Target;
+use span::{Node, Span};
+use types::{BitIntType, CType};
+
+macro_rules! otry {
+    ($e:expr) => {
+        match $e {
+            Some(v) => v,
+            None => return None,
+        }
+    };
+}
+
+/// Encoding prefix of a character constant or a string literal
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Encoding {
+    /// No prefix
+    Plain,
+    /// `u8`
+    Utf8,
+    /// `u`
+    Utf16,
+    /// `U`
+    Utf32,
+    /// `L`
+    Wide,
+}
+
+impl Encoding {
+    pub fn from_prefix(prefix: &str) -> Option<Encoding> {
+        match prefix {
+            "" => Some(Encoding::Plain),
+            "u8" => Some(Encoding::Utf8),
+            "u" => Some(Encoding::Utf16),
+            "U" => Some(Encoding::Utf32),
+            "L" => Some(Encoding::Wide),
+            _ => None,
+        }
+    }
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Encoding::Plain => "",
+            Encoding::Utf8 => "u8",
+            Encoding::Utf16 => "u",
+            Encoding::Utf32 => "U",
+            Encoding::Wide => "L",
+        }
+    }
+
+    /// Type of a code unit, the element type of string literals with this prefix
+    pub fn unit_type(self, target: &Target) -> IntegerType {
+        match self {
+            Encoding::Plain | Encoding::Utf8 => IntegerType::Char,
+            Encoding::Utf16 => IntegerType::UnsignedShort,
+            Encoding::Utf32 => IntegerType::UnsignedInt,
+            Encoding::Wide => target.wchar_type(),
+        }
+    }
+
+    /// Encoding of two adjacent string literal pieces after concatenation
+    ///
+    /// An unprefixed piece takes the prefix of the other one, pieces with different prefixes
+    /// can not be concatenated (C11 6.4.5 §5, C23 6.4.5 §6).
+    pub fn concatenate(self, other: Encoding) -> Option<Encoding> {
+        match (self, other) {
+            (Encoding::Plain, e) | (e, Encoding::Plain) => Some(e),
+            (a, b) if a == b => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Largest value of a code unit
+    fn max_unit(self, target: &Target) -> u64 {
+        (1 << (target.integer_size(self.unit_type(target)) * 8)) - 1
+    }
+}
+
+/// Kind of an error in a literal
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LiteralErrorKind {
+    /// Literal does not have the form `prefix "body"` or `prefix 'body'`
+    Malformed,
+    /// Unknown escape sequence, or hexadecimal escape or universal character name without enough
+    /// digits
+    InvalidEscape,
+    /// Universal character name does not designate a character
+    InvalidCharacter,
+    /// Value of a numeric escape sequence does not fit in a code unit
+    OutOfRange,
+    /// Numeric constant is too large for its type
+    TooLarge,
+    /// Character constant without characters
+    Empty,
+    /// String literal pieces with different prefixes
+    IncompatibleEncodings(Encoding, Encoding),
+}
+
+/// Error in a literal
+#[derive(Debug, PartialEq, Clone)]
+pub struct LiteralError {
+    pub kind: LiteralErrorKind,
+    /// Offending escape sequence or piece
+    pub span: Span,
+}
+
+impl LiteralError {
+    fn new(kind: LiteralErrorKind, span: Span) -> LiteralError {
+        LiteralError { kind, span }
+    }
+}
+
+impl fmt::Display for LiteralError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            LiteralErrorKind::Malformed => write!(fmt, "malformed literal"),
+            LiteralErrorKind::InvalidEscape => write!(fmt, "invalid escape sequence"),
+            LiteralErrorKind::InvalidCharacter => {
+                write!(fmt, "universal character name is not a valid character")
+            }
+            LiteralErrorKind::OutOfRange => write!(fmt, "escape sequence out of range"),
+            LiteralErrorKind::TooLarge => write!(fmt, "constant is too large for its type"),
+            LiteralErrorKind::Empty => write!(fmt, "empty character constant"),
+            LiteralErrorKind::IncompatibleEncodings(a, b) => write!(
+                fmt,
+                "can not concatenate string literals with prefixes '{}' and '{}'",
+                a.prefix(),
+                b.prefix()
+            ),
+        }
+    }
+}
+
+impl error::Error for LiteralError {
+    fn description(&self) -> &str {
+        "invalid literal"
+    }
+}
+
+pub type Result<T> = ::std::result::Result<T, LiteralError>;
+
+/// Decoded character constant
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CharacterValue {
+    pub encoding: Encoding,
+    /// Code units, more than one for multi-character constants and for characters that take
+    /// several units in the encoding
+    pub units: Vec<u32>,
+}
+
+impl CharacterValue {
+    /// Decode a character constant
+    ///
+    /// `raw` is the constant as written (`Constant::Character`) and `span` is its location.
+    pub fn decode(target: &Target, raw: &str, span: Span) -> Result<CharacterValue> {
+        let piece = try!(split(raw, '\'', Some(span.start), span));
+        let mut units = Vec::new();
+        for (element, span) in try!(split_elements(piece.body, piece.body_start, span)) {
+            try!(encode(target, piece.encoding, element, span, &mut units));
+        }
+        if units.is_empty() {
+            return Err(LiteralError::new(LiteralErrorKind::Empty, span));
+        }
+        Ok(CharacterValue {
+            encoding: piece.encoding,
+            units,
+        })
+    }
+}
+
+/// Decoded and concatenated string literal
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StringValue {
+    pub encoding: Encoding,
+    /// Code units without the terminating null character
+    pub units: Vec<u32>,
+}
+
+impl StringValue {
+    /// Decode a string literal
+    ///
+    /// Escape sequences in unprefixed pieces are decoded in the encoding of the whole literal.
+    /// Spans of errors in all pieces but the first are only exact if `source`, the text the
+    /// literal was parsed from, is given. Otherwise such errors are reported at the span of the
+    /// whole literal.
+    pub fn decode(
+        target: &Target,
+        n: &Node<StringLiteral>,
+        source: Option<&str>,
+    ) -> Result<StringValue> {
+        let starts = piece_starts(n, source);
+        let mut elements = Vec::new();
+        let mut encoding = Encoding::Plain;
+        for (raw, start) in n.node.iter().zip(starts) {
+            let span = match start {
+                Some(start) => Span::span(start, start + raw.len()),
+                None => n.span,
+            };
+            let piece = try!(split(raw, '"', start, span));
+            encoding = match encoding.concatenate(piece.encoding) {
+                Some(e) => e,
+                None => {
+                    let kind = LiteralErrorKind::IncompatibleEncodings(encoding, piece.encoding);
+                    return Err(LiteralError::new(kind, span));
+                }
+            };
+            elements.extend(try!(split_elements(piece.body, piece.body_start, span)));
+        }
+
+        let mut units = Vec::new();
+        for (element, span) in elements {
+            try!(encode(target, encoding, element, span, &mut units));
+        }
+        Ok(StringValue { encoding, units })
+    }
+
+    /// Size of the array in bytes, including the terminating null character
+    pub fn size(&self, target: &Target) -> u64 {
+        (self.units.len() as u64 + 1) * target.integer_size(self.encoding.unit_type(target))
+    }
+
+    /// Code units as bytes, for plain and UTF-8 strings
+    pub fn bytes(&self) -> Option<Vec<u8>> {
+        match self.encoding {
+            Encoding::Plain | Encoding::Utf8 => Some(self.units.iter().map(|&u| u as u8).collect()),
+            _ => None,
+        }
+    }
+
+    /// Characters of the string, `None` if code units are not valid in the encoding
+    ///
+    /// Plain strings are assumed to be UTF-8 encoded, wide strings are UTF-16 if `wchar_t` is
+    /// two bytes wide and UTF-32 otherwise.
+    pub fn code_points(&self, target: &Target) -> Option<String> {
+        let width = target.integer_size(self.encoding.unit_type(target));
+        match width {
+            1 => String::from_utf8(self.units.iter().map(|&u| u as u8).collect()).ok(),
+            2 => {
+                let units = self.units.iter().map(|&u| u as u16);
+                ::std::char::decode_utf16(units)
+                    .collect::<::std::result::Result<_, _>>()
+                    .ok()
+            }
+            _ => self
+                .units
+                .iter()
+                .map(|&u| ::std::char::from_u32(u))
+                .collect(),
+        }
+    }
+}
+
+/// Literal token split into prefix and body
+struct Piece<'a> {
+    encoding: Encoding,
+    body: &'a str,
+    /// Offset of the body in the source, `None` if not known
+    body_start: Option<usize>,
+}
+
+/// Split a literal token, `start` is its offset in the source if known and `span` is used for
+/// errors otherwise
+fn split<'a>(raw: &'a str, quote: char, start: Option<usize>, span: Span) -> Result<Piece<'a>> {
+    let malformed = || LiteralError::new(LiteralErrorKind::Malformed, span);
+    let open = try!(raw.find(quote).ok_or_else(&malformed));
+    let encoding = try!(Encoding::from_prefix(&raw[..open]).ok_or_else(&malformed));
+    if raw.len() < open + 2 || !raw.ends_with(quote) {
+        return Err(malformed());
+    }
+    Ok(Piece {
+        encoding,
+        body: &raw[open + 1..raw.len() - 1],
+        body_start: start.map(|s| s + open + 1),
+    })
+}
+
+/// Offsets of string literal pieces in the source
+fn piece_starts(n: &Node<StringLiteral>, source: Option<&str>) -> Vec<Option<usize>> {
+    let mut starts = vec![None; n.node.len()];
+    match source.and_then(|s| s.get(n.span.start..n.span.end)) {
+        Some(text) => {
+            let mut offset = 0;
+            for (start, piece) in starts.iter_mut().zip(&n.node) {
+                match text[offset..].find(piece.as_str()) {
+                    Some(i) => {
+                        *start = Some(n.span.start + offset + i);
+                        offset += i + piece.len();
+                    }
+                    None => break,
+                }
+            }
+        }
+        None => {
+            if let Some(start) = starts.first_mut() {
+                *start = Some(n.span.start);
+            }
+        }
+    }
+    starts
+}
+
+/// Character or numeric escape in a literal body
+#[derive(Debug, Clone, Copy)]
+enum Element {
+    /// Source character or universal character name, encoded according to the prefix
+    Char(char),
+    /// Octal or hexadecimal escape sequence, a single code unit
+    Unit(u64),
+}
+
+/// Split a literal body into characters and code units, with their spans
+///
+/// `start` is the offset of the body in the source, elements get the `fallback` span if it is
+/// not known.
+fn split_elements(
+    body: &str,
+    start: Option<usize>,
+    fallback: Span,
+) -> Result<Vec<(Element, Span)>> {
+    let span = |from: usize, to: usize| match start {
+        Some(start) => Span::span(start + from, start + to),
+        None => fallback,
+    };
+    let mut elements = Vec::new();
+    let mut chars = body.char_indices().peekable();
+    while let Some((from, c)) = chars.next() {
+        if c != '\\' {
+            elements.push((Element::Char(c), span(from, from + c.len_utf8())));
+            continue;
+        }
+        let end = |chars: &mut ::std::iter::Peekable<::std::str::CharIndices>| {
+            chars.peek().map(|&(i, _)| i).unwrap_or(body.len())
+        };
+        let invalid = |to| LiteralError::new(LiteralErrorKind::InvalidEscape, span(from, to));
+        let element = match chars.next() {
+            Some((_, '\'')) => Element::Char('\''),
+            Some((_, '"')) => Element::Char('"'),
+            Some((_, '?')) => Element::Char('?'),
+            Some((_, '\\')) => Element::Char('\\'),
+            Some((_, 'a')) => Element::Unit(7),
+            Some((_, 'b')) => Element::Unit(8),
+            Some((_, 'f')) => Element::Unit(12),
+            Some((_, 'n')) => Element::Unit(10),
+            Some((_, 'r')) => Element::Unit(13),
+            Some((_, 't')) => Element::Unit(9),
+            Some((_, 'v')) => Element::Unit(11),
+            // GNU extension
+            Some((_, 'e')) | Some((_, 'E')) => Element::Unit(27),
+            Some((_, c @ '0'...'7')) => {
+                let mut v = c.to_digit(8).unwrap() as u64;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|&(_, c)| c.to_digit(8)) {
+                        Some(d) => v = v * 8 + d as u64,
+                        None => break,
+                    }
+                    chars.next();
+                }
+                Element::Unit(v)
+            }
+            Some((_, 'x')) => {
+                let mut v: u64 = 0;
+                let mut digits = 0;
+                while let Some(d) = chars.peek().and_then(|&(_, c)| c.to_digit(16)) {
+                    // Saturate, the value is out of range for any code unit anyway
+                    v = v.saturating_mul(16).saturating_add(d as u64);
+                    digits += 1;
+                    chars.next();
+                }
+                if digits == 0 {
+                    return Err(invalid(end(&mut chars)));
+                }
+                Element::Unit(v)
+            }
+            Some((_, u @ 'u')) | Some((_, u @ 'U')) => {
+                let len = if u == 'u' { 4 } else { 8 };
+                let mut v = 0;
+                for _ in 0..len {
+                    match chars.peek().and_then(|&(_, c)| c.to_digit(16)) {
+                        Some(d) => v = v * 16 + d,
+                        None => return Err(invalid(end(&mut chars))),
+                    }
+                    chars.next();
+                }
+                match ::std::char::from_u32(v) {
+                    Some(c) => Element::Char(c),
+                    None => {
+                        let kind = LiteralErrorKind::InvalidCharacter;
+                        return Err(LiteralError::new(kind, span(from, end(&mut chars))));
+                    }
+                }
+            }
+            _ => return Err(invalid(end(&mut chars))),
+        };
+        elements.push((element, span(from, end(&mut chars))));
+    }
+    Ok(elements)
+}
+
+/// Append code units of an element in the given encoding
+fn encode(
+    target: &Target,
+    encoding: Encoding,
+    element: Element,
+    span: Span,
+    units: &mut Vec<u32>,
+) -> Result<()> {
+    let max = encoding.max_unit(target);
+    match element {
+        Element::Unit(v) if v > max => {
+            return Err(LiteralError::new(LiteralErrorKind::OutOfRange, span))
+        }
+        Element::Unit(v) => units.push(v as u32),
+        Element::Char(c) if max <= 0xff => {
+            let mut buf = [0; 4];
+            units.extend(c.encode_utf8(&mut buf).bytes().map(|b| b as u32));
+        }
+        Element::Char(c) if max <= 0xffff => {
+            let mut buf = [0; 2];
+            units.extend(c.encode_utf16(&mut buf).iter().map(|&u| u as u32));
+        }
+        Element::Char(c) => units.push(c as u32),
+    }
+    Ok(())
+}
+
+/// Value of an integer constant, `None` if it does not fit in 128 bits
+pub fn integer_value(n: &Integer) -> Option<u128> {
+    let radix = match n.base {
+        IntegerBase::Decimal => 10,
+        IntegerBase::Octal => 8,
+        IntegerBase::Hexadecimal => 16,
+        IntegerBase::Binary => 2,
+    };
+    let mut value: u128 = 0;
+    for c in n.number.chars() {
+        let digit = otry!(c.to_digit(radix));
+        value = otry!(value
+            .checked_mul(radix as u128)
+            .and_then(|v| v.checked_add(digit as u128)));
+    }
+    Some(value)
+}
+
+/// Type of an integer constant with the given value (C11 6.4.4.1, C23 6.4.4.1)
+///
+/// This is the first standard integer type in the list for the suffix and base of the constant
+/// that can represent the value, or `_BitInt(N)` of the smallest width for constants with the
+/// `wb` suffix. Returns `None` if no type can represent the value. The imaginary suffix is
+/// ignored.
+pub fn integer_type(target: &Target, n: &Integer, value: u128) -> Option<CType> {
+    use eval::IntegerType::*;
+
+    let unsigned = n.suffix.unsigned;
+    let decimal = n.base == IntegerBase::Decimal;
+    let candidates: &[IntegerType] = match (n.suffix.size, unsigned, decimal) {
+        (IntegerSize::Int, false, true) => &[Int, Long, LongLong],
+        (IntegerSize::Int, false, false) => &[
+            Int,
+            UnsignedInt,
+            Long,
+            UnsignedLong,
+            LongLong,
+            UnsignedLongLong,
+        ],
+        (IntegerSize::Int, true, _) => &[UnsignedInt, UnsignedLong, UnsignedLongLong],
+        (IntegerSize::Long, false, true) => &[Long, LongLong],
+        (IntegerSize::Long, false, false) => &[Long, UnsignedLong, LongLong, UnsignedLongLong],
+        (IntegerSize::Long, true, _) => &[UnsignedLong, UnsignedLongLong],
+        (IntegerSize::LongLong, false, true) => &[LongLong],
+        (IntegerSize::LongLong, false, false) => &[LongLong, UnsignedLongLong],
+        (IntegerSize::LongLong, true, _) => &[UnsignedLongLong],
+        (IntegerSize::BitInt, _, _) => {
+            // Sign bit and at least one value bit for signed types
+            let bits = 128 - value.leading_zeros() as u64;
+            let width = match unsigned {
+                true => bits.max(1),
+                false => (bits + 1).max(2),
+            };
+            return Some(CType::BitInt(BitIntType {
+                signed: !unsigned,
+                width,
+            }));
+        }
+    };
+    candidates
+        .iter()
+        .find(|&&ty| {
+            let bits = target.integer_size(ty) * 8 - target.is_signed(ty) as u64;
+            bits >= 128 || value >> bits == 0
+        })
+        .map(|&ty| CType::Integer(ty))
+}
+
+/// Integer constant with its value and type
+#[derive(Debug, PartialEq, Clone)]
+pub struct IntegerValue {
+    pub value: u128,
+    /// `CType::Integer` or `CType::BitInt`
+    pub ty: CType,
+}
+
+impl IntegerValue {
+    /// Decode an integer constant located at `span`
+    pub fn decode(target: &Target, n: &Integer, span: Span) -> Result<IntegerValue> {
+        let too_large = || LiteralError::new(LiteralErrorKind::TooLarge, span);
+        let value = try!(integer_value(n).ok_or_else(&too_large));
+        let ty = try!(integer_type(target, n, value).ok_or_else(&too_large));
+        Ok(IntegerValue { value, ty })
+    }
+}
+
+/// Binary floating point formats
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum BinaryFormat {
+    /// IEEE 754 binary16
+    Binary16,
+    /// IEEE 754 binary32, `float` on common targets
+    Binary32,
+    /// IEEE 754 binary64, `double` on common targets
+    Binary64,
+    /// x87 80-bit extended precision format with an explicit integer bit
+    Extended80,
+    /// IEEE 754 binary128
+    Binary128,
+}
+
+impl BinaryFormat {
+    /// Format of a floating constant, if it does not depend on the target
+    ///
+    /// `None` for `long double`, `_FloatNx` and decimal floating constants.
+    pub fn for_literal(n: &Float) -> Option<BinaryFormat> {
+        match n.suffix.format {
+            FloatFormat::Float => Some(BinaryFormat::Binary32),
+            FloatFormat::Double => Some(BinaryFormat::Binary64),
+            FloatFormat::LongDouble => None,
+            FloatFormat::TS18661Format(t) => match (t.format, t.width) {
+                (TS18661FloatFormat::BinaryInterchange, 16) => Some(BinaryFormat::Binary16),
+                (TS18661FloatFormat::BinaryInterchange, 32) => Some(BinaryFormat::Binary32),
+                (TS18661FloatFormat::BinaryInterchange, 64) => Some(BinaryFormat::Binary64),
+                (TS18661FloatFormat::BinaryInterchange, 128) => Some(BinaryFormat::Binary128),
+                _ => None,
+            },
+        }
+    }
+
+    /// Number of significand bits, including the leading bit
+    fn precision(self) -> i64 {
+        match self {
+            BinaryFormat::Binary16 => 11,
+            BinaryFormat::Binary32 => 24,
+            BinaryFormat::Binary64 => 53,
+            BinaryFormat::Extended80 => 64,
+            BinaryFormat::Binary128 => 113,
+        }
+    }
+
+    /// Largest exponent, also the exponent bias
+    fn max_exponent(self) -> i64 {
+        match self {
+            BinaryFormat::Binary16 => 15,
+            BinaryFormat::Binary32 => 127,
+            BinaryFormat::Binary64 => 1023,
+            BinaryFormat::Extended80 | BinaryFormat::Binary128 => 16383,
+        }
+    }
+
+    /// Width of the field after the exponent
+    fn fraction_bits(self) -> i64 {
+        match self {
+            BinaryFormat::Extended80 => 64,
+            f => f.precision() - 1,
+        }
+    }
+}
+
+/// Floating constant converted to a binary format
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FloatValue {
+    pub format: BinaryFormat,
+    /// Encoding of the value, in the low bits
+    pub bits: u128,
+}
+
+impl FloatValue {
+    /// Convert a floating constant located at `span` to the given format
+    ///
+    /// The value is rounded to nearest, ties to even. Hexadecimal constants are exact if the
+    /// format has enough precision. Values too large for the format are an error.
+    pub fn decode(n: &Float, format: BinaryFormat, span: Span) -> Result<FloatValue> {
+        let malformed = || LiteralError::new(LiteralErrorKind::Malformed, span);
+        let (radix, marker) = match n.base {
+            FloatBase::Decimal => (10, 'e'),
+            FloatBase::Hexadecimal => (16, 'p'),
+        };
+        let number = n.number.to_ascii_lowercase();
+        let (significand, exponent) = match number.find(marker) {
+            Some(i) => (
+                &number[..i],
+                try!(parse_exponent(&number[i + 1..]).ok_or_else(&malformed)),
+            ),
+            None if radix == 10 => (&number[..], 0),
+            None => return Err(malformed()),
+        };
+
+        let mut digits = Big::zero();
+        let mut fraction_digits = 0;
+        let mut point = false;
+        for c in significand.chars() {
+            match (c, c.to_digit(radix)) {
+                ('.', _) if !point => point = true,
+                (_, Some(d)) => {
+                    digits.mul_add(radix, d);
+                    fraction_digits += point as i64;
+                }
+                _ => return Err(malformed()),
+            }
+        }
+
+        let bits = match radix {
+            16 => round(&digits, exponent - 4 * fraction_digits, false, format),
+            _ => decimal_to_binary(digits, exponent - fraction_digits, format),
+        };
+        match bits {
+            Some(bits) => Ok(FloatValue { format, bits }),
+            None => Err(LiteralError::new(LiteralErrorKind::TooLarge, span)),
+        }
+    }
+
+    pub fn to_f32(&self) -> Option<f32> {
+        match self.format {
+            BinaryFormat::Binary32 => Some(f32::from_bits(self.bits as u32)),
+            _ => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> Option<f64> {
+        match self.format {
+            BinaryFormat::Binary64 => Some(f64::from_bits(self.bits as u64)),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a decimal exponent, saturating far beyond the range of any format
+fn parse_exponent(s: &str) -> Option<i64> {
+    let (negative, digits) = match s.as_bytes().first() {
+        Some(&b'-') => (true, &s[1..]),
+        Some(&b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let mut value: i64 = 0;
+    for c in digits.chars() {
+        value = (value * 10 + otry!(c.to_digit(10)) as i64).min(1 << 40);
+    }
+    Some(if negative { -value } else { value })
+}
+
+/// Round `digits * 10^exponent` to a binary format
+fn decimal_to_binary(digits: Big, exponent: i64, format: BinaryFormat) -> Option<u128> {
+    if digits.is_zero() {
+        return Some(0);
+    }
+    // Decimal exponents of the largest and the smallest values of all formats are within
+    // 4951 of zero
+    let magnitude = exponent + digits.bit_len() as i64 * 30103 / 100000;
+    if magnitude > 5000 {
+        return None;
+    }
+    if magnitude < -5000 {
+        return Some(0);
+    }
+    if exponent >= 0 {
+        let mut n = digits;
+        for _ in 0..exponent {
+            n.mul_add(10, 0);
+        }
+        return round(&n, 0, false, format);
+    }
+
+    let mut divisor = Big::one();
+    for _ in 0..-exponent {
+        divisor.mul_add(10, 0);
+    }
+    // Enough quotient bits for the precision of the format and the rounding bit
+    let shift =
+        (format.precision() + 2 + divisor.bit_len() as i64 - digits.bit_len() as i64).max(0);
+    let (quotient, exact) = digits.shl(shift as usize).div(&divisor);
+    round(&quotient, -shift, !exact, format)
+}
+
+/// Encode `n * 2^exponent` in a binary format, rounding to nearest even
+///
+/// `sticky` means the value is slightly larger than that. Returns `None` on overflow.
+fn round(n: &Big, exponent: i64, sticky: bool, format: BinaryFormat) -> Option<u128> {
+    if n.is_zero() {
+        return Some(0);
+    }
+    let precision = format.precision();
+    let min_exponent = 1 - format.max_exponent();
+    let len = n.bit_len() as i64;
+    let leading = len - 1 + exponent;
+    // Subnormal numbers have fewer significand bits
+    let keep = precision - (min_exponent - leading).max(0);
+    let shift = len - keep;
+
+    let mut significand: u128 = 0;
+    for i in (shift.max(0)..len).rev() {
+        significand = significand << 1 | n.bit(i as usize) as u128;
+    }
+    if shift < 0 {
+        significand <<= -shift;
+    } else if shift > 0 {
+        let half = n.bit(shift as usize - 1);
+        let rest = sticky || n.any_below(shift as usize - 1);
+        if half && (rest || significand & 1 == 1) {
+            significand += 1;
+        }
+    }
+    let mut lsb = exponent + shift;
+    if significand >> precision != 0 {
+        significand >>= 1;
+        lsb += 1;
+    }
+
+    let fraction_bits = format.fraction_bits();
+    if significand >> (precision - 1) == 0 {
+        // Subnormal or zero
+        return Some(significand);
+    }
+    let biased = lsb + precision - 1 + format.max_exponent();
+    if biased > 2 * format.max_exponent() {
+        return None;
+    }
+    let fraction = match format {
+        BinaryFormat::Extended80 => significand,
+        _ => significand & ((1 << fraction_bits) - 1),
+    };
+    Some((biased as u128) << fraction_bits | fraction)
+}
+
+/// Unsigned integer of arbitrary size, for exact conversion of floating constants
+#[derive(Debug, Clone)]
+struct Big {
+    /// Little-endian 32-bit limbs without trailing zeros
+    limbs: Vec<u32>,
+}
+
+impl Big {
+    fn zero() -> Big {
+        Big { limbs: Vec::new() }
+    }
+
+    fn one() -> Big {
+        Big { limbs: vec![1] }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn bit_len(&self) -> usize {
+        match self.limbs.last() {
+            Some(&top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        match self.limbs.get(i / 32) {
+            Some(&limb) => limb >> (i % 32) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// Test if any bit below bit `i` is set
+    fn any_below(&self, i: usize) -> bool {
+        let limb = i / 32;
+        if self.limbs[..limb.min(self.limbs.len())]
+            .iter()
+            .any(|&l| l != 0)
+        {
+            return true;
+        }
+        match self.limbs.get(limb) {
+            Some(&l) => l & ((1 << (i % 32)) - 1) != 0,
+            None => false,
+        }
+    }
+
+    /// `self = self * m + a`
+    fn mul_add(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        for limb in &mut self.limbs {
+            let v = *limb as u64 * m as u64 + carry;
+            *limb = v as u32;
+            carry = v >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    fn shl(&self, bits: usize) -> Big {
+        if self.is_zero() {
+            return Big::zero();
+        }
+        let mut limbs = vec![0; bits / 32];
+        let shift = bits % 32;
+        let mut carry = 0;
+        for &limb in &self.limbs {
+            limbs.push(limb << shift | carry);
+            carry = match shift {
+                0 => 0,
+                _ => limb >> (32 - shift),
+            };
+        }
+        if carry != 0 {
+            limbs.push(carry);
+        }
+        Big { limbs }
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    fn ge(&self, other: &Big) -> bool {
+        if self.limbs.len() != other.limbs.len() {
+            return self.limbs.len() > other.limbs.len();
+        }
+        for (a, b) in self.limbs.iter().rev().zip(other.limbs.iter().rev()) {
+            if a != b {
+                return a > b;
+            }
+        }
+        true
+    }
+
+    fn sub(&mut self, other: &Big) {
+        let mut borrow = 0;
+        for i in 0..self.limbs.len() {
+            let b = other.limbs.get(i).cloned().unwrap_or(0) as i64;
+            let v = self.limbs[i] as i64 - b - borrow;
+            borrow = (v < 0) as i64;
+            self.limbs[i] = v as u32;
+        }
+        self.trim();
+    }
+
+    /// Quotient and whether the remainder is zero, by binary long division
+    fn div(&self, divisor: &Big) -> (Big, bool) {
+        let len = self.bit_len();
+        let mut quotient = Big {
+            limbs: vec![0; len / 32 + 1],
+        };
+        let mut remainder = Big::zero();
+        for i in (0..len).rev() {
+            remainder = remainder.shl(1);
+            if self.bit(i) {
+                if remainder.is_zero() {
+                    remainder.limbs.push(1);
+                } else {
+                    remainder.limbs[0] |= 1;
+                }
+            }
+            if remainder.ge(divisor) {
+                remainder.sub(divisor);
+                quotient.limbs[i / 32] |= 1 << (i % 32);
+            }
+        }
+        quotient.trim();
+        (quotient, remainder.is_zero())
+    }
+}
+
+#[cfg(test)]
+fn string(model: ::layout::DataModel, source: &str) -> Result<StringValue> {
+    use ast::Expression;
+    use driver::{parse_expression, Config};
+
+    let expr = parse_expression(&Config::with_gcc(), source).unwrap();
+    match expr.node {
+        Expression::StringLiteral(s) => StringValue::decode(&Target::new(model), &s, Some(source)),
+        _ => panic!("not a string literal: {}", source),
+    }
+}
+
+#[cfg(test)]
+fn character(source: &str) -> Result<CharacterValue> {
+    let target = Target::new(::layout::DataModel::LP64);
+    CharacterValue::decode(&target, source, Span::span(0, source.len()))
+}
+
+#[test]
+fn test_character() {
+    let c = |e, u: &[u32]| {
+        Ok(CharacterValue {
+            encoding: e,
+            units: u.to_vec(),
+        })
+    };
+    assert_eq!(character("'a'"), c(Encoding::Plain, &[97]));
+    assert_eq!(character("'ab'"), c(Encoding::Plain, &[97, 98]));
+    assert_eq!(character(r"'\377'"), c(Encoding::Plain, &[255]));
+    assert_eq!(character(r"'\0'"), c(Encoding::Plain, &[0]));
+    assert_eq!(character("'é'"), c(Encoding::Plain, &[0xc3, 0xa9]));
+    assert_eq!(character("u8'a'"), c(Encoding::Utf8, &[97]));
+    assert_eq!(character("u'é'"), c(Encoding::Utf16, &[0xe9]));
+    assert_eq!(
+        character(r"u'\U0001F600'"),
+        c(Encoding::Utf16, &[0xd83d, 0xde00])
+    );
+    assert_eq!(character(r"U'\U0001F600'"), c(Encoding::Utf32, &[0x1f600]));
+    assert_eq!(character(r"L'\xffff'"), c(Encoding::Wide, &[0xffff]));
+
+    let e = |kind, start, end| Err(LiteralError::new(kind, Span::span(start, end)));
+    assert_eq!(character("''"), e(LiteralErrorKind::Empty, 0, 2));
+    assert_eq!(character("x'a'"), e(LiteralErrorKind::Malformed, 0, 4));
+    assert_eq!(
+        character(r"'a\q'"),
+        e(LiteralErrorKind::InvalidEscape, 2, 4)
+    );
+    assert_eq!(character(r"'\x'"), e(LiteralErrorKind::InvalidEscape, 1, 3));
+    assert_eq!(
+        character(r"'\u12'"),
+        e(LiteralErrorKind::InvalidEscape, 1, 5)
+    );
+    assert_eq!(
+        character(r"'\ud800'"),
+        e(LiteralErrorKind::InvalidCharacter, 1, 7)
+    );
+    assert_eq!(character(r"'\x100'"), e(LiteralErrorKind::OutOfRange, 1, 6));
+    assert_eq!(
+        character(r"u'\x10000'"),
+        e(LiteralErrorKind::OutOfRange, 2, 9)
+    );
+}
+
+#[test]
+fn test_string() {
+    use layout::DataModel::{LLP64, LP64};
+
+    let s = |e, u: &[u32]| {
+        Ok(StringValue {
+            encoding: e,
+            units: u.to_vec(),
+        })
+    };
+    assert_eq!(string(LP64, r#""a\tb""#), s(Encoding::Plain, &[97, 9, 98]));
+    assert_eq!(string(LP64, r#""a" "b""#), s(Encoding::Plain, &[97, 98]));
+    assert_eq!(
+        string(LP64, r#"u8"é" "\x80""#),
+        s(Encoding::Utf8, &[0xc3, 0xa9, 0x80])
+    );
+    assert_eq!(
+        string(LP64, r#""\x100" L"é""#),
+        s(Encoding::Wide, &[0x100, 0xe9])
+    );
+    assert_eq!(
+        string(LP64, r#"U"a" "\U0001F600""#),
+        s(Encoding::Utf32, &[97, 0x1f600])
+    );
+    assert_eq!(
+        string(LLP64, r#"L"\U0001F600""#),
+        s(Encoding::Wide, &[0xd83d, 0xde00])
+    );
+
+    let target = Target::new(LP64);
+    let v = string(LP64, r#"L"ab" "c""#).unwrap();
+    assert_eq!(v.size(&target), 16);
+    assert_eq!(v.bytes(), None);
+    assert_eq!(v.code_points(&target), Some("abc".to_owned()));
+    let v = string(LP64, r#""\xff""#).unwrap();
+    assert_eq!(v.bytes(), Some(vec![0xff]));
+    assert_eq!(v.code_points(&target), None);
+
+    let e = |kind, start, end| Err(LiteralError::new(kind, Span::span(start, end)));
+    assert_eq!(
+        string(LP64, r#""a"  "\x100""#),
+        e(LiteralErrorKind::OutOfRange, 6, 11)
+    );
+    assert_eq!(
+        string(LP64, r#"u"a" "b" L"c""#),
+        e(
+            LiteralErrorKind::IncompatibleEncodings(Encoding::Utf16, Encoding::Wide),
+            9,
+            13
+        )
+    );
+    assert_eq!(
+        string(LLP64, r#"L"\x10000""#),
+        e(LiteralErrorKind::OutOfRange, 2, 9)
+    );
+}
+
+#[cfg(test)]
+fn integer(source: &str) -> Result<IntegerValue> {
+    use driver::{parse_expression, Config, Flavor};
+
+    let mut config = Config::with_gcc();
+    config.flavor = Flavor::GnuC23;
+    let expr = parse_expression(&config, source).unwrap();
+    match expr.node {
+        ::ast::Expression::Constant(c) => match c.node {
+            Constant::Integer(ref i) => {
+                IntegerValue::decode(&Target::new(::layout::DataModel::LP64), i, c.span)
+            }
+            _ => panic!("not an integer constant: {}", source),
+        },
+        _ => panic!("not a constant: {}", source),
+    }
+}
+
+#[cfg(test)]
+fn float(source: &str, format: BinaryFormat) -> Result<u128> {
+    use driver::{parse_expression, Config};
+
+    let expr = parse_expression(&Config::with_gcc(), source).unwrap();
+    match expr.node {
+        ::ast::Expression::Constant(c) => match c.node {
+            Constant::Float(ref f) => FloatValue::decode(f, format, c.span).map(|v| v.bits),
+            _ => panic!("not a floating constant: {}", source),
+        },
+        _ => panic!("not a constant: {}", source),
+    }
+}
+
+#[test]
+fn test_integer() {
+    use eval::IntegerType::*;
+
+    let i = |value, ty| {
+        Ok(IntegerValue {
+            value,
+            ty: CType::Integer(ty),
+        })
+    };
+    assert_eq!(integer("0"), i(0, Int));
+    assert_eq!(integer("2147483647"), i(2147483647, Int));
+    assert_eq!(integer("2147483648"), i(2147483648, Long));
+    assert_eq!(integer("0x80000000"), i(0x80000000, UnsignedInt));
+    assert_eq!(integer("0200"), i(128, Int));
+    assert_eq!(integer("1u"), i(1, UnsignedInt));
+    assert_eq!(integer("0x1'0000'0000u"), i(1 << 32, UnsignedLong));
+    assert_eq!(
+        integer("9223372036854775808"),
+        Err(LiteralError::new(
+            LiteralErrorKind::TooLarge,
+            Span::span(0, 19)
+        ))
+    );
+    assert_eq!(
+        integer("0xffffffffffffffff"),
+        i(0xffffffffffffffff, UnsignedLong)
+    );
+    assert_eq!(integer("1ll"), i(1, LongLong));
+    assert_eq!(integer("0b101"), i(5, Int));
+
+    let b = |value, signed, width| {
+        Ok(IntegerValue {
+            value,
+            ty: CType::BitInt(BitIntType { signed, width }),
+        })
+    };
+    assert_eq!(integer("0wb"), b(0, true, 2));
+    assert_eq!(integer("0uwb"), b(0, false, 1));
+    assert_eq!(integer("255wb"), b(255, true, 9));
+    assert_eq!(integer("0xffuwb"), b(255, false, 8));
+
+    let n = Integer {
+        base: IntegerBase::Decimal,
+        number: "340282366920938463463374607431768211456".into(),
+        suffix: IntegerSuffix {
+            size: IntegerSize::BitInt,
+            unsigned: true,
+            imaginary: false,
+        },
+    };
+    assert_eq!(integer_value(&n), None);
+}
+
+#[test]
+fn test_float() {
+    use self::BinaryFormat::*;
+
+    let f64_bits = |v: f64| Ok(v.to_bits() as u128);
+    let f32_bits = |v: f32| Ok(v.to_bits() as u128);
+    assert_eq!(float("1.5", Binary64), f64_bits(1.5));
+    assert_eq!(float("0.1", Binary64), f64_bits(0.1));
+    assert_eq!(float("0.1", Binary32), f32_bits(0.1));
+    assert_eq!(float("1e23", Binary64), f64_bits(1e23));
+    assert_eq!(
+        float("2.2250738585072014e-308", Binary64),
+        f64_bits(::std::f64::MIN_POSITIVE)
+    );
+    assert_eq!(float("4.9e-324", Binary64), f64_bits(4.9e-324));
+    assert_eq!(float("2e-324", Binary64), Ok(0));
+    assert_eq!(
+        float("1.7976931348623157e308", Binary64),
+        f64_bits(::std::f64::MAX)
+    );
+    assert_eq!(float("3.4028235e38", Binary32), f32_bits(::std::f32::MAX));
+    assert_eq!(float("0x1.8p1", Binary64), f64_bits(3.0));
+    assert_eq!(float("0x.1p4", Binary32), f32_bits(1.0));
+    assert_eq!(float("0x1p-1074", Binary64), f64_bits(4.9e-324));
+    assert_eq!(float("0x1.fffffffffffff8p0", Binary64), f64_bits(2.0));
+    assert_eq!(
+        float("0x1.fffffffffffff7p0", Binary64),
+        f64_bits(1.9999999999999998)
+    );
+    assert_eq!(float("0x1.00000000000008p0", Binary64), f64_bits(1.0));
+    assert_eq!(
+        float("0x1.00000000000018p0", Binary64),
+        f64_bits(1.0000000000000004)
+    );
+    assert_eq!(float("0.", Binary64), Ok(0));
+
+    assert_eq!(float("1.0", Binary16), Ok(0x3c00));
+    assert_eq!(float("65504.0", Binary16), Ok(0x7bff));
+    assert_eq!(float("1.0", Extended80), Ok(0x3fff_8000_0000_0000_0000));
+    assert_eq!(float("0x1p-16445", Extended80), Ok(1));
+    assert_eq!(float("1.0", Binary128), Ok(0x3fff << 112));
+    assert_eq!(
+        float("0.1", Binary128),
+        Ok(0x3ffb_9999_9999_9999_9999_9999_9999_999a)
+    );
+
+    let too_large = |end| {
+        Err(LiteralError::new(
+            LiteralErrorKind::TooLarge,
+            Span::span(0, end),
+        ))
+    };
+    assert_eq!(float("1e309", Binary64), too_large(5));
+    assert_eq!(float("65520.0", Binary16), too_large(7));
+    assert_eq!(float("0x1p128", Binary32), too_large(7));
+}
@@ -1,4 +1,22 @@
 //! Convert byte offsets into line numbers
+//!
+//! Offsets in spans refer to the preprocessed source. Line markers left by the preprocessor map
+//! them back to the original files.
+//!
+//! ```
+//! # use lang_c::loc::SourceMap;
+//! # use lang_c::span::Span;
+//! let source = "# 1 \"main.c\"\nint x;\n# 7 \"util.h\" 1\nint y;\n";
+//! let map = SourceMap::new(source);
+//! let y = source.find('y').unwrap();
+//! let range = map.range(Span::span(y, y + 1));
+//! assert_eq!((range.start.file, range.start.line, range.start.column), ("util.h", 7, 5));
+//! assert_eq!(range.end.column, 6);
+//! assert_eq!(map.includes(y)[0].file, "main.c");
+//! ```
+
+use span::Span;
+
 const F_NEW: u32 = 1;
 const F_RET: u32 = 2;
 
@@ -41,6 +59,185 @@ pub fn get_location_for_offset<'a>(src: &'a str, pos: usize) -> (Location<'a>, V
     (loc, inc)
 }
 
+/// Position in an original source file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceLocation<'a> {
+    pub file: &'a str,
+    /// Line number, starting from one
+    pub line: usize,
+    /// Byte offset in the line, starting from one
+    pub column: usize,
+}
+
+/// Range of positions in an original source file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceRange<'a> {
+    pub start: SourceLocation<'a>,
+    /// Position just past the end of the range
+    pub end: SourceLocation<'a>,
+}
+
+/// Mapping from offsets in a preprocessed source to positions in the original files
+///
+/// Built once by scanning the source for line markers, each lookup is a binary search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMap {
+    /// File names from line markers, as written in them
+    files: Vec<String>,
+    /// Offsets of the starts of all lines
+    lines: Vec<usize>,
+    /// Runs of lines between line markers, ordered by their first line
+    segments: Vec<Segment>,
+    /// Locations of `#include` directives
+    includes: Vec<Include>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    /// Index of the first line in `lines`
+    first: usize,
+    file: usize,
+    /// Line number of the first line
+    line: usize,
+    /// Innermost `#include` of the file, index in `includes`
+    include: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Include {
+    file: usize,
+    line: usize,
+    parent: Option<usize>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> SourceMap {
+        let mut map = SourceMap {
+            files: vec![String::new()],
+            lines: Vec::new(),
+            segments: vec![Segment {
+                first: 0,
+                file: 0,
+                line: 1,
+                include: None,
+            }],
+            includes: Vec::new(),
+        };
+
+        let mut start = 0;
+        while start <= source.len() {
+            let end = source[start..]
+                .find('\n')
+                .map_or(source.len(), |n| start + n);
+            map.lines.push(start);
+            if let Some((l, flags)) = parse_line_directive(&source[start..end]) {
+                map.line_marker(l, flags);
+            }
+            start = end + 1;
+        }
+        map
+    }
+
+    /// Start a new segment after a line marker
+    fn line_marker(&mut self, l: Location, flags: u32) {
+        let current = self.segments.last().unwrap().clone();
+        let mut include = current.include;
+        if flags & F_NEW == F_NEW {
+            // Line of the marker itself is not a source line
+            let line = current.line + (self.lines.len() - 1 - current.first);
+            self.includes.push(Include {
+                file: current.file,
+                line,
+                parent: include,
+            });
+            include = Some(self.includes.len() - 1);
+        }
+        if flags & F_RET == F_RET {
+            include = include.and_then(|i| self.includes[i].parent);
+        }
+        let file = match self.files.iter().position(|f| f == l.file) {
+            Some(file) => file,
+            None => {
+                self.files.push(l.file.to_owned());
+                self.files.len() - 1
+            }
+        };
+        self.segments.push(Segment {
+            first: self.lines.len(),
+            file,
+            line: l.line,
+            include,
+        });
+    }
+
+    /// Index of the line containing an offset and the segment of that line
+    fn find(&self, offset: usize) -> (usize, &Segment) {
+        let line = match self.lines.binary_search(&offset) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        };
+        let segment = match self.segments.binary_search_by_key(&line, |s| s.first) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        };
+        (line, &self.segments[segment])
+    }
+
+    /// Position of an offset in the preprocessed source
+    pub fn location<'a>(&'a self, offset: usize) -> SourceLocation<'a> {
+        let (line, segment) = self.find(offset);
+        SourceLocation {
+            file: &self.files[segment.file],
+            line: segment.line + (line - segment.first),
+            column: offset - self.lines[line] + 1,
+        }
+    }
+
+    /// Positions of the start and the end of a span
+    pub fn range<'a>(&'a self, span: Span) -> SourceRange<'a> {
+        SourceRange {
+            start: self.location(span.start),
+            end: self.location(span.end),
+        }
+    }
+
+    /// Locations of `#include` directives that lead to the file containing an offset
+    ///
+    /// Same as the second element of the result of `get_location_for_offset`: top-level file
+    /// first, the innermost include last.
+    pub fn includes<'a>(&'a self, offset: usize) -> Vec<Location<'a>> {
+        let mut includes = Vec::new();
+        let mut include = self.find(offset).1.include;
+        while let Some(i) = include {
+            let i = &self.includes[i];
+            includes.push(Location {
+                file: &self.files[i.file],
+                line: i.line,
+            });
+            include = i.parent;
+        }
+        includes.reverse();
+        includes
+    }
+}
+
+#[cfg(test)]
+const INCLUDES: &str = r#"
+# 10 "foo"
+...
+# 1 "bar" 1 3 4
+# 5 "bar" 3
+# 11 "baz" 1
+...
+...
+# 6 "bar" 2
+...
+# 15 "foo" 2
+...
+...
+# 2 "ook" 1
+"#;
+
 #[test]
 fn test_get_location_for_offset() {
     fn t(src: &str, pos: usize, file: &str, line: usize, includes: &[(&str, usize)]) {
@@ -65,21 +262,7 @@ fn test_get_location_for_offset() {
     t("# 10 \"init\"\na\nb\n", 14, "init", 11, &[]);
     t("# 10 \"init\"\na\nb\n", 15, "init", 11, &[]);
 
-    const T: &'static str = r#"
-# 10 "foo"
-...
-# 1 "bar" 1 3 4
-# 5 "bar" 3
-# 11 "baz" 1
-...
-...
-# 6 "bar" 2
-...
-# 15 "foo" 2
-...
-...
-# 2 "ook" 1
-"#;
+    const T: &str = INCLUDES;
     t(T, 12, "foo", 10, &[]);
     t(T, 32, "bar", 1, &[("foo", 11)]);
     t(T, 61, "baz", 12, &[("foo", 11), ("bar", 5)]);
@@ -89,6 +272,46 @@ fn test_get_location_for_offset() {
     t(T, 114, "ook", 2, &[("foo", 17)]);
 }
 
+#[test]
+fn test_source_map() {
+    const T: &str = "# 10 \"foo\"\nab\n# 1 \"bar\" 1\nc\n\nd\n# 12 \"foo\" 2\ne";
+    let map = SourceMap::new(T);
+    let l = |file, line, column| SourceLocation { file, line, column };
+    assert_eq!(map.location(0), l("", 1, 1));
+    assert_eq!(map.location(11), l("foo", 10, 1));
+    assert_eq!(map.location(12), l("foo", 10, 2));
+    assert_eq!(map.location(26), l("bar", 1, 1));
+    assert_eq!(map.location(29), l("bar", 3, 1));
+    assert_eq!(map.location(T.len() - 1), l("foo", 12, 1));
+    assert_eq!(
+        map.range(Span::span(11, 13)),
+        SourceRange {
+            start: l("foo", 10, 1),
+            end: l("foo", 10, 3),
+        }
+    );
+
+    // `cl.exe /E` output
+    let map = SourceMap::new("#line 1 \"a.c\"\nint x;\n#line 5 \"a.c\"\n  y");
+    assert_eq!(map.location(14), l("a.c", 1, 1));
+    assert_eq!(map.location(37), l("a.c", 5, 3));
+
+    // agrees with the linear search
+    let t = INCLUDES;
+    let map = SourceMap::new(t);
+    for offset in 0..t.len() {
+        let (loc, inc) = get_location_for_offset(t, offset);
+        let location = map.location(offset);
+        assert_eq!(
+            (location.file, location.line),
+            (loc.file, loc.line),
+            "{}",
+            offset
+        );
+        assert_eq!(map.includes(offset), inc, "{}", offset);
+    }
+}
+
 macro_rules! otry {
     ($e:expr) => {
         match $e {
@@ -107,8 +330,10 @@ fn strip_prefix<'a>(s: &'a str, p: &str) -> Option<&'a str> {
 }
 
 // https://gcc.gnu.org/onlinedocs/cpp/Preprocessor-Output.html
+//
+// MSVC writes `#line` directives instead, without flags.
 fn parse_line_directive(s: &str) -> Option<(Location, u32)> {
-    let s = otry!(strip_prefix(s, "# "));
+    let s = otry!(strip_prefix(s, "# ").or_else(|| strip_prefix(s, "#line ")));
     let n = otry!(s.find(" "));
     let line = otry!(usize::from_str_radix(&s[..n], 10).ok());
 
@@ -159,4 +384,15 @@ fn test_line_directive() {
     assert_eq!(parse_line_directive("# 0 \"# #\x0a\x0a\\"), None);
     assert_eq!(parse_line_directive("# 0 \"\\"), None);
     assert_eq!(parse_line_directive("# 0 \"\\…"), None);
+    assert_eq!(
+        parse_line_directive(r#"#line 7 "c:\\a.c""#),
+        Some((
+            Location {
+                file: r#"c:\\a.c"#,
+                line: 7
+            },
+            0,
+        ))
+    );
+    assert_eq!(parse_line_directive("#line 7"), None);
 }
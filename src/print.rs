@@ -69,7 +69,12 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
                 self.field("Character");
                 self.field(c);
             }
-            _ => {},
+            Constant::Bool(b) => {
+                self.field("Bool");
+                self.field(b);
+            }
+            Constant::Nullptr => self.field("Nullptr"),
+            _ => {}
         }
 
         visit_constant(&mut self.block(), n, span);
@@ -101,6 +106,7 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
             IntegerSize::Int => "Int",
             IntegerSize::Long => "Long",
             IntegerSize::LongLong => "LongLong",
+            IntegerSize::BitInt => "BitInt",
         });
         visit_integer_size(&mut self.block(), n, span);
     }
@@ -142,6 +148,7 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
     }
     fn visit_expression(&mut self, n: &'ast Expression, span: &'ast Span) {
         self.name("Expression");
+        print_expression(self, n);
         visit_expression(&mut self.block(), n, span);
     }
     fn visit_member_operator(&mut self, n: &'ast MemberOperator, span: &'ast Span) {
@@ -205,6 +212,8 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
             UnaryOperator::Minus => "Minus",
             UnaryOperator::Complement => "Complement",
             UnaryOperator::Negate => "Negate",
+            UnaryOperator::Real => "Real",
+            UnaryOperator::Imag => "Imag",
         });
         visit_unary_operator(&mut self.block(), n, span);
     }
@@ -266,12 +275,43 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
     }
     fn visit_conditional_expression(&mut self, n: &'ast ConditionalExpression, span: &'ast Span) {
         self.name("ConditionalExpression");
+        if n.then_expression.is_none() {
+            self.field("OmittedThen");
+        }
         visit_conditional_expression(&mut self.block(), n, span);
     }
     fn visit_va_arg_expression(&mut self, n: &'ast VaArgExpression, span: &'ast Span) {
         self.name("VaArgExpression");
         visit_va_arg_expression(&mut self.block(), n, span);
     }
+    fn visit_types_compatible_expression(
+        &mut self,
+        n: &'ast TypesCompatibleExpression,
+        span: &'ast Span,
+    ) {
+        self.name("TypesCompatibleExpression");
+        visit_types_compatible_expression(&mut self.block(), n, span);
+    }
+    fn visit_choose_expression(&mut self, n: &'ast ChooseExpression, span: &'ast Span) {
+        self.name("ChooseExpression");
+        visit_choose_expression(&mut self.block(), n, span);
+    }
+    fn visit_convert_vector_expression(
+        &mut self,
+        n: &'ast ConvertVectorExpression,
+        span: &'ast Span,
+    ) {
+        self.name("ConvertVectorExpression");
+        visit_convert_vector_expression(&mut self.block(), n, span);
+    }
+    fn visit_shuffle_vector_expression(
+        &mut self,
+        n: &'ast ShuffleVectorExpression,
+        span: &'ast Span,
+    ) {
+        self.name("ShuffleVectorExpression");
+        visit_shuffle_vector_expression(&mut self.block(), n, span);
+    }
     fn visit_offset_of_expression(&mut self, n: &'ast OffsetOfExpression, span: &'ast Span) {
         self.name("OffsetOfExpression");
         visit_offset_of_expression(&mut self.block(), n, span);
@@ -306,6 +346,7 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
             StorageClassSpecifier::ThreadLocal => "ThreadLocal",
             StorageClassSpecifier::Auto => "Auto",
             StorageClassSpecifier::Register => "Register",
+            StorageClassSpecifier::Constexpr => "Constexpr",
         });
         visit_storage_class_specifier(&mut self.block(), n, span);
     }
@@ -383,6 +424,7 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
         self.field(match *n {
             FunctionSpecifier::Inline => "Inline",
             FunctionSpecifier::Noreturn => "Noreturn",
+            FunctionSpecifier::ForceInline => "ForceInline",
         });
         visit_function_specifier(&mut self.block(), n, span);
     }
@@ -466,6 +508,10 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
         self.name("LabeledStatement");
         visit_labeled_statement(&mut self.block(), n, span);
     }
+    fn visit_attributed_statement(&mut self, n: &'ast AttributedStatement, span: &'ast Span) {
+        self.name("AttributedStatement");
+        visit_attributed_statement(&mut self.block(), n, span);
+    }
     fn visit_if_statement(&mut self, n: &'ast IfStatement, span: &'ast Span) {
         self.name("IfStatement");
         visit_if_statement(&mut self.block(), n, span);
@@ -502,20 +548,61 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
     }
     fn visit_block_item(&mut self, n: &'ast BlockItem, span: &'ast Span) {
         self.name("BlockItem");
+        print_block_item(self, n);
         visit_block_item(&mut self.block(), n, span);
     }
     fn visit_external_declaration(&mut self, n: &'ast ExternalDeclaration, span: &'ast Span) {
         self.name("ExternalDeclaration");
+        print_external_declaration(self, n);
         visit_external_declaration(&mut self.block(), n, span);
     }
     fn visit_function_definition(&mut self, n: &'ast FunctionDefinition, span: &'ast Span) {
         self.name("FunctionDefinition");
         visit_function_definition(&mut self.block(), n, span);
     }
+    fn visit_pragma(&mut self, n: &'ast Pragma, span: &'ast Span) {
+        self.name("Pragma");
+
+        self.w.write_str(" [").unwrap();
+        let mut comma = false;
+        for t in &n.tokens {
+            self.field_str_ext(if comma { ", " } else { "" }, &t.node);
+            comma = true;
+        }
+        self.w.write_str("]").unwrap();
+
+        visit_pragma(&mut self.block(), n, span);
+    }
     fn visit_extension(&mut self, n: &'ast Extension, span: &'ast Span) {
         self.name("Extension");
+        print_extension(self, n);
         visit_extension(&mut self.block(), n, span);
     }
+    fn visit_calling_convention(&mut self, n: &'ast CallingConvention, _span: &'ast Span) {
+        self.name("CallingConvention");
+        self.field(match *n {
+            CallingConvention::Cdecl => "Cdecl",
+            CallingConvention::Clrcall => "Clrcall",
+            CallingConvention::Stdcall => "Stdcall",
+            CallingConvention::Fastcall => "Fastcall",
+            CallingConvention::Thiscall => "Thiscall",
+            CallingConvention::Vectorcall => "Vectorcall",
+        });
+        self.block();
+    }
+    fn visit_try_statement(&mut self, n: &'ast TryStatement, span: &'ast Span) {
+        self.name("TryStatement");
+        visit_try_statement(&mut self.block(), n, span);
+    }
+    fn visit_try_handler(&mut self, n: &'ast TryHandler, span: &'ast Span) {
+        self.name("TryHandler");
+        print_try_handler(self, n);
+        visit_try_handler(&mut self.block(), n, span);
+    }
+    fn visit_except_handler(&mut self, n: &'ast ExceptHandler, span: &'ast Span) {
+        self.name("ExceptHandler");
+        visit_except_handler(&mut self.block(), n, span);
+    }
     fn visit_attribute(&mut self, n: &'ast Attribute, span: &'ast Span) {
         self.name("Attribute");
         self.field_str(&n.name.node);
@@ -529,6 +616,25 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
         self.name("AvailabilityAttribute");
         visit_availability_attribute(&mut self.block(), n, span);
     }
+    fn visit_standard_attribute(&mut self, n: &'ast StandardAttribute, _span: &'ast Span) {
+        self.name("StandardAttribute");
+        match n.prefix {
+            Some(ref prefix) => self.field_str(&format!("{}::{}", prefix.node, n.name.node)),
+            None => self.field_str(&n.name.node),
+        }
+        if let Some(ref arguments) = n.arguments {
+            self.field("(");
+            for token in arguments {
+                self.field_str(&token.node);
+            }
+            self.field(")");
+        }
+        self.block();
+    }
+    fn visit_gnu_basic_asm_statement(&mut self, n: &'ast GnuBasicAsmStatement, span: &'ast Span) {
+        self.name("GnuBasicAsmStatement");
+        visit_gnu_basic_asm_statement(&mut self.block(), n, span);
+    }
     fn visit_gnu_extended_asm_statement(
         &mut self,
         n: &'ast GnuExtendedAsmStatement,
@@ -537,6 +643,15 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
         self.name("GnuExtendedAsmStatement");
         visit_gnu_extended_asm_statement(&mut self.block(), n, span);
     }
+    fn visit_asm_qualifier(&mut self, n: &'ast AsmQualifier, _span: &'ast Span) {
+        self.name("AsmQualifier");
+        self.field(match *n {
+            AsmQualifier::Volatile => "Volatile",
+            AsmQualifier::Inline => "Inline",
+            AsmQualifier::Goto => "Goto",
+        });
+        self.block();
+    }
     fn visit_gnu_asm_operand(&mut self, n: &'ast GnuAsmOperand, span: &'ast Span) {
         self.name("GnuAsmOperand");
         visit_gnu_asm_operand(&mut self.block(), n, span);
@@ -551,7 +666,7 @@ impl<'ast, 'a> Visit<'ast> for Printer<'a> {
     }
 }
 
-fn print_float_format<'ast>(p: &mut Printer, n: &'ast FloatFormat) {
+fn print_float_format(p: &mut Printer, n: &FloatFormat) {
     match *n {
         FloatFormat::Float => p.w.write_str(" Float").unwrap(),
         FloatFormat::Double => p.w.write_str(" Double").unwrap(),
@@ -559,13 +674,13 @@ fn print_float_format<'ast>(p: &mut Printer, n: &'ast FloatFormat) {
         _ => {}
     }
 }
-fn print_declarator_kind<'ast>(p: &mut Printer, n: &'ast DeclaratorKind) {
+fn print_declarator_kind(p: &mut Printer, n: &DeclaratorKind) {
     match *n {
         DeclaratorKind::Abstract => p.w.write_str(" Abstract").unwrap(),
         _ => {}
     }
 }
-fn print_derived_declarator<'ast>(p: &mut Printer, n: &'ast DerivedDeclarator) {
+fn print_derived_declarator(p: &mut Printer, n: &DerivedDeclarator) {
     match *n {
         DerivedDeclarator::Pointer(_) => p.w.write_str(" Pointer").unwrap(),
         DerivedDeclarator::KRFunction(_) => p.w.write_str(" KRFunction").unwrap(),
@@ -573,7 +688,7 @@ fn print_derived_declarator<'ast>(p: &mut Printer, n: &'ast DerivedDeclarator) {
         _ => {}
     }
 }
-fn print_array_size<'ast>(p: &mut Printer, n: &'ast ArraySize) {
+fn print_array_size(p: &mut Printer, n: &ArraySize) {
     match *n {
         ArraySize::Unknown => p.w.write_str(" Unknown").unwrap(),
         ArraySize::VariableUnknown => p.w.write_str(" VariableUnknown").unwrap(),
@@ -581,36 +696,65 @@ fn print_array_size<'ast>(p: &mut Printer, n: &'ast ArraySize) {
         ArraySize::StaticExpression(_) => p.w.write_str(" StaticExpression").unwrap(),
     }
 }
-fn print_statement<'ast>(p: &mut Printer, n: &'ast Statement) {
+fn print_statement(p: &mut Printer, n: &Statement) {
     match *n {
         Statement::Compound(_) => p.w.write_str(" Compound").unwrap(),
         Statement::Goto(_) => p.w.write_str(" Goto").unwrap(),
+        Statement::GotoComputed(_) => p.w.write_str(" GotoComputed").unwrap(),
         Statement::Continue => p.w.write_str(" Continue").unwrap(),
         Statement::Break => p.w.write_str(" Break").unwrap(),
         Statement::Return(_) => p.w.write_str(" Return").unwrap(),
+        Statement::Leave => p.w.write_str(" Leave").unwrap(),
         _ => {}
     }
 }
-fn print_offset_member<'ast>(p: &mut Printer, n: &'ast OffsetMember) {
+fn print_expression(p: &mut Printer, n: &Expression) {
+    if let Expression::LabelAddress(_) = *n {
+        p.w.write_str(" LabelAddress").unwrap();
+    }
+}
+fn print_extension(p: &mut Printer, n: &Extension) {
+    if let Extension::Declspec(_) = *n {
+        p.w.write_str(" Declspec").unwrap();
+    }
+}
+fn print_try_handler(p: &mut Printer, n: &TryHandler) {
+    if let TryHandler::Finally(_) = *n {
+        p.w.write_str(" Finally").unwrap();
+    }
+}
+fn print_offset_member(p: &mut Printer, n: &OffsetMember) {
     match *n {
         OffsetMember::Member(_) => p.w.write_str(" Member").unwrap(),
         OffsetMember::IndirectMember(_) => p.w.write_str(" IndirectMember").unwrap(),
         _ => {}
     }
 }
-fn print_label<'ast>(p: &mut Printer, n: &'ast Label) {
+fn print_label(p: &mut Printer, n: &Label) {
     match *n {
         Label::Default => p.w.write_str(" Default").unwrap(),
         _ => {}
     }
 }
-fn print_for_initializer<'ast>(p: &mut Printer, n: &'ast ForInitializer) {
+fn print_for_initializer(p: &mut Printer, n: &ForInitializer) {
     match *n {
         ForInitializer::Empty => p.w.write_str(" Empty").unwrap(),
         _ => {}
     }
 }
-fn print_type_specifier<'ast>(p: &mut Printer, n: &'ast TypeSpecifier) {
+fn print_block_item(p: &mut Printer, n: &BlockItem) {
+    match *n {
+        BlockItem::LabelDeclaration(_) => p.w.write_str(" LabelDeclaration").unwrap(),
+        BlockItem::Error => p.w.write_str(" Error").unwrap(),
+        _ => {}
+    }
+}
+fn print_external_declaration(p: &mut Printer, n: &ExternalDeclaration) {
+    if let ExternalDeclaration::Error = *n {
+        p.w.write_str(" Error").unwrap();
+    }
+}
+fn print_type_specifier(p: &mut Printer, n: &TypeSpecifier) {
     match *n {
         TypeSpecifier::Void => p.w.write_str(" Void").unwrap(),
         TypeSpecifier::Char => p.w.write_str(" Char").unwrap(),
@@ -623,7 +767,10 @@ fn print_type_specifier<'ast>(p: &mut Printer, n: &'ast TypeSpecifier) {
         TypeSpecifier::Unsigned => p.w.write_str(" Unsigned").unwrap(),
         TypeSpecifier::Complex => p.w.write_str(" Complex").unwrap(),
         TypeSpecifier::Atomic(_) => p.w.write_str(" Atomic").unwrap(),
+        TypeSpecifier::BitInt(_) => p.w.write_str(" BitInt").unwrap(),
+        TypeSpecifier::SizedInt(w) => write!(p.w, " SizedInt {}", w).unwrap(),
         TypeSpecifier::TypedefName(_) => p.w.write_str(" TypedefName").unwrap(),
+        TypeSpecifier::TypeOfUnqual(_) => p.w.write_str(" TypeOfUnqual").unwrap(),
         _ => {}
     }
 }
@@ -0,0 +1,852 @@
+//! Name resolution
+//!
+//! Finds the declaration each identifier refers to, following the scope (C11 6.2.1) and name
+//! space (C11 6.2.3) rules.
+//!
+//! ```
+//! # use lang_c::driver::{parse_preprocessed, Config};
+//! # use lang_c::resolve::{resolve, SymbolKind};
+//! let source = "int x; int f(int x) { return x; }";
+//! let parse = parse_preprocessed(&Config::default(), source.into()).unwrap();
+//! let resolution = resolve(&parse.unit);
+//!
+//! // `x` in the return statement refers to the parameter
+//! let use_span = resolution.references[0].span;
+//! assert_eq!(&source[use_span.start..use_span.end], "x");
+//! let symbol = resolution.symbol_at(use_span).unwrap();
+//! assert_eq!(symbol.kind, SymbolKind::Parameter);
+//! assert_eq!(resolution.declaration(use_span).unwrap().start, 17);
+//! ```
+//!
+//! Members are collected for every structure and union definition, but member names in `.` and
+//! `->` expressions, designators and `offsetof` are not resolved because that requires the type of
+//! the expression. Identifiers in attributes are ignored. Names that are not declared in the
+//! translation unit, such as builtins and `__func__`, are listed as unresolved.
+
+use std::collections::HashMap;
+
+use ast::*;
+use span::{Node, Span};
+use types::function_declarator;
+use visit::{self, Visit};
+
+/// Index of a symbol in `Resolution::symbols`
+pub type SymbolId = usize;
+
+/// Index of a scope in `Resolution::scopes`
+pub type ScopeId = usize;
+
+/// Kinds of identifiers that can be used in the same scope without conflict
+///
+/// (C11 6.2.3)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Namespace {
+    /// Objects, functions, typedef names and enumeration constants
+    Ordinary,
+    /// Structure, union and enumeration tags
+    Tag,
+    Label,
+    /// Members of a structure or union, each has its own name space
+    Member,
+}
+
+/// What an identifier denotes
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SymbolKind {
+    Object,
+    Function,
+    Parameter,
+    Typedef,
+    EnumConstant,
+    Struct,
+    Union,
+    Enum,
+    Label,
+    Member,
+}
+
+impl SymbolKind {
+    pub fn namespace(&self) -> Namespace {
+        match *self {
+            SymbolKind::Object
+            | SymbolKind::Function
+            | SymbolKind::Parameter
+            | SymbolKind::Typedef
+            | SymbolKind::EnumConstant => Namespace::Ordinary,
+            SymbolKind::Struct | SymbolKind::Union | SymbolKind::Enum => Namespace::Tag,
+            SymbolKind::Label => Namespace::Label,
+            SymbolKind::Member => Namespace::Member,
+        }
+    }
+}
+
+/// Declared entity
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Scope the name is declared in
+    pub scope: ScopeId,
+    /// Spans of the identifier in every declaration of this entity, first declaration first
+    ///
+    /// Labels are declared by their definition or a local label declaration.
+    pub declarations: Vec<Span>,
+}
+
+impl Symbol {
+    /// Span of the identifier in the first declaration
+    pub fn span(&self) -> Span {
+        self.declarations[0]
+    }
+}
+
+/// Kind of scope
+///
+/// (C11 6.2.1)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ScopeKind {
+    File,
+    /// Labels of a function definition
+    Function,
+    Block,
+    /// Parameters of a function declarator that is not part of a definition
+    Prototype,
+}
+
+/// Region of the program where declared names are visible
+#[derive(Debug, PartialEq, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// Enclosing scope, `None` for the file scope
+    pub parent: Option<ScopeId>,
+    /// Statement, function definition or declarator that opens the scope
+    pub span: Span,
+    names: HashMap<(Namespace, String), SymbolId>,
+}
+
+/// Members of a structure or union definition
+#[derive(Debug, PartialEq, Clone)]
+pub struct Record {
+    /// Span of the structure or union specifier
+    pub span: Span,
+    /// Tag of the type, `None` for anonymous types
+    pub tag: Option<SymbolId>,
+    /// Members by name, including members of anonymous structure and union members
+    pub members: HashMap<String, SymbolId>,
+}
+
+/// Use of a declared name
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Reference {
+    /// Span of the identifier
+    pub span: Span,
+    pub symbol: SymbolId,
+}
+
+/// Result of name resolution
+#[derive(Debug, PartialEq, Clone)]
+pub struct Resolution {
+    /// All scopes, the file scope is first
+    pub scopes: Vec<Scope>,
+    pub symbols: Vec<Symbol>,
+    /// Structure and union definitions in source order
+    pub records: Vec<Record>,
+    /// Uses of declared names in source order
+    pub references: Vec<Reference>,
+    /// Uses of names without a visible declaration
+    pub unresolved: Vec<(Namespace, Node<String>)>,
+}
+
+impl Resolution {
+    /// Symbol referred to by an identifier use
+    pub fn reference(&self, span: Span) -> Option<SymbolId> {
+        self.references
+            .binary_search_by_key(&span.start, |r| r.span.start)
+            .ok()
+            .map(|i| self.references[i])
+            .filter(|r| r.span.end == span.end)
+            .map(|r| r.symbol)
+    }
+
+    /// Symbol referred to by an identifier use
+    pub fn symbol_at(&self, span: Span) -> Option<&Symbol> {
+        self.reference(span).map(|s| &self.symbols[s])
+    }
+
+    /// Span of the first declaration of the name used at `span`
+    pub fn declaration(&self, span: Span) -> Option<Span> {
+        self.symbol_at(span).map(|s| s.span())
+    }
+
+    /// Spans of all uses of a symbol
+    pub fn uses(&self, symbol: SymbolId) -> Vec<Span> {
+        self.references
+            .iter()
+            .filter(|r| r.symbol == symbol)
+            .map(|r| r.span)
+            .collect()
+    }
+
+    /// Find a name visible in a scope
+    pub fn lookup(&self, scope: ScopeId, namespace: Namespace, name: &str) -> Option<SymbolId> {
+        let key = (namespace, name.to_owned());
+        let mut scope = Some(scope);
+        while let Some(s) = scope {
+            if let Some(&symbol) = self.scopes[s].names.get(&key) {
+                return Some(symbol);
+            }
+            scope = self.scopes[s].parent;
+        }
+        None
+    }
+
+    /// Members of the structure or union defined by the specifier at `span`
+    pub fn record(&self, span: Span) -> Option<&Record> {
+        self.records
+            .binary_search_by_key(&span.start, |r| r.span.start)
+            .ok()
+            .map(|i| &self.records[i])
+    }
+
+    /// Members of a structure or union, if its definition was seen
+    pub fn tag_record(&self, tag: SymbolId) -> Option<&Record> {
+        self.records.iter().find(|r| r.tag == Some(tag))
+    }
+}
+
+/// Resolve names in a translation unit
+pub fn resolve(unit: &TranslationUnit) -> Resolution {
+    let mut resolver = Resolver {
+        resolution: Resolution {
+            scopes: vec![Scope {
+                kind: ScopeKind::File,
+                parent: None,
+                span: Span::none(),
+                names: HashMap::new(),
+            }],
+            symbols: Vec::new(),
+            records: Vec::new(),
+            references: Vec::new(),
+            unresolved: Vec::new(),
+        },
+        scope: 0,
+        records: Vec::new(),
+        labels: Vec::new(),
+        typedef: false,
+        forward_tag: false,
+        parameters: None,
+    };
+    resolver.visit_translation_unit(unit);
+    let mut resolution = resolver.resolution;
+    resolution.references.sort_by_key(|r| r.span.start);
+    resolution
+}
+
+struct Resolver {
+    resolution: Resolution,
+    scope: ScopeId,
+    /// Structure and union definitions being visited
+    records: Vec<usize>,
+    /// Label uses in the function being visited, with the scope of the use
+    labels: Vec<(Node<String>, ScopeId)>,
+    /// Visiting declarators of a `typedef` declaration
+    typedef: bool,
+    /// Visiting a `struct s;` declaration
+    forward_tag: bool,
+    /// Parameter list of the function definition being visited
+    parameters: Option<*const DerivedDeclarator>,
+}
+
+impl Resolver {
+    fn enter_scope(&mut self, kind: ScopeKind, span: Span) {
+        self.resolution.scopes.push(Scope {
+            kind,
+            parent: Some(self.scope),
+            span,
+            names: HashMap::new(),
+        });
+        self.scope = self.resolution.scopes.len() - 1;
+    }
+
+    fn leave_scope(&mut self) {
+        self.scope = self.resolution.scopes[self.scope]
+            .parent
+            .expect("more scope pops than pushes");
+    }
+
+    /// Declare a name in a scope, or add a declaration to the entity already declared there
+    fn declare(&mut self, scope: ScopeId, kind: SymbolKind, name: &str, span: Span) -> SymbolId {
+        let key = (kind.namespace(), name.to_owned());
+        if let Some(&symbol) = self.resolution.scopes[scope].names.get(&key) {
+            self.resolution.symbols[symbol].declarations.push(span);
+            return symbol;
+        }
+        self.resolution.symbols.push(Symbol {
+            name: name.to_owned(),
+            kind,
+            scope,
+            declarations: vec![span],
+        });
+        let symbol = self.resolution.symbols.len() - 1;
+        self.resolution.scopes[scope].names.insert(key, symbol);
+        symbol
+    }
+
+    /// Record a use of a name visible in the current scope
+    fn use_name(&mut self, namespace: Namespace, name: &str, span: Span) -> Option<SymbolId> {
+        match self.resolution.lookup(self.scope, namespace, name) {
+            Some(symbol) => {
+                self.resolution.references.push(Reference { span, symbol });
+                Some(symbol)
+            }
+            None => {
+                let name = Node::new(name.to_owned(), span);
+                self.resolution.unresolved.push((namespace, name));
+                None
+            }
+        }
+    }
+
+    /// Nearest enclosing function scope
+    fn function_scope(&self, mut scope: ScopeId) -> Option<ScopeId> {
+        loop {
+            let s = &self.resolution.scopes[scope];
+            match (s.kind, s.parent) {
+                (ScopeKind::Function, _) => return Some(scope),
+                (_, Some(parent)) => scope = parent,
+                (_, None) => return None,
+            }
+        }
+    }
+
+    /// Find a label visible from a scope, local labels first
+    fn find_label(&self, mut scope: ScopeId, name: &str) -> Option<SymbolId> {
+        let key = (Namespace::Label, name.to_owned());
+        loop {
+            let s = &self.resolution.scopes[scope];
+            if let Some(&symbol) = s.names.get(&key) {
+                return Some(symbol);
+            }
+            match (s.kind, s.parent) {
+                (ScopeKind::Function, _) | (_, None) => return None,
+                (_, Some(parent)) => scope = parent,
+            }
+        }
+    }
+
+    fn tag(&mut self, kind: SymbolKind, identifier: &Node<Identifier>, definition: bool) -> usize {
+        let name = &identifier.node.name;
+        let visible = self.resolution.lookup(self.scope, Namespace::Tag, name);
+        match visible {
+            Some(symbol) if !definition && !self.forward_tag => {
+                self.resolution.references.push(Reference {
+                    span: identifier.span,
+                    symbol,
+                });
+                symbol
+            }
+            // Definitions and the first mention of a tag declare it (C11 6.7.2.3)
+            _ => self.declare(self.scope, kind, name, identifier.span),
+        }
+    }
+
+    fn resolve_labels(&mut self) {
+        for (name, scope) in ::std::mem::replace(&mut self.labels, Vec::new()) {
+            match self.find_label(scope, &name.node) {
+                Some(symbol) => self.resolution.references.push(Reference {
+                    span: name.span,
+                    symbol,
+                }),
+                None => self.resolution.unresolved.push((Namespace::Label, name)),
+            }
+        }
+    }
+
+    fn use_label(&mut self, identifier: &Node<Identifier>) {
+        let name = Node::new(identifier.node.name.clone(), identifier.span);
+        self.labels.push((name, self.scope));
+    }
+}
+
+impl<'ast> Visit<'ast> for Resolver {
+    fn visit_identifier(&mut self, _identifier: &'ast Identifier, _span: &'ast Span) {}
+
+    fn visit_extension(&mut self, _extension: &'ast Extension, _span: &'ast Span) {}
+
+    fn visit_expression(&mut self, expression: &'ast Expression, span: &'ast Span) {
+        match *expression {
+            Expression::Identifier(ref i) => {
+                self.use_name(Namespace::Ordinary, &i.node.name, i.span);
+            }
+            Expression::LabelAddress(ref l) => self.use_label(l),
+            _ => visit::visit_expression(self, expression, span),
+        }
+    }
+
+    fn visit_member_expression(&mut self, member: &'ast MemberExpression, _span: &'ast Span) {
+        let e = &member.expression;
+        self.visit_expression(&e.node, &e.span);
+    }
+
+    fn visit_type_specifier(&mut self, type_specifier: &'ast TypeSpecifier, span: &'ast Span) {
+        match *type_specifier {
+            TypeSpecifier::TypedefName(ref t) => {
+                self.use_name(Namespace::Ordinary, &t.node.name, t.span);
+            }
+            _ => visit::visit_type_specifier(self, type_specifier, span),
+        }
+    }
+
+    fn visit_struct_type(&mut self, struct_type: &'ast StructType, span: &'ast Span) {
+        let kind = match struct_type.kind.node {
+            StructKind::Struct => SymbolKind::Struct,
+            StructKind::Union => SymbolKind::Union,
+        };
+        let definition = struct_type.declarations.is_some();
+        let tag = struct_type
+            .identifier
+            .as_ref()
+            .map(|i| self.tag(kind, i, definition));
+        self.forward_tag = false;
+
+        if let Some(ref declarations) = struct_type.declarations {
+            self.resolution.records.push(Record {
+                span: *span,
+                tag,
+                members: HashMap::new(),
+            });
+            self.records.push(self.resolution.records.len() - 1);
+            for declaration in declarations {
+                self.visit_struct_declaration(&declaration.node, &declaration.span);
+            }
+            self.records.pop();
+        }
+    }
+
+    fn visit_struct_field(&mut self, struct_field: &'ast StructField, span: &'ast Span) {
+        visit::visit_struct_field(self, struct_field, span);
+
+        // Members of an anonymous structure or union are members of the containing one
+        if struct_field.declarators.is_empty() {
+            for s in &struct_field.specifiers {
+                if let SpecifierQualifier::TypeSpecifier(ref t) = s.node {
+                    if let TypeSpecifier::Struct(ref s) = t.node {
+                        let members = match self.resolution.record(s.span) {
+                            Some(r) => r.members.clone(),
+                            None => continue,
+                        };
+                        let record = *self.records.last().unwrap();
+                        self.resolution.records[record].members.extend(members);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_struct_declarator(
+        &mut self,
+        struct_declarator: &'ast StructDeclarator,
+        span: &'ast Span,
+    ) {
+        visit::visit_struct_declarator(self, struct_declarator, span);
+        let name = match struct_declarator.declarator {
+            Some(ref d) => declarator_identifier(&d.node),
+            None => None,
+        };
+        if let (Some(name), Some(&record)) = (name, self.records.last()) {
+            let members = &self.resolution.records[record].members;
+            let symbol = match members.get(&name.node.name) {
+                Some(&symbol) => {
+                    self.resolution.symbols[symbol].declarations.push(name.span);
+                    symbol
+                }
+                None => {
+                    self.resolution.symbols.push(Symbol {
+                        name: name.node.name.clone(),
+                        kind: SymbolKind::Member,
+                        scope: self.scope,
+                        declarations: vec![name.span],
+                    });
+                    self.resolution.symbols.len() - 1
+                }
+            };
+            self.resolution.records[record]
+                .members
+                .insert(name.node.name.clone(), symbol);
+        }
+    }
+
+    fn visit_enum_type(&mut self, enum_type: &'ast EnumType, _span: &'ast Span) {
+        let definition = !enum_type.enumerators.is_empty();
+        if let Some(ref i) = enum_type.identifier {
+            self.tag(SymbolKind::Enum, i, definition);
+        }
+        self.forward_tag = false;
+        for enumerator in &enum_type.enumerators {
+            self.visit_enumerator(&enumerator.node, &enumerator.span);
+        }
+    }
+
+    fn visit_enumerator(&mut self, enumerator: &'ast Enumerator, span: &'ast Span) {
+        visit::visit_enumerator(self, enumerator, span);
+        let i = &enumerator.identifier;
+        self.declare(self.scope, SymbolKind::EnumConstant, &i.node.name, i.span);
+    }
+
+    fn visit_declaration(&mut self, declaration: &'ast Declaration, _span: &'ast Span) {
+        let mut types = 0;
+        self.typedef = false;
+        for s in &declaration.specifiers {
+            match s.node {
+                DeclarationSpecifier::StorageClass(ref s) => {
+                    self.typedef |= s.node == StorageClassSpecifier::Typedef;
+                }
+                DeclarationSpecifier::TypeSpecifier(_) => types += 1,
+                _ => {}
+            }
+        }
+        let typedef = self.typedef;
+        for specifier in &declaration.specifiers {
+            // `struct s;` declares a new tag even if one is visible from an outer scope
+            self.forward_tag = declaration.declarators.is_empty() && types == 1;
+            self.visit_declaration_specifier(&specifier.node, &specifier.span);
+        }
+        self.forward_tag = false;
+        for declarator in &declaration.declarators {
+            self.typedef = typedef;
+            self.visit_init_declarator(&declarator.node, &declarator.span);
+        }
+    }
+
+    fn visit_init_declarator(&mut self, init_declarator: &'ast InitDeclarator, _span: &'ast Span) {
+        let typedef = self.typedef;
+        let d = &init_declarator.declarator;
+        self.visit_declarator(&d.node, &d.span);
+        if let Some(name) = declarator_identifier(&d.node) {
+            let kind = if typedef {
+                SymbolKind::Typedef
+            } else if function_declarator(&d.node).is_some() {
+                SymbolKind::Function
+            } else {
+                SymbolKind::Object
+            };
+            self.declare(self.scope, kind, &name.node.name, name.span);
+        }
+        if let Some(ref initializer) = init_declarator.initializer {
+            self.visit_initializer(&initializer.node, &initializer.span);
+        }
+    }
+
+    fn visit_derived_declarator(
+        &mut self,
+        derived_declarator: &'ast DerivedDeclarator,
+        span: &'ast Span,
+    ) {
+        let definition = self.parameters == Some(derived_declarator as *const _);
+        match *derived_declarator {
+            DerivedDeclarator::Function(ref f) if !definition => {
+                self.enter_scope(ScopeKind::Prototype, *span);
+                self.visit_function_declarator(&f.node, &f.span);
+                self.leave_scope();
+            }
+            DerivedDeclarator::KRFunction(ref names) if definition => {
+                for n in names {
+                    self.declare(self.scope, SymbolKind::Parameter, &n.node.name, n.span);
+                }
+            }
+            _ => visit::visit_derived_declarator(self, derived_declarator, span),
+        }
+    }
+
+    fn visit_parameter_declaration(
+        &mut self,
+        parameter_declaration: &'ast ParameterDeclaration,
+        span: &'ast Span,
+    ) {
+        self.typedef = false;
+        visit::visit_parameter_declaration(self, parameter_declaration, span);
+        if let Some(ref d) = parameter_declaration.declarator {
+            if let Some(name) = declarator_identifier(&d.node) {
+                self.declare(
+                    self.scope,
+                    SymbolKind::Parameter,
+                    &name.node.name,
+                    name.span,
+                );
+            }
+        }
+    }
+
+    fn visit_function_definition(
+        &mut self,
+        function_definition: &'ast FunctionDefinition,
+        span: &'ast Span,
+    ) {
+        for specifier in &function_definition.specifiers {
+            self.visit_declaration_specifier(&specifier.node, &specifier.span);
+        }
+
+        let outer = self.scope;
+        let outer_labels = ::std::mem::replace(&mut self.labels, Vec::new());
+        self.enter_scope(ScopeKind::Function, *span);
+        self.enter_scope(ScopeKind::Block, function_definition.statement.span);
+
+        let d = &function_definition.declarator;
+        let outer_parameters = self.parameters;
+        self.parameters = function_declarator(&d.node).map(|p| &p.node as *const _);
+        self.visit_declarator(&d.node, &d.span);
+        self.parameters = outer_parameters;
+        if let Some(name) = declarator_identifier(&d.node) {
+            self.declare(outer, SymbolKind::Function, &name.node.name, name.span);
+        }
+
+        // K&R parameter declarations redeclare parameters in the same scope
+        for declaration in &function_definition.declarations {
+            for declarator in &declaration.node.declarators {
+                let d = &declarator.node.declarator;
+                self.visit_declarator(&d.node, &d.span);
+                if let Some(name) = declarator_identifier(&d.node) {
+                    self.declare(
+                        self.scope,
+                        SymbolKind::Parameter,
+                        &name.node.name,
+                        name.span,
+                    );
+                }
+            }
+            for specifier in &declaration.node.specifiers {
+                self.visit_declaration_specifier(&specifier.node, &specifier.span);
+            }
+        }
+
+        let body = &function_definition.statement;
+        match body.node {
+            Statement::Compound(ref items) => {
+                for item in items {
+                    self.visit_block_item(&item.node, &item.span);
+                }
+            }
+            _ => self.visit_statement(&body.node, &body.span),
+        }
+
+        self.resolve_labels();
+        self.labels = outer_labels;
+        self.leave_scope();
+        self.leave_scope();
+    }
+
+    fn visit_block_item(&mut self, block_item: &'ast BlockItem, span: &'ast Span) {
+        match *block_item {
+            BlockItem::LabelDeclaration(ref labels) => {
+                for l in labels {
+                    self.declare(self.scope, SymbolKind::Label, &l.node.name, l.span);
+                }
+            }
+            _ => visit::visit_block_item(self, block_item, span),
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &'ast Statement, span: &'ast Span) {
+        match *statement {
+            Statement::Compound(_) => {
+                self.enter_scope(ScopeKind::Block, *span);
+                visit::visit_statement(self, statement, span);
+                self.leave_scope();
+            }
+            Statement::For(_) => {
+                self.enter_scope(ScopeKind::Block, *span);
+                visit::visit_statement(self, statement, span);
+                self.leave_scope();
+            }
+            Statement::Goto(ref g) => self.use_label(g),
+            _ => visit::visit_statement(self, statement, span),
+        }
+    }
+
+    fn visit_label(&mut self, label: &'ast Label, span: &'ast Span) {
+        match *label {
+            Label::Identifier(ref i) => {
+                let symbol = self.find_label(self.scope, &i.node.name);
+                match (symbol, self.function_scope(self.scope)) {
+                    (Some(symbol), _) => {
+                        self.resolution.symbols[symbol].declarations.push(i.span);
+                    }
+                    (None, Some(scope)) => {
+                        self.declare(scope, SymbolKind::Label, &i.node.name, i.span);
+                    }
+                    (None, None) => {}
+                }
+            }
+            _ => visit::visit_label(self, label, span),
+        }
+    }
+
+    fn visit_gnu_extended_asm_statement(
+        &mut self,
+        asm: &'ast GnuExtendedAsmStatement,
+        span: &'ast Span,
+    ) {
+        visit::visit_gnu_extended_asm_statement(self, asm, span);
+        for label in &asm.labels {
+            self.use_label(label);
+        }
+    }
+}
+
+fn declarator_identifier(n: &Declarator) -> Option<&Node<Identifier>> {
+    match n.kind.node {
+        DeclaratorKind::Abstract => None,
+        DeclaratorKind::Identifier(ref i) => Some(i),
+        DeclaratorKind::Declarator(ref d) => declarator_identifier(&d.node),
+    }
+}
+
+#[cfg(test)]
+fn resolved(source: &str) -> (Resolution, Vec<(String, String, usize)>) {
+    use driver::{parse_preprocessed, Config};
+
+    let parse = parse_preprocessed(&Config::with_gcc(), source.into()).unwrap();
+    let resolution = resolve(&parse.unit);
+    let uses = resolution
+        .references
+        .iter()
+        .map(|r| {
+            let symbol = &resolution.symbols[r.symbol];
+            let name = source[r.span.start..r.span.end].to_owned();
+            (name, format!("{:?}", symbol.kind), symbol.span().start)
+        })
+        .collect();
+    (resolution, uses)
+}
+
+#[cfg(test)]
+fn uses(source: &str) -> Vec<(String, String, usize)> {
+    resolved(source).1
+}
+
+#[cfg(test)]
+fn u(name: &str, kind: &str, offset: usize) -> (String, String, usize) {
+    (name.to_owned(), kind.to_owned(), offset)
+}
+
+#[test]
+fn test_scopes() {
+    let s = "int x; int f(int x) { { int x; x; } return x; } int g(void) { return x; }";
+    assert_eq!(
+        uses(s),
+        vec![
+            u("x", "Object", 28),
+            u("x", "Parameter", 17),
+            u("x", "Object", 4),
+        ]
+    );
+
+    // parameters of a prototype are not visible outside of it
+    let s = "int n; void f(int n, int a[n]); int g(void) { return n; }";
+    assert_eq!(uses(s), vec![u("n", "Parameter", 18), u("n", "Object", 4)]);
+
+    // a name is in scope right after its declarator
+    let s = "int f(void) { int x = sizeof x; for (int i = 0; i < x; i++) ; return i; }";
+    let (resolution, found) = resolved(s);
+    assert_eq!(
+        found,
+        vec![
+            u("x", "Object", 18),
+            u("i", "Object", 41),
+            u("x", "Object", 18),
+            u("i", "Object", 41),
+        ]
+    );
+    assert_eq!(resolution.unresolved.len(), 1);
+    assert_eq!(resolution.unresolved[0].1.node, "i");
+}
+
+#[test]
+fn test_namespaces() {
+    let s = "struct s { int s; } s; typedef struct s t; int f(void) { t s; return s.s; }";
+    assert_eq!(
+        uses(s),
+        vec![
+            u("s", "Struct", 7),
+            u("t", "Typedef", 40),
+            u("s", "Object", 59)
+        ]
+    );
+
+    // a tag declaration in an inner scope hides the outer tag
+    let s = "struct s; void f(void) { struct s; struct s *p; } struct s *q;";
+    assert_eq!(uses(s), vec![u("s", "Struct", 32), u("s", "Struct", 7)]);
+
+    let s = "enum e { A, B = A + 1 }; enum e v = B;";
+    assert_eq!(
+        uses(s),
+        vec![
+            u("A", "EnumConstant", 9),
+            u("e", "Enum", 5),
+            u("B", "EnumConstant", 12),
+        ]
+    );
+}
+
+#[test]
+fn test_labels() {
+    let s = "void f(void) { goto end; { end: ; } void *p = &&end; }";
+    assert_eq!(uses(s), vec![u("end", "Label", 27), u("end", "Label", 27)]);
+
+    let s = "void f(void) { x: { __label__ x; goto x; x: ; } goto x; }";
+    assert_eq!(uses(s), vec![u("x", "Label", 30), u("x", "Label", 15)]);
+
+    let (resolution, found) = resolved("void f(void) { goto out; }");
+    assert_eq!(found, vec![]);
+    assert_eq!(resolution.unresolved[0].0, Namespace::Label);
+}
+
+#[test]
+fn test_functions() {
+    let s = "int f(a, b) int a; char *b; { return a + f(a, b); }";
+    let (resolution, found) = resolved(s);
+    assert_eq!(
+        found,
+        vec![
+            u("a", "Parameter", 6),
+            u("f", "Function", 4),
+            u("a", "Parameter", 6),
+            u("b", "Parameter", 9),
+        ]
+    );
+    let a = resolution.reference(resolution.references[0].span).unwrap();
+    assert_eq!(resolution.symbols[a].declarations.len(), 2);
+    assert_eq!(resolution.uses(a).len(), 2);
+
+    let s = "int (*f(int x))(int y) { return x; } void g(int (*p)(void));";
+    let (resolution, found) = resolved(s);
+    assert_eq!(found, vec![u("x", "Parameter", 12)]);
+    let file = &resolution.scopes[0];
+    assert_eq!(
+        resolution.symbols[file.names[&(Namespace::Ordinary, "f".into())]].kind,
+        SymbolKind::Function
+    );
+    assert_eq!(
+        resolution.symbols[file.names[&(Namespace::Ordinary, "g".into())]].kind,
+        SymbolKind::Function
+    );
+    assert_eq!(resolution.lookup(0, Namespace::Ordinary, "y"), None);
+}
+
+#[test]
+fn test_members() {
+    let s = "struct s { int a; union { int b; struct { int c; }; }; struct t { int d; } e; };";
+    let (resolution, _) = resolved(s);
+    let tag = resolution.lookup(0, Namespace::Tag, "s").unwrap();
+    let record = resolution.tag_record(tag).unwrap();
+    let mut members: Vec<_> = record.members.keys().cloned().collect();
+    members.sort();
+    assert_eq!(members, vec!["a", "b", "c", "e"]);
+    assert_eq!(record.span.start, 0);
+    assert_eq!(resolution.record(record.span), Some(record));
+
+    // nested structure tags are in the enclosing scope (C11 6.2.1 §4)
+    let tag = resolution.lookup(0, Namespace::Tag, "t").unwrap();
+    let members = &resolution.tag_record(tag).unwrap().members;
+    assert_eq!(resolution.symbols[members["d"]].kind, SymbolKind::Member);
+}
@@ -59,7 +59,23 @@ pub const RESERVED_C11: &'static [&'static str] = &[
     "_Decimal128x",
 ];
 
+pub const RESERVED_C23: &[&str] = &[
+    "alignas",
+    "alignof",
+    "bool",
+    "constexpr",
+    "false",
+    "nullptr",
+    "static_assert",
+    "thread_local",
+    "true",
+    "typeof",
+    "typeof_unqual",
+    "_BitInt",
+];
+
 pub const RESERVED_GNU: &'static [&'static str] = &[
+    "_BitInt",
     "__FUNCTION__",
     "__PRETTY_FUNCTION__",
     "__alignof",
@@ -68,7 +84,11 @@ pub const RESERVED_GNU: &'static [&'static str] = &[
     "__asm__",
     "__attribute",
     "__attribute__",
+    "__builtin_choose_expr",
+    "__builtin_convertvector",
     "__builtin_offsetof",
+    "__builtin_shufflevector",
+    "__builtin_types_compatible_p",
     "__builtin_va_arg",
     "__complex",
     "__complex__",
@@ -93,6 +113,29 @@ pub const RESERVED_GNU: &'static [&'static str] = &[
     "__volatile__",
 ];
 
+// Ref: https://learn.microsoft.com/en-us/cpp/cpp/keywords-cpp
+pub const RESERVED_MSVC: &[&str] = &[
+    "__cdecl",
+    "__clrcall",
+    "__declspec",
+    "__except",
+    "__fastcall",
+    "__finally",
+    "__forceinline",
+    "__inline",
+    "__int8",
+    "__int16",
+    "__int32",
+    "__int64",
+    "__leave",
+    "__pragma",
+    "__restrict",
+    "__stdcall",
+    "__thiscall",
+    "__try",
+    "__vectorcall",
+];
+
 // Ref: https://clang.llvm.org/docs/AttributeReference.html
 pub const RESERVED_CLANG: &'static [&'static str] = &[
     // Only enabled with -fms-extensions and only affect *-*-win32 targets
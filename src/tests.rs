@@ -13,6 +13,7 @@ use env::Env;
 use parser;
 use print::Printer;
 use span::Span;
+use unparse::Unparser;
 use visit::Visit;
 
 struct Case {
@@ -70,13 +71,14 @@ impl Case {
         })
     }
 
-    fn run(&self) -> bool {
+    fn env(&self) -> Env {
         let mut env = None;
 
         for pragma in &self.pragma {
             match *pragma {
                 Pragma::Gnu => env = Some(Env::with_gnu()),
                 Pragma::Clang => env = Some(Env::with_clang()),
+                Pragma::Msvc => env = Some(Env::with_msvc()),
                 _ => {}
             }
         }
@@ -85,11 +87,18 @@ impl Case {
 
         for pragma in &self.pragma {
             match *pragma {
+                Pragma::C23 => env.enable_c23(),
                 Pragma::Typedef(ref name) => env.add_typename(&name),
                 _ => {}
             }
         }
 
+        env
+    }
+
+    fn run(&self) -> bool {
+        let mut env = self.env();
+
         pegviz::marker_start(&self.source);
 
         let (actual, error) = match self.kind.parse_and_print(&self.source, &mut env) {
@@ -108,8 +117,13 @@ impl Case {
             })
             .collect::<Vec<_>>();
 
+        let round_trip = match error {
+            Some(_) => None,
+            None => self.round_trip(&actual),
+        };
+
         let output_matches = actual == self.expect;
-        let success = output_matches && pragma_fail.is_empty();
+        let success = output_matches && pragma_fail.is_empty() && round_trip.is_none();
 
         if !success {
             writeln!(stdout(), "\n{}:", self.name).unwrap();
@@ -126,6 +140,10 @@ impl Case {
             }
         }
 
+        if let Some(unparsed) = round_trip {
+            writeln!(stdout(), "Round trip changed the tree:\n{}", unparsed).unwrap();
+        }
+
         if !output_matches {
             let width = self.expect.lines().map(|s| s.len()).max().unwrap_or(25);
             let mut alines = Some(self.expect.lines());
@@ -156,6 +174,20 @@ impl Case {
         success
     }
 
+    /// Print the tree as C source, parse it again and compare the trees
+    ///
+    /// Returns the printed source if the trees differ.
+    fn round_trip(&self, printed: &str) -> Option<String> {
+        let unparsed = match self.kind.parse_and_unparse(&self.source, &mut self.env()) {
+            Ok(Some(s)) => s,
+            _ => return None,
+        };
+        match self.kind.parse_and_print(&unparsed, &mut self.env()) {
+            Ok(ref s) if s == printed => None,
+            _ => Some(unparsed),
+        }
+    }
+
     fn save(&self, actual: &str) -> io::Result<()> {
         let mut buf = String::new();
         let mut file = BufReader::new(try!(File::open(&self.path)));
@@ -245,6 +277,30 @@ impl Kind {
 
         Ok(s)
     }
+
+    fn parse_and_unparse(
+        &self,
+        source: &str,
+        env: &mut Env,
+    ) -> Result<Option<String>, parser::ParseError> {
+        let source = source.trim_right();
+
+        let mut s = "".to_string();
+        {
+            let mut p = Unparser::new(&mut s);
+            match *self {
+                Kind::Constant => return Ok(None),
+                Kind::Declaration => p.declaration(&try!(parser::declaration(source, env)).node),
+                Kind::Statement => p.statement(&try!(parser::statement(source, env)).node),
+                Kind::Expression => p.expression(&try!(parser::expression(source, env)).node),
+                Kind::TranslationUnit => {
+                    p.translation_unit(&try!(parser::translation_unit(source, env)))
+                }
+            }
+        }
+
+        Ok(Some(s))
+    }
 }
 
 #[derive(Debug)]
@@ -253,6 +309,10 @@ enum Pragma {
     Gnu,
     // Enable clang extensions
     Clang,
+    /// Enable MSVC extensions
+    Msvc,
+    /// Enable C23 features
+    C23,
     /// Define typename
     Typedef(String),
     /// Assert argument is a typename
@@ -269,6 +329,8 @@ impl Pragma {
         Some(match line[0].trim() {
             "gnu" => Pragma::Gnu,
             "clang" => Pragma::Clang,
+            "msvc" => Pragma::Msvc,
+            "c23" => Pragma::C23,
             "typedef" => Pragma::Typedef(match line.pop() {
                 Some(v) => v,
                 None => return None,
@@ -0,0 +1,1052 @@
+//! Semantic types of declared names
+//!
+//! In the syntax tree the type of a name is split between declaration specifiers, which are
+//! shared by all declarators in a declaration, and a list of derived declarators. `TypeResolver`
+//! combines them into a `QualType`.
+//!
+//! ```
+//! # use lang_c::driver::{parse_declaration, Config};
+//! # use lang_c::layout::{DataModel, Target};
+//! # use lang_c::types::TypeResolver;
+//! let source = "const char *(*table[4])(int, ...);";
+//! let decl = parse_declaration(&Config::default(), source).unwrap();
+//! let resolver = TypeResolver::new(Target::new(DataModel::LP64));
+//! let types = resolver.declaration_types(&decl).unwrap();
+//! assert_eq!(types[0].0.node.name, "table");
+//! assert_eq!(types[0].1.to_string(), "const char *(*[4])(int, ...)");
+//! ```
+//!
+//! Structure, union and enumeration types refer to their tag. Typedef names keep the name
+//! together with the type they stand for, use `QualType::canonical` to look through them.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use ast::*;
+use eval::{EvalError, EvalErrorKind, Evaluator, IntegerType};
+use layout::Target;
+use span::{Node, Span};
+use unparse::Unparser;
+
+/// Type with qualifiers
+#[derive(Debug, PartialEq, Clone)]
+pub struct QualType {
+    pub ty: CType,
+    pub qualifiers: Qualifiers,
+}
+
+/// Type qualifiers
+///
+/// (C11 6.7.3)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Qualifiers {
+    pub is_const: bool,
+    pub is_volatile: bool,
+    pub is_restrict: bool,
+    pub is_atomic: bool,
+}
+
+/// Unqualified type
+///
+/// (C11 6.2.5)
+#[derive(Debug, PartialEq, Clone)]
+pub enum CType {
+    Void,
+    /// Standard integer types, including `_Bool` and plain `char`
+    Integer(IntegerType),
+    /// `_BitInt(N)` and `unsigned _BitInt(N)`
+    ///
+    /// (C23 6.2.5)
+    BitInt(BitIntType),
+    /// Real floating types
+    Float(FloatType),
+    /// Complex types
+    Complex(FloatType),
+    Pointer(Box<QualType>),
+    /// Block pointer
+    ///
+    /// [Clang extension](https://clang.llvm.org/docs/BlockLanguageSpec.html)
+    BlockPointer(Box<QualType>),
+    Array(ArrayType),
+    Function(FunctionType),
+    /// Structure or union type
+    Struct(StructKind, Tag),
+    Enum(Tag),
+    /// Type named by a typedef
+    Typedef(TypedefType),
+    /// Type of `nullptr`
+    ///
+    /// (C23 6.2.5)
+    Nullptr,
+    /// Type of an expression, `typeof(expression)`
+    ///
+    /// [GNU extension](https://gcc.gnu.org/onlinedocs/gcc/Typeof.html), (C23 6.7.3.6)
+    TypeOf(TypeOfExpression),
+}
+
+/// Bit-precise integer type
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct BitIntType {
+    pub signed: bool,
+    /// Width in bits
+    pub width: u64,
+}
+
+/// Real floating types
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum FloatType {
+    Float,
+    Double,
+    LongDouble,
+    TS18661(TS18661FloatType),
+}
+
+/// Array type
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArrayType {
+    pub element: Box<QualType>,
+    pub length: ArrayLength,
+    /// Qualifiers between brackets of an array parameter, `int a[const]`
+    pub qualifiers: Qualifiers,
+    /// Array parameter has a `static` minimum size
+    pub is_static: bool,
+}
+
+/// Number of elements in an array
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrayLength {
+    /// `[]`
+    Unknown,
+    /// Integer constant expression
+    Fixed(u64),
+    /// Variable length array, `None` for `[*]`
+    Variable(Option<Box<Node<Expression>>>),
+}
+
+/// Function type
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionType {
+    pub return_type: Box<QualType>,
+    /// Parameters with their declared types, before adjustment of arrays and functions
+    pub parameters: Vec<Parameter>,
+    /// Prototype ends with `...`
+    pub variadic: bool,
+    /// Parameter types are declared
+    ///
+    /// False for K&R style declarations like `f()` and `f(a, b)`.
+    pub prototype: bool,
+}
+
+/// Function parameter
+#[derive(Debug, PartialEq, Clone)]
+pub struct Parameter {
+    pub name: Option<Node<Identifier>>,
+    pub ty: QualType,
+}
+
+/// Reference to a structure, union or enumeration tag
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tag {
+    /// `None` for anonymous types
+    pub name: Option<String>,
+    /// Span of the type specifier, distinguishes anonymous types
+    pub span: Span,
+}
+
+/// Typedef name and its type
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypedefType {
+    pub name: String,
+    pub ty: Box<QualType>,
+}
+
+/// Type of an expression
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypeOfExpression {
+    pub expression: Box<Node<Expression>>,
+    /// `typeof_unqual`, qualifiers of the expression type are removed
+    pub unqualified: bool,
+}
+
+impl QualType {
+    /// Unqualified type
+    pub fn new(ty: CType) -> QualType {
+        QualType {
+            ty,
+            qualifiers: Qualifiers::default(),
+        }
+    }
+
+    /// Type with qualifiers added
+    ///
+    /// Qualifiers of an array type apply to its elements (C11 6.7.3 §9).
+    pub fn qualified(mut self, qualifiers: Qualifiers) -> QualType {
+        match self.ty {
+            CType::Array(ref mut a) => {
+                let element = ::std::mem::replace(&mut *a.element, QualType::new(CType::Void));
+                *a.element = element.qualified(qualifiers);
+            }
+            _ => self.qualifiers = self.qualifiers.union(qualifiers),
+        }
+        self
+    }
+
+    /// Type with the same type but no qualifiers
+    pub fn unqualified(&self) -> QualType {
+        QualType::new(self.ty.clone())
+    }
+
+    /// Type with typedef names replaced by their types, qualifiers of typedefs are merged
+    ///
+    /// Only the outermost type is replaced, typedefs in pointed-to types, array elements and
+    /// function parameters are kept.
+    pub fn canonical(&self) -> QualType {
+        match self.ty {
+            CType::Typedef(ref t) => t.ty.canonical().qualified(self.qualifiers),
+            _ => self.clone(),
+        }
+    }
+
+    /// Parameter type after adjustment of arrays and functions to pointers (C11 6.7.6.3)
+    pub fn adjusted(&self) -> QualType {
+        let canonical = self.canonical();
+        match canonical.ty {
+            CType::Array(a) => QualType {
+                ty: CType::Pointer(a.element),
+                qualifiers: a.qualifiers,
+            },
+            CType::Function(_) => QualType::new(CType::Pointer(Box::new(canonical))),
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Qualifiers {
+    /// No qualifiers are set
+    pub fn is_empty(&self) -> bool {
+        *self == Qualifiers::default()
+    }
+
+    /// Qualifiers set in either `self` or `other`
+    pub fn union(self, other: Qualifiers) -> Qualifiers {
+        Qualifiers {
+            is_const: self.is_const || other.is_const,
+            is_volatile: self.is_volatile || other.is_volatile,
+            is_restrict: self.is_restrict || other.is_restrict,
+            is_atomic: self.is_atomic || other.is_atomic,
+        }
+    }
+
+    fn add(&mut self, n: &TypeQualifier) {
+        match *n {
+            TypeQualifier::Const => self.is_const = true,
+            TypeQualifier::Volatile => self.is_volatile = true,
+            TypeQualifier::Restrict => self.is_restrict = true,
+            TypeQualifier::Atomic => self.is_atomic = true,
+            TypeQualifier::Nonnull | TypeQualifier::NullUnspecified | TypeQualifier::Nullable => {}
+        }
+    }
+}
+
+type Result<T> = ::std::result::Result<T, EvalError>;
+
+/// Name of a structure member, if it has one, and its type
+pub type FieldType<'a> = (Option<&'a Node<Identifier>>, QualType);
+
+/// Converts declarations to types
+///
+/// Holds typedef names visible in the current scope and an evaluator for array sizes.
+#[derive(Debug, Clone)]
+pub struct TypeResolver {
+    eval: Evaluator,
+    typedefs: Vec<HashMap<String, QualType>>,
+}
+
+impl TypeResolver {
+    pub fn new(target: Target) -> TypeResolver {
+        TypeResolver {
+            eval: Evaluator::new(target),
+            typedefs: vec![HashMap::new()],
+        }
+    }
+
+    /// Evaluator used for array sizes and `_BitInt` widths
+    pub fn evaluator(&self) -> &Evaluator {
+        &self.eval
+    }
+
+    pub fn evaluator_mut(&mut self) -> &mut Evaluator {
+        &mut self.eval
+    }
+
+    pub fn enter_scope(&mut self) {
+        self.eval.enter_scope();
+        self.typedefs.push(HashMap::new());
+    }
+
+    pub fn leave_scope(&mut self) {
+        self.eval.leave_scope();
+        self.typedefs.pop().expect("more scope pops than pushes");
+    }
+
+    /// Define a typedef name in the innermost scope
+    pub fn add_typedef(&mut self, name: &str, ty: QualType) {
+        self.typedefs
+            .last_mut()
+            .unwrap()
+            .insert(name.to_owned(), ty);
+    }
+
+    /// Find the type a typedef name stands for
+    pub fn typedef(&self, name: &str) -> Option<&QualType> {
+        self.typedefs
+            .iter()
+            .rev()
+            .filter_map(|s| s.get(name))
+            .next()
+    }
+
+    /// Add typedef names, tags and enumeration constants defined by a declaration
+    pub fn add_declaration(&mut self, n: &Node<Declaration>) -> Result<()> {
+        try!(self.eval.add_declaration(n));
+        let typedef = n.node.specifiers.iter().any(|s| match s.node {
+            DeclarationSpecifier::StorageClass(ref s) => s.node == StorageClassSpecifier::Typedef,
+            _ => false,
+        });
+        if typedef {
+            for (name, ty) in try!(self.declaration_types(n)) {
+                self.add_typedef(&name.node.name, ty);
+            }
+        }
+        Ok(())
+    }
+
+    /// Types of all names declared in a declaration
+    pub fn declaration_types<'a>(
+        &self,
+        n: &'a Node<Declaration>,
+    ) -> Result<Vec<(&'a Node<Identifier>, QualType)>> {
+        let base = try!(self.declaration_specifiers_type(&n.node.specifiers, n.span));
+        let mut types = Vec::new();
+        for d in &n.node.declarators {
+            let declarator = &d.node.declarator;
+            if let Some(name) = declarator_identifier(&declarator.node) {
+                types.push((name, try!(self.apply_declarator(base.clone(), declarator))));
+            }
+        }
+        Ok(types)
+    }
+
+    /// Type of a declarator with the given declaration specifiers
+    pub fn declarator_type(
+        &self,
+        specifiers: &[Node<DeclarationSpecifier>],
+        n: &Node<Declarator>,
+    ) -> Result<QualType> {
+        let base = try!(self.declaration_specifiers_type(specifiers, n.span));
+        self.apply_declarator(base, n)
+    }
+
+    /// Type named by a type name
+    pub fn type_name(&self, n: &Node<TypeName>) -> Result<QualType> {
+        let mut types = Vec::new();
+        let mut qualifiers = Qualifiers::default();
+        for s in &n.node.specifiers {
+            match s.node {
+                SpecifierQualifier::TypeSpecifier(ref t) => types.push(t),
+                SpecifierQualifier::TypeQualifier(ref q) => qualifiers.add(&q.node),
+                _ => {}
+            }
+        }
+        let base = try!(self.specifiers_type(&types, qualifiers, n.span));
+        match n.node.declarator {
+            Some(ref d) => self.apply_declarator(base, d),
+            None => Ok(base),
+        }
+    }
+
+    /// Declared type of a function parameter, before adjustment
+    pub fn parameter_type(&self, n: &Node<ParameterDeclaration>) -> Result<QualType> {
+        let base = try!(self.declaration_specifiers_type(&n.node.specifiers, n.span));
+        match n.node.declarator {
+            Some(ref d) => self.apply_declarator(base, d),
+            None => Ok(base),
+        }
+    }
+
+    /// Types of members declared in a structure field declaration
+    ///
+    /// Anonymous structure and union members and unnamed bit-fields have no name.
+    pub fn field_types<'a>(&self, n: &'a Node<StructField>) -> Result<Vec<FieldType<'a>>> {
+        let base = try!(self.type_name(&Node::new(
+            TypeName {
+                specifiers: n.node.specifiers.clone(),
+                declarator: None,
+            },
+            n.span,
+        )));
+        if n.node.declarators.is_empty() {
+            return Ok(vec![(None, base)]);
+        }
+        let mut types = Vec::new();
+        for d in &n.node.declarators {
+            match d.node.declarator {
+                Some(ref d) => types.push((
+                    declarator_identifier(&d.node),
+                    try!(self.apply_declarator(base.clone(), d)),
+                )),
+                None => types.push((None, base.clone())),
+            }
+        }
+        Ok(types)
+    }
+
+    /// Type of a defined function
+    ///
+    /// Parameters of a K&R style definition get their types from the declaration list.
+    pub fn function_definition_type(&self, n: &Node<FunctionDefinition>) -> Result<QualType> {
+        let mut ty = try!(self.declarator_type(&n.node.specifiers, &n.node.declarator));
+        if let CType::Function(ref mut f) = ty.ty {
+            if !f.prototype {
+                for declaration in &n.node.declarations {
+                    for (name, declared) in try!(self.declaration_types(declaration)) {
+                        for p in &mut f.parameters {
+                            if p.name.as_ref().map(|n| &n.node.name) == Some(&name.node.name) {
+                                p.ty = declared.clone();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(ty)
+    }
+
+    fn declaration_specifiers_type(
+        &self,
+        specifiers: &[Node<DeclarationSpecifier>],
+        span: Span,
+    ) -> Result<QualType> {
+        let mut types = Vec::new();
+        let mut qualifiers = Qualifiers::default();
+        for s in specifiers {
+            match s.node {
+                DeclarationSpecifier::TypeSpecifier(ref t) => types.push(t),
+                DeclarationSpecifier::TypeQualifier(ref q) => qualifiers.add(&q.node),
+                _ => {}
+            }
+        }
+        self.specifiers_type(&types, qualifiers, span)
+    }
+
+    fn specifiers_type(
+        &self,
+        specifiers: &[&Node<TypeSpecifier>],
+        qualifiers: Qualifiers,
+        span: Span,
+    ) -> Result<QualType> {
+        let ty = match specifiers.first().map(|s| &s.node) {
+            // Implicit `int` (C89 3.5.2)
+            None => CType::Integer(IntegerType::Int),
+            Some(TypeSpecifier::Struct(s)) if specifiers.len() == 1 => CType::Struct(
+                s.node.kind.node,
+                Tag {
+                    name: s.node.identifier.as_ref().map(|i| i.node.name.clone()),
+                    span: s.span,
+                },
+            ),
+            Some(TypeSpecifier::Enum(e)) if specifiers.len() == 1 => CType::Enum(Tag {
+                name: e.node.identifier.as_ref().map(|i| i.node.name.clone()),
+                span: e.span,
+            }),
+            Some(TypeSpecifier::TypedefName(i)) if specifiers.len() == 1 => {
+                match self.typedef(&i.node.name) {
+                    Some(ty) => CType::Typedef(TypedefType {
+                        name: i.node.name.clone(),
+                        ty: Box::new(ty.clone()),
+                    }),
+                    None => return Err(EvalError::new(EvalErrorKind::IncompleteType, i.span)),
+                }
+            }
+            Some(TypeSpecifier::Atomic(t)) if specifiers.len() == 1 => {
+                let atomic = Qualifiers {
+                    is_atomic: true,
+                    ..Qualifiers::default()
+                };
+                return Ok(try!(self.type_name(t))
+                    .qualified(atomic)
+                    .qualified(qualifiers));
+            }
+            Some(TypeSpecifier::TypeOf(t)) if specifiers.len() == 1 => {
+                return Ok(try!(self.type_of(t, false)).qualified(qualifiers));
+            }
+            Some(TypeSpecifier::TypeOfUnqual(t)) if specifiers.len() == 1 => {
+                return Ok(try!(self.type_of(t, true)).qualified(qualifiers));
+            }
+            _ => match specifiers.iter().find(|s| match s.node {
+                TypeSpecifier::BitInt(_) => true,
+                _ => false,
+            }) {
+                Some(&&Node {
+                    node: TypeSpecifier::BitInt(ref width),
+                    ..
+                }) => {
+                    let unsigned = specifiers.iter().any(|s| s.node == TypeSpecifier::Unsigned);
+                    let others = specifiers.iter().any(|s| match s.node {
+                        TypeSpecifier::BitInt(_)
+                        | TypeSpecifier::Signed
+                        | TypeSpecifier::Unsigned => false,
+                        _ => true,
+                    });
+                    let width = try!(self.eval.evaluate(width));
+                    if others || width.value < 1 {
+                        return Err(EvalError::new(EvalErrorKind::UnsupportedType, span));
+                    }
+                    CType::BitInt(BitIntType {
+                        signed: !unsigned,
+                        width: width.value as u64,
+                    })
+                }
+                _ => try!(arithmetic_type(specifiers, span)),
+            },
+        };
+        Ok(QualType { ty, qualifiers })
+    }
+
+    fn type_of(&self, n: &Node<TypeOf>, unqualified: bool) -> Result<QualType> {
+        match n.node {
+            TypeOf::Type(ref t) => {
+                let ty = try!(self.type_name(t));
+                Ok(if unqualified { ty.unqualified() } else { ty })
+            }
+            TypeOf::Expression(ref e) => Ok(QualType::new(CType::TypeOf(TypeOfExpression {
+                expression: Box::new(e.clone()),
+                unqualified,
+            }))),
+        }
+    }
+
+    /// Apply derived declarators to a base type
+    ///
+    /// The nested declarator, if there is one, is applied last, so `int *(*p)[3]` is a pointer
+    /// to an array of pointers to `int`.
+    fn apply_declarator(&self, base: QualType, n: &Node<Declarator>) -> Result<QualType> {
+        let mut ty = base;
+        for derived in derived_declarators(&n.node) {
+            ty = match derived.node {
+                DerivedDeclarator::Pointer(ref q) => QualType {
+                    ty: CType::Pointer(Box::new(ty)),
+                    qualifiers: pointer_qualifiers(q),
+                },
+                DerivedDeclarator::Block(ref q) => QualType {
+                    ty: CType::BlockPointer(Box::new(ty)),
+                    qualifiers: pointer_qualifiers(q),
+                },
+                DerivedDeclarator::Array(ref a) => {
+                    let mut qualifiers = Qualifiers::default();
+                    for q in &a.node.qualifiers {
+                        qualifiers.add(&q.node);
+                    }
+                    let (length, is_static) = match a.node.size {
+                        ArraySize::Unknown => (ArrayLength::Unknown, false),
+                        ArraySize::VariableUnknown => (ArrayLength::Variable(None), false),
+                        ArraySize::VariableExpression(ref e) => (try!(self.array_length(e)), false),
+                        ArraySize::StaticExpression(ref e) => (try!(self.array_length(e)), true),
+                    };
+                    QualType::new(CType::Array(ArrayType {
+                        element: Box::new(ty),
+                        length,
+                        qualifiers,
+                        is_static,
+                    }))
+                }
+                DerivedDeclarator::Function(ref f) => {
+                    let mut parameters = Vec::new();
+                    for p in &f.node.parameters {
+                        parameters.push(Parameter {
+                            name: p
+                                .node
+                                .declarator
+                                .as_ref()
+                                .and_then(|d| declarator_identifier(&d.node))
+                                .cloned(),
+                            ty: try!(self.parameter_type(p)),
+                        });
+                    }
+                    // `(void)` declares a function without parameters
+                    if parameters.len() == 1
+                        && parameters[0].name.is_none()
+                        && parameters[0].ty == QualType::new(CType::Void)
+                    {
+                        parameters.clear();
+                    }
+                    QualType::new(CType::Function(FunctionType {
+                        return_type: Box::new(ty),
+                        parameters,
+                        variadic: f.node.ellipsis == Ellipsis::Some,
+                        prototype: true,
+                    }))
+                }
+                DerivedDeclarator::KRFunction(ref names) => {
+                    QualType::new(CType::Function(FunctionType {
+                        return_type: Box::new(ty),
+                        parameters: names
+                            .iter()
+                            .map(|n| Parameter {
+                                name: Some(n.clone()),
+                                ty: QualType::new(CType::Integer(IntegerType::Int)),
+                            })
+                            .collect(),
+                        variadic: false,
+                        prototype: false,
+                    }))
+                }
+            };
+        }
+        match n.node.kind.node {
+            DeclaratorKind::Declarator(ref d) => self.apply_declarator(ty, d),
+            _ => Ok(ty),
+        }
+    }
+
+    fn array_length(&self, n: &Node<Expression>) -> Result<ArrayLength> {
+        match self.eval.evaluate(n) {
+            Ok(v) if v.value < 0 => Err(EvalError::new(EvalErrorKind::UnsupportedType, n.span)),
+            Ok(v) => Ok(ArrayLength::Fixed(v.value as u64)),
+            Err(EvalError {
+                kind: EvalErrorKind::NotConstant,
+                ..
+            })
+            | Err(EvalError {
+                kind: EvalErrorKind::UnknownIdentifier(_),
+                ..
+            }) => Ok(ArrayLength::Variable(Some(Box::new(n.clone())))),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Classify a list of arithmetic type specifiers (C11 6.7.2)
+pub fn arithmetic_type(specifiers: &[&Node<TypeSpecifier>], span: Span) -> Result<CType> {
+    let unsupported = || EvalError::new(EvalErrorKind::UnsupportedType, span);
+    let (mut void, mut char, mut short, mut int, mut long, mut bool) = (0, 0, 0, 0, 0, 0);
+    let (mut signed, mut unsigned, mut float, mut double, mut complex) = (0, 0, 0, 0, 0);
+    let mut sized = None;
+    let mut ts18661 = None;
+    for s in specifiers {
+        match s.node {
+            TypeSpecifier::Void => void += 1,
+            TypeSpecifier::Char => char += 1,
+            TypeSpecifier::Short => short += 1,
+            TypeSpecifier::Int => int += 1,
+            TypeSpecifier::Long => long += 1,
+            TypeSpecifier::Float => float += 1,
+            TypeSpecifier::Double => double += 1,
+            TypeSpecifier::Signed => signed += 1,
+            TypeSpecifier::Unsigned => unsigned += 1,
+            TypeSpecifier::Bool => bool += 1,
+            TypeSpecifier::Complex => complex += 1,
+            TypeSpecifier::SizedInt(bits) => sized = Some(bits),
+            TypeSpecifier::TS18661Float(t) => ts18661 = Some(t),
+            _ => return Err(unsupported()),
+        }
+    }
+    let floating = |f: FloatType| {
+        Ok(if complex > 0 {
+            CType::Complex(f)
+        } else {
+            CType::Float(f)
+        })
+    };
+    if let Some(t) = ts18661 {
+        if specifiers.len() != 1 + complex {
+            return Err(unsupported());
+        }
+        return floating(FloatType::TS18661(t));
+    }
+
+    let integer = |ty: IntegerType| {
+        if complex > 0 {
+            return Err(unsupported());
+        }
+        Ok(CType::Integer(if unsigned > 0 {
+            ty.to_unsigned()
+        } else {
+            ty
+        }))
+    };
+    if signed + unsigned > 1 || int > 1 || complex > 1 {
+        return Err(unsupported());
+    }
+    let integral = void + bool + float + double == 0;
+    if !integral && int + signed + unsigned > 0 {
+        return Err(unsupported());
+    }
+    match (void, char, short, long, bool, float, double, sized) {
+        (1, 0, 0, 0, 0, 0, 0, None) if complex == 0 => Ok(CType::Void),
+        (0, 1, 0, 0, 0, 0, 0, None) if int == 0 => {
+            if signed > 0 {
+                integer(IntegerType::SignedChar)
+            } else if unsigned > 0 {
+                integer(IntegerType::UnsignedChar)
+            } else {
+                integer(IntegerType::Char)
+            }
+        }
+        (0, 0, 1, 0, 0, 0, 0, None) => integer(IntegerType::Short),
+        (0, 0, 0, 0, 0, 0, 0, None) if int > 0 || signed + unsigned > 0 => {
+            integer(IntegerType::Int)
+        }
+        (0, 0, 0, 1, 0, 0, 0, None) => integer(IntegerType::Long),
+        (0, 0, 0, 2, 0, 0, 0, None) => integer(IntegerType::LongLong),
+        (0, 0, 0, 0, 1, 0, 0, None) => integer(IntegerType::Bool),
+        (0, 0, 0, 0, 0, 1, 0, None) => floating(FloatType::Float),
+        (0, 0, 0, 0, 0, 0, 1, None) => floating(FloatType::Double),
+        (0, 0, 0, 1, 0, 0, 1, None) => floating(FloatType::LongDouble),
+        // `_Complex` alone means `_Complex double` (GNU extension)
+        (0, 0, 0, 0, 0, 0, 0, None) if complex > 0 => floating(FloatType::Double),
+        (0, 0, 0, 0, 0, 0, 0, Some(bits)) => integer(match bits {
+            8 => IntegerType::SignedChar,
+            16 => IntegerType::Short,
+            32 => IntegerType::Int,
+            64 => IntegerType::LongLong,
+            _ => return Err(unsupported()),
+        }),
+        _ => Err(unsupported()),
+    }
+}
+
+/// Derived declarators in the order they apply to the base type
+///
+/// Pointers apply in the order they are written, followed by array and function declarators in
+/// reverse order: `int *a[2][3]` is an array of two arrays of three pointers.
+pub fn derived_declarators(n: &Declarator) -> Vec<&Node<DerivedDeclarator>> {
+    let is_prefix = |d: &&Node<DerivedDeclarator>| match d.node {
+        DerivedDeclarator::Pointer(_) | DerivedDeclarator::Block(_) => true,
+        _ => false,
+    };
+    let mut derived: Vec<_> = n.derived.iter().filter(&is_prefix).collect();
+    derived.extend(n.derived.iter().rev().filter(|d| !is_prefix(d)));
+    derived
+}
+
+/// Function declarator that applies to the declared name, if the name is a function
+///
+/// This is the declarator with the parameters of a function definition.
+pub fn function_declarator(n: &Declarator) -> Option<&Node<DerivedDeclarator>> {
+    match n.kind.node {
+        DeclaratorKind::Declarator(ref d) => function_declarator(&d.node),
+        _ => match derived_declarators(n).pop() {
+            Some(d) => match d.node {
+                DerivedDeclarator::Function(_) | DerivedDeclarator::KRFunction(_) => Some(d),
+                _ => None,
+            },
+            None => None,
+        },
+    }
+}
+
+fn pointer_qualifiers(n: &[Node<PointerQualifier>]) -> Qualifiers {
+    let mut qualifiers = Qualifiers::default();
+    for q in n {
+        if let PointerQualifier::TypeQualifier(ref q) = q.node {
+            qualifiers.add(&q.node);
+        }
+    }
+    qualifiers
+}
+
+fn declarator_identifier(n: &Declarator) -> Option<&Node<Identifier>> {
+    match n.kind.node {
+        DeclaratorKind::Abstract => None,
+        DeclaratorKind::Identifier(ref i) => Some(i),
+        DeclaratorKind::Declarator(ref d) => declarator_identifier(&d.node),
+    }
+}
+
+impl fmt::Display for QualType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&declarator(self, String::new()))
+    }
+}
+
+impl fmt::Display for CType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&declarator(&QualType::new(self.clone()), String::new()))
+    }
+}
+
+/// Write a type as a declaration of `inner`, which is empty for an abstract declarator
+fn declarator(ty: &QualType, inner: String) -> String {
+    let wrap = |s: String, to: &QualType| match to.ty {
+        CType::Array(_) | CType::Function(_) => format!("({})", s),
+        _ => s,
+    };
+    match ty.ty {
+        CType::Pointer(ref to) | CType::BlockPointer(ref to) => {
+            let mut s = match ty.ty {
+                CType::Pointer(_) => "*".to_owned(),
+                _ => "^".to_owned(),
+            };
+            let qualifiers = qualifier_list(ty.qualifiers);
+            s.push_str(&qualifiers.join(" "));
+            if !qualifiers.is_empty() && !inner.is_empty() {
+                s.push(' ');
+            }
+            s.push_str(&inner);
+            declarator(to, wrap(s, to))
+        }
+        CType::Array(ref a) => {
+            let mut qualifiers = qualifier_list(a.qualifiers);
+            if a.is_static {
+                qualifiers.insert(0, "static");
+            }
+            let mut s = format!("{}[{}", inner, qualifiers.join(" "));
+            match a.length {
+                ArrayLength::Unknown => {}
+                ArrayLength::Fixed(n) if qualifiers.is_empty() => s.push_str(&n.to_string()),
+                ArrayLength::Fixed(n) => s.push_str(&format!(" {}", n)),
+                ArrayLength::Variable(None) => s.push('*'),
+                ArrayLength::Variable(Some(ref e)) => {
+                    if !qualifiers.is_empty() {
+                        s.push(' ');
+                    }
+                    Unparser::new(&mut s).expression(&e.node);
+                }
+            }
+            s.push(']');
+            declarator(&a.element, s)
+        }
+        CType::Function(ref f) => {
+            let mut parameters: Vec<_> = f
+                .parameters
+                .iter()
+                .map(|p| match f.prototype {
+                    true => p.ty.to_string(),
+                    false => p
+                        .name
+                        .as_ref()
+                        .map_or(String::new(), |n| n.node.name.clone()),
+                })
+                .collect();
+            if f.variadic {
+                parameters.push("...".to_owned());
+            }
+            if f.prototype && parameters.is_empty() {
+                parameters.push("void".to_owned());
+            }
+            let s = format!("{}({})", inner, parameters.join(", "));
+            declarator(&f.return_type, s)
+        }
+        _ => {
+            let mut s = qualifier_list(ty.qualifiers).join(" ");
+            if !s.is_empty() {
+                s.push(' ');
+            }
+            s.push_str(&base_name(&ty.ty));
+            if !inner.is_empty() {
+                s.push(' ');
+                s.push_str(&inner);
+            }
+            s
+        }
+    }
+}
+
+fn qualifier_list(q: Qualifiers) -> Vec<&'static str> {
+    let mut list = Vec::new();
+    if q.is_const {
+        list.push("const");
+    }
+    if q.is_volatile {
+        list.push("volatile");
+    }
+    if q.is_restrict {
+        list.push("restrict");
+    }
+    if q.is_atomic {
+        list.push("_Atomic");
+    }
+    list
+}
+
+fn base_name(ty: &CType) -> String {
+    let tag = |keyword: &str, tag: &Tag| match tag.name {
+        Some(ref name) => format!("{} {}", keyword, name),
+        None => format!("{} <anonymous>", keyword),
+    };
+    match *ty {
+        CType::Void => "void".to_owned(),
+        CType::Integer(ty) => match ty {
+            IntegerType::Bool => "_Bool",
+            IntegerType::Char => "char",
+            IntegerType::SignedChar => "signed char",
+            IntegerType::UnsignedChar => "unsigned char",
+            IntegerType::Short => "short",
+            IntegerType::UnsignedShort => "unsigned short",
+            IntegerType::Int => "int",
+            IntegerType::UnsignedInt => "unsigned int",
+            IntegerType::Long => "long",
+            IntegerType::UnsignedLong => "unsigned long",
+            IntegerType::LongLong => "long long",
+            IntegerType::UnsignedLongLong => "unsigned long long",
+        }
+        .to_owned(),
+        CType::BitInt(ref b) if b.signed => format!("_BitInt({})", b.width),
+        CType::BitInt(ref b) => format!("unsigned _BitInt({})", b.width),
+        CType::Float(ref f) => float_name(f),
+        CType::Complex(ref f) => format!("_Complex {}", float_name(f)),
+        CType::Struct(StructKind::Struct, ref t) => tag("struct", t),
+        CType::Struct(StructKind::Union, ref t) => tag("union", t),
+        CType::Enum(ref t) => tag("enum", t),
+        CType::Typedef(ref t) => t.name.clone(),
+        CType::Nullptr => "nullptr_t".to_owned(),
+        CType::TypeOf(ref t) => {
+            let mut s = match t.unqualified {
+                true => "typeof_unqual(".to_owned(),
+                false => "typeof(".to_owned(),
+            };
+            Unparser::new(&mut s).expression(&t.expression.node);
+            s.push(')');
+            s
+        }
+        CType::Pointer(_) | CType::BlockPointer(_) | CType::Array(_) | CType::Function(_) => {
+            unreachable!()
+        }
+    }
+}
+
+fn float_name(f: &FloatType) -> String {
+    match *f {
+        FloatType::Float => "float".to_owned(),
+        FloatType::Double => "double".to_owned(),
+        FloatType::LongDouble => "long double".to_owned(),
+        FloatType::TS18661(t) => {
+            let (prefix, extended) = match t.format {
+                TS18661FloatFormat::BinaryInterchange => ("_Float", ""),
+                TS18661FloatFormat::BinaryExtended => ("_Float", "x"),
+                TS18661FloatFormat::DecimalInterchange => ("_Decimal", ""),
+                TS18661FloatFormat::DecimalExtended => ("_Decimal", "x"),
+            };
+            format!("{}{}{}", prefix, t.width, extended)
+        }
+    }
+}
+
+#[cfg(test)]
+fn declared(resolver: &TypeResolver, source: &str) -> Vec<String> {
+    use driver::{parse_declaration, Config};
+
+    let mut config = Config::with_gcc();
+    config.typedef_names.push("T".into());
+    let decl = parse_declaration(&config, source).unwrap();
+    let types = resolver.declaration_types(&decl).unwrap();
+    types
+        .iter()
+        .map(|&(name, ref ty)| format!("{}: {}", name.node.name, ty))
+        .collect()
+}
+
+#[test]
+fn test_declarators() {
+    use layout::DataModel;
+
+    let r = TypeResolver::new(Target::new(DataModel::LP64));
+    assert_eq!(
+        declared(&r, "unsigned long a, *const b, c[2][3];"),
+        vec![
+            "a: unsigned long",
+            "b: unsigned long *const",
+            "c: unsigned long [2][3]"
+        ]
+    );
+    assert_eq!(
+        declared(&r, "int (*p)[4], *q[4], (*f)(void), g(), h(a, b);"),
+        vec![
+            "p: int (*)[4]",
+            "q: int *[4]",
+            "f: int (*)(void)",
+            "g: int ()",
+            "h: int (a, b)"
+        ]
+    );
+    assert_eq!(
+        declared(&r, "void (*signal(int, void (*)(int)))(int);"),
+        vec!["signal: void (*(int, void (*)(int)))(int)"]
+    );
+    assert_eq!(
+        declared(&r, "volatile struct s *x, *const *y;"),
+        vec!["x: volatile struct s *", "y: volatile struct s *const *"]
+    );
+    assert_eq!(declared(&r, "enum { A } e;"), vec!["e: enum <anonymous>"]);
+    assert_eq!(
+        declared(&r, "static _Complex float c;"),
+        vec!["c: _Complex float"]
+    );
+    assert_eq!(
+        declared(&r, "_Atomic(long long) l;"),
+        vec!["l: _Atomic long long"]
+    );
+    assert_eq!(
+        declared(&r, "unsigned _BitInt(7) b;"),
+        vec!["b: unsigned _BitInt(7)"]
+    );
+    assert_eq!(
+        declared(&r, "int f(int n, char s[static n], const int a[const 3]);"),
+        vec!["f: int (int, char [static n], const int [const 3])"]
+    );
+}
+
+#[test]
+fn test_parameters() {
+    use driver::{parse_declaration, Config};
+    use layout::DataModel;
+
+    let r = TypeResolver::new(Target::new(DataModel::LP64));
+    let decl = parse_declaration(&Config::default(), "int f(int a[3], int g(void));").unwrap();
+    let (_, ty) = r.declaration_types(&decl).unwrap().pop().unwrap();
+    let f = match ty.ty {
+        CType::Function(f) => f,
+        _ => panic!("not a function"),
+    };
+    assert!(f.prototype && !f.variadic);
+    assert_eq!(f.parameters[0].name.as_ref().unwrap().node.name, "a");
+    assert_eq!(f.parameters[0].ty.adjusted().to_string(), "int *");
+    assert_eq!(f.parameters[1].ty.adjusted().to_string(), "int (*)(void)");
+}
+
+#[test]
+fn test_typedefs() {
+    use driver::{parse_declaration, parse_preprocessed, Config};
+    use layout::DataModel;
+
+    let mut r = TypeResolver::new(Target::new(DataModel::LP64));
+    let mut config = Config::with_gcc();
+    config.typedef_names.push("T".into());
+    let decl = parse_declaration(&config, "typedef const int T[2];").unwrap();
+    r.add_declaration(&decl).unwrap();
+    assert_eq!(declared(&r, "volatile T t;"), vec!["t: volatile T"]);
+
+    let decl = parse_declaration(&config, "volatile T t;").unwrap();
+    let (_, ty) = r.declaration_types(&decl).unwrap().pop().unwrap();
+    let canonical = ty.canonical();
+    assert_eq!(canonical.to_string(), "const volatile int [2]");
+    assert_eq!(ty.adjusted().to_string(), "const volatile int *");
+
+    let source = "int f(a, b) char *a; { return 0; }";
+    let unit = parse_preprocessed(&config, source.into()).unwrap().unit;
+    let f = match unit.0[0].node {
+        ExternalDeclaration::FunctionDefinition(ref f) => f,
+        _ => panic!("not a function definition"),
+    };
+    let ty = r.function_definition_type(f).unwrap();
+    assert_eq!(ty.to_string(), "int (a, b)");
+    match ty.ty {
+        CType::Function(ref f) => {
+            assert!(!f.prototype);
+            assert_eq!(f.parameters[0].ty.to_string(), "char *");
+            assert_eq!(f.parameters[1].ty.to_string(), "int");
+        }
+        _ => panic!("not a function"),
+    }
+}
@@ -0,0 +1,1589 @@
+#![allow(unknown_lints)]
+#![allow(bare_trait_objects)]
+
+//! C source printer for abstract syntax tree
+//!
+//! Turns the AST back into C source code. Parentheses are inserted where
+//! required by operator precedence, so printing a parsed tree and parsing
+//! the result again produces an identical tree.
+//!
+//! ```
+//! # use lang_c::driver::{parse_preprocessed, Config};
+//! use lang_c::unparse::Unparser;
+//! let config = Config::default();
+//! let parse = parse_preprocessed(&config, "int main(){return 1+2*3;}".into()).unwrap();
+//! let s = &mut String::new();
+//! Unparser::new(s).translation_unit(&parse.unit);
+//! assert_eq!(s, "int main()\n{\n    return 1 + 2 * 3;\n}\n");
+//! ```
+use std::fmt;
+
+use ast::*;
+use span::Node;
+
+const COMMA: u8 = 0;
+const ASSIGNMENT: u8 = 1;
+const CONDITIONAL: u8 = 2;
+const LOGICAL_OR: u8 = 3;
+const CAST: u8 = 13;
+const UNARY: u8 = 14;
+const POSTFIX: u8 = 15;
+const PRIMARY: u8 = 16;
+
+/// C source printer
+///
+/// Statements and definitions are printed one per line with four space
+/// indentation. Spaces are inserted between tokens that would otherwise
+/// merge into one (for example in `- -x`).
+pub struct Unparser<'a> {
+    w: &'a mut fmt::Write,
+    indent: usize,
+    last: Option<char>,
+}
+
+impl<'a> Unparser<'a> {
+    pub fn new(w: &'a mut fmt::Write) -> Unparser<'a> {
+        Unparser {
+            w,
+            indent: 0,
+            last: None,
+        }
+    }
+
+    fn write(&mut self, s: &str) {
+        if let (Some(a), Some(b)) = (self.last, s.chars().next()) {
+            if needs_space(a, b) {
+                self.w.write_char(' ').unwrap();
+            }
+        }
+        self.w.write_str(s).unwrap();
+        if let Some(c) = s.chars().next_back() {
+            self.last = Some(c);
+        }
+    }
+
+    fn newline(&mut self) {
+        self.w.write_char('\n').unwrap();
+        write!(&mut self.w, "{:1$}", "", self.indent * 4).unwrap();
+        self.last = Some('\n');
+    }
+
+    fn list<T, F>(&mut self, items: &[T], sep: &str, mut f: F)
+    where
+        F: FnMut(&mut Self, &T),
+    {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.write(sep);
+            }
+            f(self, item);
+        }
+    }
+
+    /// Print a complete translation unit, ending with a newline
+    pub fn translation_unit(&mut self, n: &TranslationUnit) {
+        let mut previous = None;
+        for ed in &n.0 {
+            if let ExternalDeclaration::Error = ed.node {
+                continue;
+            }
+            let function = is_function_definition(&ed.node);
+            if let Some(previous) = previous {
+                self.newline();
+                if function || previous {
+                    self.newline();
+                }
+            }
+            self.external_declaration(&ed.node);
+            previous = Some(function);
+        }
+        if previous.is_some() {
+            self.newline();
+        }
+    }
+
+    pub fn external_declaration(&mut self, n: &ExternalDeclaration) {
+        match *n {
+            ExternalDeclaration::Declaration(ref d) => self.declaration(&d.node),
+            ExternalDeclaration::StaticAssert(ref s) => self.static_assert(&s.node),
+            ExternalDeclaration::FunctionDefinition(ref f) => self.function_definition(&f.node),
+            ExternalDeclaration::Asm(ref a) => self.asm_statement(&a.node),
+            ExternalDeclaration::Pragma(ref p) => self.pragma(&p.node),
+            ExternalDeclaration::Error => {}
+        }
+    }
+
+    pub fn function_definition(&mut self, n: &FunctionDefinition) {
+        self.declaration_specifiers(&n.specifiers);
+        self.write(" ");
+        self.declarator(&n.declarator.node, false);
+        for d in &n.declarations {
+            self.newline();
+            self.declaration(&d.node);
+        }
+        self.newline();
+        self.statement(&n.statement.node);
+    }
+
+    /// Print a declaration, including the terminating semicolon
+    pub fn declaration(&mut self, n: &Declaration) {
+        self.declaration_specifiers(&n.specifiers);
+        if !n.declarators.is_empty() {
+            self.write(" ");
+            self.list(&n.declarators, ", ", |p, d| p.init_declarator(&d.node));
+        }
+        self.write(";");
+    }
+
+    fn declaration_specifiers(&mut self, n: &[Node<DeclarationSpecifier>]) {
+        self.list(n, " ", |p, s| p.declaration_specifier(&s.node));
+    }
+
+    fn declaration_specifier(&mut self, n: &DeclarationSpecifier) {
+        match *n {
+            DeclarationSpecifier::StorageClass(ref s) => {
+                self.write(storage_class_specifier(&s.node))
+            }
+            DeclarationSpecifier::TypeSpecifier(ref t) => self.type_specifier(&t.node),
+            DeclarationSpecifier::TypeQualifier(ref q) => self.write(type_qualifier(&q.node)),
+            DeclarationSpecifier::Function(ref f) => self.write(function_specifier(&f.node)),
+            DeclarationSpecifier::Alignment(ref a) => self.alignment_specifier(&a.node),
+            DeclarationSpecifier::Extension(ref e) => self.attribute_specifier(e),
+        }
+    }
+
+    fn init_declarator(&mut self, n: &InitDeclarator) {
+        self.declarator(&n.declarator.node, true);
+        if let Some(ref i) = n.initializer {
+            self.write(" = ");
+            self.initializer(&i.node);
+        }
+    }
+
+    fn type_specifier(&mut self, n: &TypeSpecifier) {
+        match *n {
+            TypeSpecifier::Void => self.write("void"),
+            TypeSpecifier::Char => self.write("char"),
+            TypeSpecifier::Short => self.write("short"),
+            TypeSpecifier::Int => self.write("int"),
+            TypeSpecifier::Long => self.write("long"),
+            TypeSpecifier::Float => self.write("float"),
+            TypeSpecifier::Double => self.write("double"),
+            TypeSpecifier::Signed => self.write("signed"),
+            TypeSpecifier::Unsigned => self.write("unsigned"),
+            TypeSpecifier::Bool => self.write("_Bool"),
+            TypeSpecifier::Complex => self.write("_Complex"),
+            TypeSpecifier::SizedInt(w) => self.write(&format!("__int{}", w)),
+            TypeSpecifier::BitInt(ref e) => {
+                self.write("_BitInt(");
+                self.expression_prec(&e.node, CONDITIONAL);
+                self.write(")");
+            }
+            TypeSpecifier::Atomic(ref t) => {
+                self.write("_Atomic(");
+                self.type_name(&t.node);
+                self.write(")");
+            }
+            TypeSpecifier::Struct(ref s) => self.struct_type(&s.node),
+            TypeSpecifier::Enum(ref e) => self.enum_type(&e.node),
+            TypeSpecifier::TypedefName(ref i) => self.write(&i.node.name),
+            TypeSpecifier::TypeOf(ref t) => {
+                self.write("__typeof__(");
+                self.type_of(&t.node);
+                self.write(")");
+            }
+            TypeSpecifier::TypeOfUnqual(ref t) => {
+                self.write("__typeof_unqual__(");
+                self.type_of(&t.node);
+                self.write(")");
+            }
+            TypeSpecifier::TS18661Float(ref f) => {
+                let s = match f.format {
+                    TS18661FloatFormat::BinaryInterchange => format!("_Float{}", f.width),
+                    TS18661FloatFormat::BinaryExtended => format!("_Float{}x", f.width),
+                    TS18661FloatFormat::DecimalInterchange => format!("_Decimal{}", f.width),
+                    TS18661FloatFormat::DecimalExtended => format!("_Decimal{}x", f.width),
+                };
+                self.write(&s);
+            }
+        }
+    }
+
+    fn struct_type(&mut self, n: &StructType) {
+        self.write(match n.kind.node {
+            StructKind::Struct => "struct",
+            StructKind::Union => "union",
+        });
+        if !n.extensions.is_empty() {
+            self.write(" ");
+            self.extensions(&n.extensions);
+        }
+        if let Some(ref i) = n.identifier {
+            self.write(" ");
+            self.write(&i.node.name);
+        }
+        match n.declarations {
+            Some(ref d) if d.is_empty() => self.write(" {}"),
+            Some(ref d) => {
+                self.write(" {");
+                self.indent += 1;
+                for d in d {
+                    self.newline();
+                    match d.node {
+                        StructDeclaration::Field(ref f) => self.struct_field(&f.node),
+                        StructDeclaration::StaticAssert(ref s) => self.static_assert(&s.node),
+                        StructDeclaration::Pragma(ref p) => self.pragma(&p.node),
+                    }
+                }
+                self.indent -= 1;
+                self.newline();
+                self.write("}");
+            }
+            None => {}
+        }
+    }
+
+    fn struct_field(&mut self, n: &StructField) {
+        self.specifier_qualifiers(&n.specifiers);
+        if !n.declarators.is_empty() {
+            self.write(" ");
+            self.list(&n.declarators, ", ", |p, d| p.struct_declarator(&d.node));
+        }
+        self.write(";");
+    }
+
+    fn struct_declarator(&mut self, n: &StructDeclarator) {
+        match (n.declarator.as_ref(), n.bit_width.as_ref()) {
+            (Some(d), None) => self.declarator(&d.node, true),
+            (Some(d), Some(w)) => {
+                // Attributes of a bit-field follow the width
+                let (prefix, suffix) = split_extensions(&d.node, true);
+                self.declarator_body(&d.node, prefix);
+                self.write(" : ");
+                self.expression_prec(&w.node, CONDITIONAL);
+                let suffix = non_standard(suffix);
+                if !suffix.is_empty() {
+                    self.write(" ");
+                    self.extension_refs(&suffix);
+                }
+            }
+            (None, Some(w)) => {
+                self.write(": ");
+                self.expression_prec(&w.node, CONDITIONAL);
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn specifier_qualifiers(&mut self, n: &[Node<SpecifierQualifier>]) {
+        self.list(n, " ", |p, s| match s.node {
+            SpecifierQualifier::TypeSpecifier(ref t) => p.type_specifier(&t.node),
+            SpecifierQualifier::TypeQualifier(ref q) => p.write(type_qualifier(&q.node)),
+            SpecifierQualifier::Alignment(ref a) => p.alignment_specifier(&a.node),
+            SpecifierQualifier::Extension(ref e) => p.attribute_specifier(e),
+        });
+    }
+
+    fn enum_type(&mut self, n: &EnumType) {
+        self.write("enum");
+        if let Some(ref i) = n.identifier {
+            self.write(" ");
+            self.write(&i.node.name);
+        }
+        if n.enumerators.is_empty() {
+            return;
+        }
+        self.write(" {");
+        self.indent += 1;
+        for e in &n.enumerators {
+            self.newline();
+            self.write(&e.node.identifier.node.name);
+            if !e.node.extensions.is_empty() {
+                self.write(" ");
+                self.extensions(&e.node.extensions);
+            }
+            if let Some(ref e) = e.node.expression {
+                self.write(" = ");
+                self.expression_prec(&e.node, CONDITIONAL);
+            }
+            self.write(",");
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn alignment_specifier(&mut self, n: &AlignmentSpecifier) {
+        self.write("_Alignas(");
+        match *n {
+            AlignmentSpecifier::Type(ref t) => self.type_name(&t.node),
+            AlignmentSpecifier::Constant(ref e) => self.expression_prec(&e.node, CONDITIONAL),
+        }
+        self.write(")");
+    }
+
+    /// Print a declarator
+    ///
+    /// Extensions are printed after the declarator if `postfix` is set,
+    /// except for attributes preceding an asm label. Otherwise all
+    /// extensions are printed before the declarator.
+    fn declarator(&mut self, n: &Declarator, postfix: bool) {
+        let (prefix, suffix) = split_extensions(n, postfix);
+        self.declarator_body(n, prefix);
+        let suffix = non_standard(suffix);
+        if !suffix.is_empty() {
+            self.write(" ");
+            self.extension_refs(&suffix);
+        }
+    }
+
+    fn declarator_body(&mut self, n: &Declarator, prefix: &[Node<Extension>]) {
+        let prefix = non_standard(prefix);
+        if !prefix.is_empty() {
+            self.extension_refs(&prefix);
+            self.write(" ");
+        }
+        for d in &n.derived {
+            let (token, qualifiers) = match d.node {
+                DerivedDeclarator::Pointer(ref q) => ("*", q),
+                DerivedDeclarator::Block(ref q) => ("^", q),
+                _ => continue,
+            };
+            self.write(token);
+            if !qualifiers.is_empty() {
+                self.list(qualifiers, " ", |p, q| p.pointer_qualifier(&q.node));
+                self.write(" ");
+            }
+        }
+        match n.kind.node {
+            DeclaratorKind::Abstract => {}
+            DeclaratorKind::Identifier(ref i) => self.write(&i.node.name),
+            DeclaratorKind::Declarator(ref d) => {
+                self.write("(");
+                self.declarator(&d.node, false);
+                self.write(")");
+            }
+        }
+        let standard = n
+            .extensions
+            .iter()
+            .filter(|e| is_standard_attribute(&e.node))
+            .collect::<Vec<_>>();
+        if !standard.is_empty() {
+            self.write(" ");
+            self.extension_refs(&standard);
+        }
+        for d in &n.derived {
+            match d.node {
+                DerivedDeclarator::Pointer(_) | DerivedDeclarator::Block(_) => {}
+                DerivedDeclarator::Array(ref a) => self.array_declarator(&a.node),
+                DerivedDeclarator::Function(ref f) => self.function_declarator(&f.node),
+                DerivedDeclarator::KRFunction(ref p) => {
+                    self.write("(");
+                    self.list(p, ", ", |p, i| p.write(&i.node.name));
+                    self.write(")");
+                }
+            }
+        }
+    }
+
+    fn pointer_qualifier(&mut self, n: &PointerQualifier) {
+        match *n {
+            PointerQualifier::TypeQualifier(ref q) => self.write(type_qualifier(&q.node)),
+            PointerQualifier::Extension(ref e) => self.attribute_specifier(e),
+        }
+    }
+
+    fn array_declarator(&mut self, n: &ArrayDeclarator) {
+        self.write("[");
+        if let ArraySize::StaticExpression(_) = n.size {
+            self.write("static ");
+        }
+        self.list(&n.qualifiers, " ", |p, q| p.write(type_qualifier(&q.node)));
+        match n.size {
+            ArraySize::Unknown => {}
+            ArraySize::VariableUnknown => self.write("*"),
+            ArraySize::VariableExpression(ref e) | ArraySize::StaticExpression(ref e) => {
+                if !n.qualifiers.is_empty() {
+                    self.write(" ");
+                }
+                self.expression_prec(&e.node, ASSIGNMENT);
+            }
+        }
+        self.write("]");
+        if !n.extensions.is_empty() {
+            self.write(" ");
+            self.extensions(&n.extensions);
+        }
+    }
+
+    fn function_declarator(&mut self, n: &FunctionDeclarator) {
+        self.write("(");
+        self.list(&n.parameters, ", ", |p, d| p.parameter_declaration(&d.node));
+        if let Ellipsis::Some = n.ellipsis {
+            self.write(", ...");
+        }
+        self.write(")");
+        if !n.extensions.is_empty() {
+            self.write(" ");
+            self.extensions(&n.extensions);
+        }
+    }
+
+    fn parameter_declaration(&mut self, n: &ParameterDeclaration) {
+        self.declaration_specifiers(&n.specifiers);
+        if let Some(ref d) = n.declarator {
+            self.write(" ");
+            self.declarator(&d.node, false);
+        }
+        if !n.extensions.is_empty() {
+            self.write(" ");
+            self.extensions(&n.extensions);
+        }
+    }
+
+    pub fn type_name(&mut self, n: &TypeName) {
+        self.specifier_qualifiers(&n.specifiers);
+        if let Some(ref d) = n.declarator {
+            self.write(" ");
+            self.declarator(&d.node, false);
+        }
+    }
+
+    fn initializer(&mut self, n: &Initializer) {
+        match *n {
+            Initializer::Expression(ref e) => self.expression_prec(&e.node, ASSIGNMENT),
+            Initializer::List(ref l) => self.initializer_list(l),
+        }
+    }
+
+    fn initializer_list(&mut self, n: &[Node<InitializerListItem>]) {
+        if n.is_empty() {
+            return self.write("{}");
+        }
+        self.write("{ ");
+        self.list(n, ", ", |p, i| {
+            for d in &i.node.designation {
+                p.designator(&d.node);
+            }
+            if !i.node.designation.is_empty() {
+                p.write(" = ");
+            }
+            p.initializer(&i.node.initializer.node);
+        });
+        self.write(" }");
+    }
+
+    fn designator(&mut self, n: &Designator) {
+        match *n {
+            Designator::Index(ref e) => {
+                self.write("[");
+                self.expression_prec(&e.node, CONDITIONAL);
+                self.write("]");
+            }
+            Designator::Member(ref i) => {
+                self.write(".");
+                self.write(&i.node.name);
+            }
+            Designator::Range(ref r) => {
+                self.write("[");
+                self.expression_prec(&r.node.from.node, CONDITIONAL);
+                self.write(" ... ");
+                self.expression_prec(&r.node.to.node, CONDITIONAL);
+                self.write("]");
+            }
+        }
+    }
+
+    fn type_of(&mut self, n: &TypeOf) {
+        match *n {
+            TypeOf::Expression(ref e) => self.expression_prec(&e.node, COMMA),
+            TypeOf::Type(ref t) => self.type_name(&t.node),
+        }
+    }
+
+    fn static_assert(&mut self, n: &StaticAssert) {
+        self.write("_Static_assert(");
+        self.expression_prec(&n.expression.node, CONDITIONAL);
+        if let Some(ref m) = n.message {
+            self.write(", ");
+            self.string_literal(&m.node);
+        }
+        self.write(");");
+    }
+
+    fn attribute_specifier(&mut self, n: &[Node<Extension>]) {
+        if n.is_empty() {
+            self.write("__attribute__(())");
+        } else {
+            self.extensions(n);
+        }
+    }
+
+    fn attribute_group(&mut self, n: &[&Node<Extension>]) {
+        let (open, separator, close) = match n.first().map(|e| &e.node) {
+            Some(&Extension::StandardAttribute(_)) => ("[[", ", ", "]]"),
+            Some(&Extension::Declspec(_)) => ("__declspec(", " ", ")"),
+            _ => ("__attribute__((", ", ", "))"),
+        };
+        self.write(open);
+        self.list(n, separator, |p, e| p.extension(&e.node));
+        self.write(close);
+    }
+
+    /// Print a flat list of extensions, grouping adjacent attributes of the same kind
+    fn extensions(&mut self, n: &[Node<Extension>]) {
+        self.extension_refs(&n.iter().collect::<Vec<_>>());
+    }
+
+    fn extension_refs(&mut self, n: &[&Node<Extension>]) {
+        let mut rest = n;
+        while let Some(first) = rest.first() {
+            if rest.len() < n.len() {
+                self.write(" ");
+            }
+            let len = match extension_group(&first.node) {
+                None => {
+                    self.extension(&first.node);
+                    1
+                }
+                group => {
+                    let len = rest
+                        .iter()
+                        .position(|e| extension_group(&e.node) != group)
+                        .unwrap_or(rest.len());
+                    self.attribute_group(&rest[..len]);
+                    len
+                }
+            };
+            rest = &rest[len..];
+        }
+    }
+
+    fn extension(&mut self, n: &Extension) {
+        match *n {
+            Extension::Attribute(ref a) | Extension::Declspec(ref a) => {
+                self.write(&a.name.node);
+                if !a.arguments.is_empty() {
+                    self.write("(");
+                    self.list(&a.arguments, ", ", |p, e| {
+                        p.expression_prec(&e.node, ASSIGNMENT)
+                    });
+                    self.write(")");
+                }
+            }
+            Extension::CallingConvention(ref c) => self.write(calling_convention(c)),
+            Extension::AsmLabel(ref s) => {
+                self.write("__asm__(");
+                self.string_literal(&s.node);
+                self.write(")");
+            }
+            Extension::AvailabilityAttribute(ref a) => {
+                self.write("availability(");
+                self.write(&a.node.platform.node.name);
+                for c in &a.node.clauses {
+                    self.write(", ");
+                    self.availability_clause(&c.node);
+                }
+                self.write(")");
+            }
+            Extension::StandardAttribute(ref a) => {
+                if let Some(ref prefix) = a.node.prefix {
+                    self.write(&prefix.node);
+                    self.write("::");
+                }
+                self.write(&a.node.name.node);
+                if let Some(ref arguments) = a.node.arguments {
+                    self.write("(");
+                    let mut previous = "(";
+                    for token in arguments {
+                        let token = &token.node[..];
+                        let open = previous == "(" || previous == "[" || previous == "{";
+                        let close = token == ")" || token == "]" || token == "}" || token == ",";
+                        let postfix = (token == "(" || token == "[")
+                            && previous.chars().all(|c| c.is_alphanumeric() || c == '_');
+                        if !open && !close && !postfix {
+                            self.write(" ");
+                        }
+                        self.write(token);
+                        previous = token;
+                    }
+                    self.write(")");
+                }
+            }
+        }
+    }
+
+    fn availability_clause(&mut self, n: &AvailabilityClause) {
+        match *n {
+            AvailabilityClause::Introduced(ref v) => {
+                self.write("introduced=");
+                self.availability_version(&v.node);
+            }
+            AvailabilityClause::Deprecated(ref v) => {
+                self.write("deprecated=");
+                self.availability_version(&v.node);
+            }
+            AvailabilityClause::Obsoleted(ref v) => {
+                self.write("obsoleted=");
+                self.availability_version(&v.node);
+            }
+            AvailabilityClause::Unavailable => self.write("unavailable"),
+            AvailabilityClause::Message(ref s) => {
+                self.write("message=");
+                self.string_literal(&s.node);
+            }
+            AvailabilityClause::Replacement(ref s) => {
+                self.write("replacement=");
+                self.string_literal(&s.node);
+            }
+        }
+    }
+
+    fn availability_version(&mut self, n: &AvailabilityVersion) {
+        self.write(&n.major);
+        if let Some(ref minor) = n.minor {
+            self.write(".");
+            self.write(minor);
+        }
+        if let Some(ref subminor) = n.subminor {
+            self.write(".");
+            self.write(subminor);
+        }
+    }
+
+    /// Print a statement starting at the current position
+    ///
+    /// Nested statements are printed on separate lines, indented relative
+    /// to the current indentation level.
+    pub fn statement(&mut self, n: &Statement) {
+        match *n {
+            Statement::Labeled(ref l) => {
+                self.label(&l.node.label.node);
+                self.write(":");
+                self.substatement(&l.node.statement.node);
+            }
+            Statement::Compound(ref items) => {
+                self.write("{");
+                self.indent += 1;
+                for item in items {
+                    self.block_item(&item.node);
+                }
+                self.indent -= 1;
+                self.newline();
+                self.write("}");
+            }
+            Statement::Expression(ref e) => {
+                if let Some(ref e) = *e {
+                    self.expression_prec(&e.node, COMMA);
+                }
+                self.write(";");
+            }
+            Statement::If(ref s) => {
+                self.write("if (");
+                self.expression_prec(&s.node.condition.node, COMMA);
+                self.write(")");
+                let then = &s.node.then_statement.node;
+                match s.node.else_statement {
+                    Some(ref e) => {
+                        if is_dangling(then) {
+                            // Keep the else from binding to the inner if
+                            self.write(" {");
+                            self.indent += 1;
+                            self.newline();
+                            self.statement(then);
+                            self.indent -= 1;
+                            self.newline();
+                            self.write("} else");
+                        } else {
+                            self.substatement(then);
+                            if is_compound(then) {
+                                self.write(" else");
+                            } else {
+                                self.newline();
+                                self.write("else");
+                            }
+                        }
+                        if let Statement::If(_) = e.node {
+                            self.write(" ");
+                            self.statement(&e.node);
+                        } else {
+                            self.substatement(&e.node);
+                        }
+                    }
+                    None => self.substatement(then),
+                }
+            }
+            Statement::Switch(ref s) => {
+                self.write("switch (");
+                self.expression_prec(&s.node.expression.node, COMMA);
+                self.write(")");
+                self.substatement(&s.node.statement.node);
+            }
+            Statement::While(ref s) => {
+                self.write("while (");
+                self.expression_prec(&s.node.expression.node, COMMA);
+                self.write(")");
+                self.substatement(&s.node.statement.node);
+            }
+            Statement::DoWhile(ref s) => {
+                self.write("do");
+                self.substatement(&s.node.statement.node);
+                if is_compound(&s.node.statement.node) {
+                    self.write(" ");
+                } else {
+                    self.newline();
+                }
+                self.write("while (");
+                self.expression_prec(&s.node.expression.node, COMMA);
+                self.write(");");
+            }
+            Statement::For(ref s) => {
+                self.write("for (");
+                match s.node.initializer.node {
+                    ForInitializer::Empty => self.write(";"),
+                    ForInitializer::Expression(ref e) => {
+                        self.expression_prec(&e.node, COMMA);
+                        self.write(";");
+                    }
+                    ForInitializer::Declaration(ref d) => self.declaration(&d.node),
+                    ForInitializer::StaticAssert(ref s) => self.static_assert(&s.node),
+                }
+                if let Some(ref e) = s.node.condition {
+                    self.write(" ");
+                    self.expression_prec(&e.node, COMMA);
+                }
+                self.write(";");
+                if let Some(ref e) = s.node.step {
+                    self.write(" ");
+                    self.expression_prec(&e.node, COMMA);
+                }
+                self.write(")");
+                self.substatement(&s.node.statement.node);
+            }
+            Statement::Goto(ref i) => {
+                self.write("goto ");
+                self.write(&i.node.name);
+                self.write(";");
+            }
+            Statement::GotoComputed(ref e) => {
+                self.write("goto *");
+                self.expression_prec(&e.node, COMMA);
+                self.write(";");
+            }
+            Statement::Continue => self.write("continue;"),
+            Statement::Break => self.write("break;"),
+            Statement::Return(ref e) => {
+                self.write("return");
+                if let Some(ref e) = *e {
+                    self.write(" ");
+                    self.expression_prec(&e.node, COMMA);
+                }
+                self.write(";");
+            }
+            Statement::Attributed(ref a) => {
+                self.extensions(&a.node.attributes);
+                if let Statement::Expression(None) = a.node.statement.node {
+                    return self.write(";");
+                }
+                self.write(" ");
+                self.statement(&a.node.statement.node);
+            }
+            Statement::Asm(ref a) => self.asm_statement(&a.node),
+            Statement::Try(ref t) => {
+                self.write("__try ");
+                self.statement(&t.node.statement.node);
+                match t.node.handler.node {
+                    TryHandler::Except(ref e) => {
+                        self.write(" __except (");
+                        self.expression_prec(&e.node.filter.node, COMMA);
+                        self.write(") ");
+                        self.statement(&e.node.statement.node);
+                    }
+                    TryHandler::Finally(ref s) => {
+                        self.write(" __finally ");
+                        self.statement(&s.node);
+                    }
+                }
+            }
+            Statement::Leave => self.write("__leave;"),
+        }
+    }
+
+    fn substatement(&mut self, n: &Statement) {
+        if is_compound(n) {
+            self.write(" ");
+            self.statement(n);
+        } else {
+            self.indent += 1;
+            self.newline();
+            self.statement(n);
+            self.indent -= 1;
+        }
+    }
+
+    fn block_item(&mut self, n: &BlockItem) {
+        match *n {
+            BlockItem::Declaration(ref d) => {
+                self.newline();
+                self.declaration(&d.node);
+            }
+            BlockItem::StaticAssert(ref s) => {
+                self.newline();
+                self.static_assert(&s.node);
+            }
+            BlockItem::Statement(ref s) => {
+                self.newline();
+                self.statement(&s.node);
+            }
+            BlockItem::LabelDeclaration(ref l) => {
+                self.newline();
+                self.write("__label__ ");
+                self.list(l, ", ", |p, i| p.write(&i.node.name));
+                self.write(";");
+            }
+            BlockItem::FunctionDefinition(ref f) => {
+                self.newline();
+                self.function_definition(&f.node);
+            }
+            BlockItem::Pragma(ref p) => {
+                self.newline();
+                self.pragma(&p.node);
+            }
+            BlockItem::Error => {}
+        }
+    }
+
+    /// Print a pragma as a directive, keeping tokens that were adjacent in the source together
+    fn pragma(&mut self, n: &Pragma) {
+        self.write("#pragma");
+        let mut end = None;
+        for t in &n.tokens {
+            if end != Some(t.span.start) {
+                self.write(" ");
+            }
+            self.write(&t.node);
+            end = Some(t.span.end);
+        }
+    }
+
+    fn label(&mut self, n: &Label) {
+        match *n {
+            Label::Identifier(ref i) => self.write(&i.node.name),
+            Label::Case(ref e) => {
+                self.write("case ");
+                self.expression_prec(&e.node, CONDITIONAL);
+            }
+            Label::CaseRange(ref r) => {
+                self.write("case ");
+                self.expression_prec(&r.node.low.node, CONDITIONAL);
+                self.write(" ... ");
+                self.expression_prec(&r.node.high.node, CONDITIONAL);
+            }
+            Label::Default => self.write("default"),
+        }
+    }
+
+    fn asm_statement(&mut self, n: &AsmStatement) {
+        self.write("__asm__");
+        match *n {
+            AsmStatement::GnuBasic(ref a) => {
+                self.asm_qualifiers(&a.qualifiers);
+                self.write(" (");
+                self.string_literal(&a.template.node);
+            }
+            AsmStatement::GnuExtended(ref a) => {
+                self.asm_qualifiers(&a.qualifiers);
+                self.write(" (");
+                self.string_literal(&a.template.node);
+                self.write(" :");
+                self.asm_operands(&a.outputs);
+                if !a.inputs.is_empty() || !a.clobbers.is_empty() || !a.labels.is_empty() {
+                    self.write(" :");
+                    self.asm_operands(&a.inputs);
+                }
+                if !a.clobbers.is_empty() || !a.labels.is_empty() {
+                    self.write(" :");
+                    if !a.clobbers.is_empty() {
+                        self.write(" ");
+                    }
+                    self.list(&a.clobbers, ", ", |p, s| p.string_literal(&s.node));
+                }
+                if !a.labels.is_empty() {
+                    self.write(" : ");
+                    self.list(&a.labels, ", ", |p, l| p.write(&l.node.name));
+                }
+            }
+        }
+        self.write(");");
+    }
+
+    fn asm_qualifiers(&mut self, n: &[Node<AsmQualifier>]) {
+        for q in n {
+            self.write(" ");
+            self.write(asm_qualifier(&q.node));
+        }
+    }
+
+    fn asm_operands(&mut self, n: &[Node<GnuAsmOperand>]) {
+        if !n.is_empty() {
+            self.write(" ");
+        }
+        self.list(n, ", ", |p, o| {
+            if let Some(ref i) = o.node.symbolic_name {
+                p.write("[");
+                p.write(&i.node.name);
+                p.write("] ");
+            }
+            p.string_literal(&o.node.constraints.node);
+            p.write(" (");
+            p.expression_prec(&o.node.variable_name.node, COMMA);
+            p.write(")");
+        });
+    }
+
+    pub fn expression(&mut self, n: &Expression) {
+        self.expression_prec(n, COMMA);
+    }
+
+    /// Print an expression, adding parentheses if it binds weaker than `min`
+    fn expression_prec(&mut self, n: &Expression, min: u8) {
+        if precedence(n) < min {
+            self.write("(");
+            self.expression_prec(n, COMMA);
+            self.write(")");
+            return;
+        }
+
+        match *n {
+            Expression::Identifier(ref i) => self.write(&i.node.name),
+            Expression::Constant(ref c) => self.constant(&c.node),
+            Expression::StringLiteral(ref s) => self.string_literal(&s.node),
+            Expression::GenericSelection(ref g) => {
+                self.write("_Generic(");
+                self.expression_prec(&g.node.expression.node, ASSIGNMENT);
+                for a in &g.node.associations {
+                    self.write(", ");
+                    match a.node {
+                        GenericAssociation::Type(ref t) => {
+                            self.type_name(&t.node.type_name.node);
+                            self.write(": ");
+                            self.expression_prec(&t.node.expression.node, ASSIGNMENT);
+                        }
+                        GenericAssociation::Default(ref e) => {
+                            self.write("default: ");
+                            self.expression_prec(&e.node, ASSIGNMENT);
+                        }
+                    }
+                }
+                self.write(")");
+            }
+            Expression::Member(ref m) => {
+                self.expression_prec(&m.node.expression.node, POSTFIX);
+                self.write(match m.node.operator.node {
+                    MemberOperator::Direct => ".",
+                    MemberOperator::Indirect => "->",
+                });
+                self.write(&m.node.identifier.node.name);
+            }
+            Expression::Call(ref c) => {
+                self.expression_prec(&c.node.callee.node, POSTFIX);
+                self.write("(");
+                self.list(&c.node.arguments, ", ", |p, e| {
+                    p.expression_prec(&e.node, ASSIGNMENT)
+                });
+                self.write(")");
+            }
+            Expression::CompoundLiteral(ref c) => {
+                self.write("(");
+                self.type_name(&c.node.type_name.node);
+                self.write(")");
+                self.initializer_list(&c.node.initializer_list);
+            }
+            Expression::SizeOfTy(ref s) => {
+                self.write("sizeof(");
+                self.type_name(&s.node.0.node);
+                self.write(")");
+            }
+            Expression::SizeOfVal(ref s) => {
+                self.write("sizeof ");
+                match s.node.0.node {
+                    // `sizeof (T){...}` would be read as `sizeof (T)`
+                    Expression::CompoundLiteral(_) => {
+                        self.write("(");
+                        self.expression_prec(&s.node.0.node, COMMA);
+                        self.write(")");
+                    }
+                    ref e => self.expression_prec(e, UNARY),
+                }
+            }
+            Expression::AlignOf(ref a) => {
+                self.write("_Alignof(");
+                self.type_name(&a.node.0.node);
+                self.write(")");
+            }
+            Expression::UnaryOperator(ref u) => {
+                let operand = &u.node.operand.node;
+                match u.node.operator.node {
+                    UnaryOperator::PostIncrement => {
+                        self.expression_prec(operand, POSTFIX);
+                        self.write("++");
+                    }
+                    UnaryOperator::PostDecrement => {
+                        self.expression_prec(operand, POSTFIX);
+                        self.write("--");
+                    }
+                    UnaryOperator::PreIncrement => {
+                        self.write("++");
+                        self.expression_prec(operand, UNARY);
+                    }
+                    UnaryOperator::PreDecrement => {
+                        self.write("--");
+                        self.expression_prec(operand, UNARY);
+                    }
+                    ref op => {
+                        self.write(unary_operator(op));
+                        self.expression_prec(operand, CAST);
+                    }
+                }
+            }
+            Expression::Cast(ref c) => {
+                self.write("(");
+                self.type_name(&c.node.type_name.node);
+                self.write(")");
+                self.expression_prec(&c.node.expression.node, CAST);
+            }
+            Expression::BinaryOperator(ref b) => {
+                let op = &b.node.operator.node;
+                let (lhs, rhs) = (&b.node.lhs.node, &b.node.rhs.node);
+                match *op {
+                    BinaryOperator::Index => {
+                        self.expression_prec(lhs, POSTFIX);
+                        self.write("[");
+                        self.expression_prec(rhs, COMMA);
+                        self.write("]");
+                    }
+                    _ if binary_precedence(op) == ASSIGNMENT => {
+                        self.expression_prec(lhs, UNARY);
+                        self.write(" ");
+                        self.write(binary_operator(op));
+                        self.write(" ");
+                        self.expression_prec(rhs, ASSIGNMENT);
+                    }
+                    _ => {
+                        let p = binary_precedence(op);
+                        self.expression_prec(lhs, p);
+                        self.write(" ");
+                        self.write(binary_operator(op));
+                        self.write(" ");
+                        self.expression_prec(rhs, p + 1);
+                    }
+                }
+            }
+            Expression::Conditional(ref c) => {
+                self.expression_prec(&c.node.condition.node, LOGICAL_OR);
+                match c.node.then_expression {
+                    Some(ref e) => {
+                        self.write(" ? ");
+                        self.expression_prec(&e.node, COMMA);
+                        self.write(" : ");
+                    }
+                    None => self.write(" ?: "),
+                }
+                self.expression_prec(&c.node.else_expression.node, CONDITIONAL);
+            }
+            Expression::Comma(ref e) => {
+                self.list(e, ", ", |p, e| p.expression_prec(&e.node, ASSIGNMENT));
+            }
+            Expression::OffsetOf(ref o) => {
+                self.write("__builtin_offsetof(");
+                self.type_name(&o.node.type_name.node);
+                self.write(", ");
+                self.write(&o.node.designator.node.base.node.name);
+                for m in &o.node.designator.node.members {
+                    match m.node {
+                        OffsetMember::Member(ref i) => {
+                            self.write(".");
+                            self.write(&i.node.name);
+                        }
+                        OffsetMember::IndirectMember(ref i) => {
+                            self.write("->");
+                            self.write(&i.node.name);
+                        }
+                        OffsetMember::Index(ref e) => {
+                            self.write("[");
+                            self.expression_prec(&e.node, COMMA);
+                            self.write("]");
+                        }
+                    }
+                }
+                self.write(")");
+            }
+            Expression::VaArg(ref v) => {
+                self.write("__builtin_va_arg(");
+                self.expression_prec(&v.node.va_list.node, ASSIGNMENT);
+                self.write(", ");
+                self.type_name(&v.node.type_name.node);
+                self.write(")");
+            }
+            Expression::Statement(ref s) => {
+                self.write("(");
+                self.statement(&s.node);
+                self.write(")");
+            }
+            Expression::LabelAddress(ref l) => {
+                self.write("&&");
+                self.write(&l.node.name);
+            }
+            Expression::TypesCompatible(ref t) => {
+                self.write("__builtin_types_compatible_p(");
+                self.type_name(&t.node.type_name_1.node);
+                self.write(", ");
+                self.type_name(&t.node.type_name_2.node);
+                self.write(")");
+            }
+            Expression::ChooseExpr(ref c) => {
+                self.write("__builtin_choose_expr(");
+                self.expression_prec(&c.node.condition.node, ASSIGNMENT);
+                self.write(", ");
+                self.expression_prec(&c.node.then_expression.node, ASSIGNMENT);
+                self.write(", ");
+                self.expression_prec(&c.node.else_expression.node, ASSIGNMENT);
+                self.write(")");
+            }
+            Expression::ConvertVector(ref c) => {
+                self.write("__builtin_convertvector(");
+                self.expression_prec(&c.node.vector.node, ASSIGNMENT);
+                self.write(", ");
+                self.type_name(&c.node.type_name.node);
+                self.write(")");
+            }
+            Expression::ShuffleVector(ref v) => {
+                self.write("__builtin_shufflevector(");
+                self.expression_prec(&v.node.vector_1.node, ASSIGNMENT);
+                self.write(", ");
+                self.expression_prec(&v.node.vector_2.node, ASSIGNMENT);
+                for i in &v.node.indices {
+                    self.write(", ");
+                    self.expression_prec(&i.node, ASSIGNMENT);
+                }
+                self.write(")");
+            }
+        }
+    }
+
+    pub fn constant(&mut self, n: &Constant) {
+        match *n {
+            Constant::Integer(ref i) => {
+                let mut s = String::from(match i.base {
+                    IntegerBase::Decimal => "",
+                    IntegerBase::Octal => "0",
+                    IntegerBase::Hexadecimal => "0x",
+                    IntegerBase::Binary => "0b",
+                });
+                s.push_str(&i.number);
+                if i.suffix.unsigned {
+                    s.push('u');
+                }
+                s.push_str(match i.suffix.size {
+                    IntegerSize::Int => "",
+                    IntegerSize::Long => "l",
+                    IntegerSize::LongLong => "ll",
+                    IntegerSize::BitInt => "wb",
+                });
+                if i.suffix.imaginary {
+                    s.push('i');
+                }
+                self.write(&s);
+            }
+            Constant::Float(ref f) => {
+                let mut s = String::from(match f.base {
+                    FloatBase::Decimal => "",
+                    FloatBase::Hexadecimal => "0x",
+                });
+                s.push_str(&f.number);
+                match f.suffix.format {
+                    FloatFormat::Float => s.push('f'),
+                    FloatFormat::Double => {}
+                    FloatFormat::LongDouble => s.push('l'),
+                    FloatFormat::TS18661Format(t) => {
+                        s.push_str(&match t.format {
+                            TS18661FloatFormat::BinaryInterchange => format!("f{}", t.width),
+                            TS18661FloatFormat::BinaryExtended => format!("f{}x", t.width),
+                            TS18661FloatFormat::DecimalInterchange => format!("d{}", t.width),
+                            TS18661FloatFormat::DecimalExtended => format!("d{}x", t.width),
+                        });
+                    }
+                }
+                if f.suffix.imaginary {
+                    s.push('i');
+                }
+                self.write(&s);
+            }
+            Constant::Character(ref c) => self.write(c),
+            Constant::Bool(b) => self.write(if b { "true" } else { "false" }),
+            Constant::Nullptr => self.write("nullptr"),
+        }
+    }
+
+    fn string_literal(&mut self, n: &StringLiteral) {
+        self.list(n, " ", |p, s| p.write(s));
+    }
+}
+
+fn needs_space(a: char, b: char) -> bool {
+    let ident = |c: char| c.is_alphanumeric() || c == '_';
+    let op = |c: char| "+-&|<>=".contains(c);
+    (ident(a) && ident(b)) || (op(a) && op(b)) || (a == '/' && (b == '*' || b == '/'))
+}
+
+fn is_asm_label(n: &Extension) -> bool {
+    match *n {
+        Extension::AsmLabel(_) => true,
+        _ => false,
+    }
+}
+
+fn is_msvc_extension(n: &Extension) -> bool {
+    match *n {
+        Extension::Declspec(_) | Extension::CallingConvention(_) => true,
+        _ => false,
+    }
+}
+
+/// Kind of the specifier an extension is printed in, `None` if printed alone
+fn extension_group(n: &Extension) -> Option<u8> {
+    match *n {
+        Extension::AsmLabel(_) | Extension::CallingConvention(_) => None,
+        Extension::StandardAttribute(_) => Some(0),
+        Extension::Declspec(_) => Some(1),
+        _ => Some(2),
+    }
+}
+
+fn is_standard_attribute(n: &Extension) -> bool {
+    match *n {
+        Extension::StandardAttribute(_) => true,
+        _ => false,
+    }
+}
+
+fn non_standard(n: &[Node<Extension>]) -> Vec<&Node<Extension>> {
+    n.iter()
+        .filter(|e| !is_standard_attribute(&e.node))
+        .collect()
+}
+
+fn is_compound(n: &Statement) -> bool {
+    match *n {
+        Statement::Compound(_) => true,
+        _ => false,
+    }
+}
+
+/// Test if an `else` following this statement would bind to an inner `if`
+fn is_dangling(n: &Statement) -> bool {
+    match *n {
+        Statement::If(ref s) => match s.node.else_statement {
+            Some(ref e) => is_dangling(&e.node),
+            None => true,
+        },
+        Statement::Labeled(ref s) => is_dangling(&s.node.statement.node),
+        Statement::Switch(ref s) => is_dangling(&s.node.statement.node),
+        Statement::While(ref s) => is_dangling(&s.node.statement.node),
+        Statement::For(ref s) => is_dangling(&s.node.statement.node),
+        Statement::Attributed(ref s) => is_dangling(&s.node.statement.node),
+        _ => false,
+    }
+}
+
+fn is_function_definition(n: &ExternalDeclaration) -> bool {
+    match *n {
+        ExternalDeclaration::FunctionDefinition(_) => true,
+        _ => false,
+    }
+}
+
+fn split_extensions(n: &Declarator, postfix: bool) -> (&[Node<Extension>], &[Node<Extension>]) {
+    let at = if postfix {
+        n.extensions
+            .iter()
+            .position(|e| is_asm_label(&e.node))
+            .unwrap_or_else(|| {
+                // MSVC specifiers are only valid before the declarator
+                n.extensions
+                    .iter()
+                    .take_while(|e| is_msvc_extension(&e.node))
+                    .count()
+            })
+    } else {
+        n.extensions.len()
+    };
+    n.extensions.split_at(at)
+}
+
+fn precedence(n: &Expression) -> u8 {
+    match *n {
+        Expression::Identifier(_)
+        | Expression::Constant(_)
+        | Expression::StringLiteral(_)
+        | Expression::GenericSelection(_)
+        | Expression::OffsetOf(_)
+        | Expression::VaArg(_)
+        | Expression::Statement(_)
+        | Expression::TypesCompatible(_)
+        | Expression::ChooseExpr(_)
+        | Expression::ConvertVector(_)
+        | Expression::ShuffleVector(_) => PRIMARY,
+        Expression::Member(_) | Expression::Call(_) | Expression::CompoundLiteral(_) => POSTFIX,
+        Expression::UnaryOperator(ref u) => match u.node.operator.node {
+            UnaryOperator::PostIncrement | UnaryOperator::PostDecrement => POSTFIX,
+            _ => UNARY,
+        },
+        Expression::SizeOfTy(_)
+        | Expression::SizeOfVal(_)
+        | Expression::AlignOf(_)
+        | Expression::LabelAddress(_) => UNARY,
+        Expression::Cast(_) => CAST,
+        Expression::BinaryOperator(ref b) => binary_precedence(&b.node.operator.node),
+        Expression::Conditional(_) => CONDITIONAL,
+        Expression::Comma(_) => COMMA,
+    }
+}
+
+fn binary_precedence(n: &BinaryOperator) -> u8 {
+    match *n {
+        BinaryOperator::Index => POSTFIX,
+        BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 12,
+        BinaryOperator::Plus | BinaryOperator::Minus => 11,
+        BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => 10,
+        BinaryOperator::Less
+        | BinaryOperator::Greater
+        | BinaryOperator::LessOrEqual
+        | BinaryOperator::GreaterOrEqual => 9,
+        BinaryOperator::Equals | BinaryOperator::NotEquals => 8,
+        BinaryOperator::BitwiseAnd => 7,
+        BinaryOperator::BitwiseXor => 6,
+        BinaryOperator::BitwiseOr => 5,
+        BinaryOperator::LogicalAnd => 4,
+        BinaryOperator::LogicalOr => LOGICAL_OR,
+        _ => ASSIGNMENT,
+    }
+}
+
+fn binary_operator(n: &BinaryOperator) -> &'static str {
+    match *n {
+        BinaryOperator::Index => "[]",
+        BinaryOperator::Multiply => "*",
+        BinaryOperator::Divide => "/",
+        BinaryOperator::Modulo => "%",
+        BinaryOperator::Plus => "+",
+        BinaryOperator::Minus => "-",
+        BinaryOperator::ShiftLeft => "<<",
+        BinaryOperator::ShiftRight => ">>",
+        BinaryOperator::Less => "<",
+        BinaryOperator::Greater => ">",
+        BinaryOperator::LessOrEqual => "<=",
+        BinaryOperator::GreaterOrEqual => ">=",
+        BinaryOperator::Equals => "==",
+        BinaryOperator::NotEquals => "!=",
+        BinaryOperator::BitwiseAnd => "&",
+        BinaryOperator::BitwiseXor => "^",
+        BinaryOperator::BitwiseOr => "|",
+        BinaryOperator::LogicalAnd => "&&",
+        BinaryOperator::LogicalOr => "||",
+        BinaryOperator::Assign => "=",
+        BinaryOperator::AssignMultiply => "*=",
+        BinaryOperator::AssignDivide => "/=",
+        BinaryOperator::AssignModulo => "%=",
+        BinaryOperator::AssignPlus => "+=",
+        BinaryOperator::AssignMinus => "-=",
+        BinaryOperator::AssignShiftLeft => "<<=",
+        BinaryOperator::AssignShiftRight => ">>=",
+        BinaryOperator::AssignBitwiseAnd => "&=",
+        BinaryOperator::AssignBitwiseXor => "^=",
+        BinaryOperator::AssignBitwiseOr => "|=",
+    }
+}
+
+fn unary_operator(n: &UnaryOperator) -> &'static str {
+    match *n {
+        UnaryOperator::PostIncrement | UnaryOperator::PreIncrement => "++",
+        UnaryOperator::PostDecrement | UnaryOperator::PreDecrement => "--",
+        UnaryOperator::Address => "&",
+        UnaryOperator::Indirection => "*",
+        UnaryOperator::Plus => "+",
+        UnaryOperator::Minus => "-",
+        UnaryOperator::Complement => "~",
+        UnaryOperator::Negate => "!",
+        UnaryOperator::Real => "__real__ ",
+        UnaryOperator::Imag => "__imag__ ",
+    }
+}
+
+fn asm_qualifier(n: &AsmQualifier) -> &'static str {
+    match *n {
+        AsmQualifier::Volatile => "volatile",
+        AsmQualifier::Inline => "inline",
+        AsmQualifier::Goto => "goto",
+    }
+}
+
+fn storage_class_specifier(n: &StorageClassSpecifier) -> &'static str {
+    match *n {
+        StorageClassSpecifier::Typedef => "typedef",
+        StorageClassSpecifier::Extern => "extern",
+        StorageClassSpecifier::Static => "static",
+        StorageClassSpecifier::ThreadLocal => "_Thread_local",
+        StorageClassSpecifier::Auto => "auto",
+        StorageClassSpecifier::Register => "register",
+        StorageClassSpecifier::Constexpr => "constexpr",
+    }
+}
+
+fn calling_convention(n: &CallingConvention) -> &'static str {
+    match *n {
+        CallingConvention::Cdecl => "__cdecl",
+        CallingConvention::Clrcall => "__clrcall",
+        CallingConvention::Stdcall => "__stdcall",
+        CallingConvention::Fastcall => "__fastcall",
+        CallingConvention::Thiscall => "__thiscall",
+        CallingConvention::Vectorcall => "__vectorcall",
+    }
+}
+
+fn type_qualifier(n: &TypeQualifier) -> &'static str {
+    match *n {
+        TypeQualifier::Const => "const",
+        TypeQualifier::Restrict => "restrict",
+        TypeQualifier::Volatile => "volatile",
+        TypeQualifier::Nonnull => "_Nonnull",
+        TypeQualifier::NullUnspecified => "_Null_unspecified",
+        TypeQualifier::Nullable => "_Nullable",
+        TypeQualifier::Atomic => "_Atomic",
+    }
+}
+
+fn function_specifier(n: &FunctionSpecifier) -> &'static str {
+    match *n {
+        FunctionSpecifier::Inline => "inline",
+        FunctionSpecifier::Noreturn => "_Noreturn",
+        FunctionSpecifier::ForceInline => "__forceinline",
+    }
+}
+
+#[cfg(test)]
+fn round_trip(source: &str) -> String {
+    use driver::{parse_preprocessed, Config};
+    use print::Printer;
+    use visit::Visit;
+
+    let config = Config::with_gcc();
+    let dump = |unit: &TranslationUnit| {
+        let mut s = String::new();
+        Printer::new(&mut s).visit_translation_unit(unit);
+        s
+    };
+
+    let first = parse_preprocessed(&config, source.into()).unwrap();
+    let mut printed = String::new();
+    Unparser::new(&mut printed).translation_unit(&first.unit);
+    let second = match parse_preprocessed(&config, printed.clone()) {
+        Ok(parse) => parse,
+        Err(e) => panic!("{}\n{}", printed, e),
+    };
+    assert_eq!(dump(&first.unit), dump(&second.unit), "\n{}", printed);
+    printed
+}
+
+#[test]
+fn test_expressions() {
+    let s = round_trip(
+        "int f(int a, int b, int *p) {
+            a = (a + b) * (a - b) / -(-b) - - -a;
+            a = a << (b >> 1) < b == (a & b & &a != 0);
+            a = (a, b) ? a ? b : a : (b = a);
+            a = (int)-a + sizeof((int){1}) + sizeof p[0] + sizeof(int *) + *p++ + (*p)++;
+            a = ++*p + -(unsigned long)++a + !~a + (a ? b : a)[p];
+            return a += b -= (a, b);
+        }",
+    );
+    assert!(s.contains("a = (a + b) * (a - b) / - -b - - -a;"));
+    assert!(s.contains("a = a << (b >> 1) < b == (a & b & &a != 0);"));
+    assert!(s.contains("a = (a, b) ? a ? b : a : (b = a);"));
+}
+
+#[test]
+fn test_literals() {
+    round_trip(
+        "long double x[] = { 0, 1, 017, 0x1fULL, 0b101, 1e10, .5f, 0x1.8p3l, 2.0df, 3.5i, 'a', L'\\n' };
+        char *s = \"abc\" L\"def\" \"\\\"\";",
+    );
+}
+
+#[test]
+fn test_declarations() {
+    round_trip(
+        "typedef int (*fp)(int, char **argv, ...);
+        typedef struct list { struct list *next; int x : 3, : 0; unsigned y; } list_t;
+        enum e { A, B = 3, C } e1;
+        union u;
+        static const volatile int *const *restrict q[static 3][], (*g(void))[2];
+        void h(int a[const *], int (*)(void), fp f, list_t l);
+        _Alignas(16) _Thread_local int aligned;
+        int k(a, b) int a; char *b; { return a; }
+        __typeof__(1 + 2) t1;
+        __typeof__(int *) t2;
+        __typeof_unqual__(const int) t3;
+        _Static_assert(sizeof(int) == 4, \"int\");",
+    );
+}
+
+#[test]
+fn test_gnu_extensions() {
+    round_trip(
+        "extern int printf(const char *, ...) __attribute__((format(printf, 1, 2)));
+        int x __asm__(\"y\") __attribute__((unused)) = 1, z __attribute__((aligned(8), packed));
+        struct s { int a __attribute__((packed)); int b : 2 __attribute__((packed)); };
+        struct empty {};
+        int *__attribute__((aligned(8))) p;
+        int f(int n) {
+            int a[10] = { [1] = 2, [3 ... 5] = 7, [8] = 1 };
+            struct { int x, y; } s = { .x = 1, .y = 2 };
+            switch (n) {
+            case 1 ... 3:
+                return ({ int t = n; t * 2; });
+            default:
+                break;
+            }
+            __asm__ volatile (\"nop\" : : \"r\" (n) : \"memory\");
+            __asm__ (\"mov %1, %0\" : [out] \"=r\" (n) : \"r\" (a[0]));
+            __asm__(\"nop\");
+            return __builtin_offsetof(struct s, b) + __builtin_va_arg(n, int) + __alignof__(int);
+        }",
+    );
+}
+
+#[test]
+fn test_statements() {
+    let s = round_trip(
+        "void f(int a) {
+            if (a) if (a) a++; else a--;
+            if (a) { if (a) a++; } else a--;
+            if (a) a++; else if (a) a--; else { a = 0; }
+            while (a) a--;
+            do a++; while (a < 10);
+            do { a++; } while (a < 10);
+            for (;;) break;
+            for (int i = 0; i < a; i++) { continue; }
+            for (a = 0; a < 10; a++) ;
+            goto end;
+        end:
+            return;
+        }",
+    );
+    assert!(
+        s.contains("    if (a) {\n        if (a)\n            a++;\n    } else\n        a--;\n")
+    );
+    assert!(s.contains("    do {\n        a++;\n    } while (a < 10);\n"));
+}
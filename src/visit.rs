@@ -17,6 +17,23 @@
 //! a corresponding free function in this module).
 //!
 //! Free functions apply the visitor to sub-nodes of any given AST node.
+//!
+//! `VisitMut` trait and `visit_*_mut` free functions do the same for mutable references, so that
+//! the AST can be modified in place.
+//!
+//! ```rust
+//! # use lang_c::{ast, span, visit};
+//! struct Rename;
+//!
+//! impl visit::VisitMut for Rename {
+//!     fn visit_identifier_mut(&mut self, ident: &mut ast::Identifier, span: &mut span::Span) {
+//!         if ident.name == "foo" {
+//!             ident.name = "bar".into();
+//!         }
+//!         visit::visit_identifier_mut(self, ident, span);
+//!     }
+//! }
+//! ```
 
 use ast::*;
 use span::Span;
@@ -173,6 +190,38 @@ pub trait Visit<'ast> {
         visit_va_arg_expression(self, va_arg_expression, span)
     }
 
+    fn visit_types_compatible_expression(
+        &mut self,
+        types_compatible_expression: &'ast TypesCompatibleExpression,
+        span: &'ast Span,
+    ) {
+        visit_types_compatible_expression(self, types_compatible_expression, span)
+    }
+
+    fn visit_choose_expression(
+        &mut self,
+        choose_expression: &'ast ChooseExpression,
+        span: &'ast Span,
+    ) {
+        visit_choose_expression(self, choose_expression, span)
+    }
+
+    fn visit_convert_vector_expression(
+        &mut self,
+        convert_vector_expression: &'ast ConvertVectorExpression,
+        span: &'ast Span,
+    ) {
+        visit_convert_vector_expression(self, convert_vector_expression, span)
+    }
+
+    fn visit_shuffle_vector_expression(
+        &mut self,
+        shuffle_vector_expression: &'ast ShuffleVectorExpression,
+        span: &'ast Span,
+    ) {
+        visit_shuffle_vector_expression(self, shuffle_vector_expression, span)
+    }
+
     fn visit_offset_of_expression(
         &mut self,
         offset_of_expression: &'ast OffsetOfExpression,
@@ -401,6 +450,26 @@ pub trait Visit<'ast> {
         visit_labeled_statement(self, labeled_statement, span)
     }
 
+    fn visit_attributed_statement(
+        &mut self,
+        attributed_statement: &'ast AttributedStatement,
+        span: &'ast Span,
+    ) {
+        visit_attributed_statement(self, attributed_statement, span)
+    }
+
+    fn visit_try_statement(&mut self, try_statement: &'ast TryStatement, span: &'ast Span) {
+        visit_try_statement(self, try_statement, span)
+    }
+
+    fn visit_try_handler(&mut self, try_handler: &'ast TryHandler, span: &'ast Span) {
+        visit_try_handler(self, try_handler, span)
+    }
+
+    fn visit_except_handler(&mut self, except_handler: &'ast ExceptHandler, span: &'ast Span) {
+        visit_except_handler(self, except_handler, span)
+    }
+
     fn visit_if_statement(&mut self, if_statement: &'ast IfStatement, span: &'ast Span) {
         visit_if_statement(self, if_statement, span)
     }
@@ -465,6 +534,10 @@ pub trait Visit<'ast> {
         visit_function_definition(self, function_definition, span)
     }
 
+    fn visit_pragma(&mut self, pragma: &'ast Pragma, span: &'ast Span) {
+        visit_pragma(self, pragma, span)
+    }
+
     fn visit_extension(&mut self, extension: &'ast Extension, span: &'ast Span) {
         visit_extension(self, extension, span)
     }
@@ -487,6 +560,28 @@ pub trait Visit<'ast> {
 
     fn visit_availability_clause(&mut self, _clause: &'ast AvailabilityClause, _span: &'ast Span) {}
 
+    fn visit_standard_attribute(
+        &mut self,
+        _standard_attribute: &'ast StandardAttribute,
+        _span: &'ast Span,
+    ) {
+    }
+
+    fn visit_calling_convention(
+        &mut self,
+        _calling_convention: &'ast CallingConvention,
+        _span: &'ast Span,
+    ) {
+    }
+
+    fn visit_gnu_basic_asm_statement(
+        &mut self,
+        gnu_basic_asm_statement: &'ast GnuBasicAsmStatement,
+        span: &'ast Span,
+    ) {
+        visit_gnu_basic_asm_statement(self, gnu_basic_asm_statement, span)
+    }
+
     fn visit_gnu_extended_asm_statement(
         &mut self,
         gnu_extended_asm_statement: &'ast GnuExtendedAsmStatement,
@@ -495,6 +590,8 @@ pub trait Visit<'ast> {
         visit_gnu_extended_asm_statement(self, gnu_extended_asm_statement, span)
     }
 
+    fn visit_asm_qualifier(&mut self, _asm_qualifier: &'ast AsmQualifier, _span: &'ast Span) {}
+
     fn visit_gnu_asm_operand(&mut self, gnu_asm_operand: &'ast GnuAsmOperand, span: &'ast Span) {
         visit_gnu_asm_operand(self, gnu_asm_operand, span)
     }
@@ -520,6 +617,8 @@ pub fn visit_constant<'ast, V: Visit<'ast> + ?Sized>(
         Constant::Integer(ref i) => visitor.visit_integer(i, span),
         Constant::Float(ref f) => visitor.visit_float(f, span),
         Constant::Character(_) => {}
+        Constant::Bool(_) => {}
+        Constant::Nullptr => {}
     }
 }
 
@@ -583,9 +682,8 @@ pub fn visit_float_format<'ast, V: Visit<'ast> + ?Sized>(
     float_format: &'ast FloatFormat,
     span: &'ast Span,
 ) {
-    match *float_format {
-        FloatFormat::TS18661Format(ref f) => visitor.visit_ts18661_float_type(f, span),
-        _ => {}
+    if let FloatFormat::TS18661Format(ref f) = *float_format {
+        visitor.visit_ts18661_float_type(f, span);
     }
 }
 
@@ -628,6 +726,17 @@ pub fn visit_expression<'ast, V: Visit<'ast> + ?Sized>(
         Expression::OffsetOf(ref o) => visitor.visit_offset_of_expression(&o.node, &o.span),
         Expression::VaArg(ref v) => visitor.visit_va_arg_expression(&v.node, &v.span),
         Expression::Statement(ref s) => visitor.visit_statement(&s.node, &s.span),
+        Expression::LabelAddress(ref l) => visitor.visit_identifier(&l.node, &l.span),
+        Expression::TypesCompatible(ref t) => {
+            visitor.visit_types_compatible_expression(&t.node, &t.span)
+        }
+        Expression::ChooseExpr(ref c) => visitor.visit_choose_expression(&c.node, &c.span),
+        Expression::ConvertVector(ref c) => {
+            visitor.visit_convert_vector_expression(&c.node, &c.span)
+        }
+        Expression::ShuffleVector(ref v) => {
+            visitor.visit_shuffle_vector_expression(&v.node, &v.span)
+        }
     }
 }
 
@@ -832,10 +941,9 @@ pub fn visit_conditional_expression<'ast, V: Visit<'ast> + ?Sized>(
         &conditional_expression.condition.node,
         &conditional_expression.condition.span,
     );
-    visitor.visit_expression(
-        &conditional_expression.then_expression.node,
-        &conditional_expression.then_expression.span,
-    );
+    if let Some(ref e) = conditional_expression.then_expression {
+        visitor.visit_expression(&e.node, &e.span);
+    }
     visitor.visit_expression(
         &conditional_expression.else_expression.node,
         &conditional_expression.else_expression.span,
@@ -857,6 +965,73 @@ pub fn visit_va_arg_expression<'ast, V: Visit<'ast> + ?Sized>(
     );
 }
 
+pub fn visit_types_compatible_expression<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    types_compatible_expression: &'ast TypesCompatibleExpression,
+    _span: &'ast Span,
+) {
+    visitor.visit_type_name(
+        &types_compatible_expression.type_name_1.node,
+        &types_compatible_expression.type_name_1.span,
+    );
+    visitor.visit_type_name(
+        &types_compatible_expression.type_name_2.node,
+        &types_compatible_expression.type_name_2.span,
+    );
+}
+
+pub fn visit_choose_expression<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    choose_expression: &'ast ChooseExpression,
+    _span: &'ast Span,
+) {
+    visitor.visit_expression(
+        &choose_expression.condition.node,
+        &choose_expression.condition.span,
+    );
+    visitor.visit_expression(
+        &choose_expression.then_expression.node,
+        &choose_expression.then_expression.span,
+    );
+    visitor.visit_expression(
+        &choose_expression.else_expression.node,
+        &choose_expression.else_expression.span,
+    );
+}
+
+pub fn visit_convert_vector_expression<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    convert_vector_expression: &'ast ConvertVectorExpression,
+    _span: &'ast Span,
+) {
+    visitor.visit_expression(
+        &convert_vector_expression.vector.node,
+        &convert_vector_expression.vector.span,
+    );
+    visitor.visit_type_name(
+        &convert_vector_expression.type_name.node,
+        &convert_vector_expression.type_name.span,
+    );
+}
+
+pub fn visit_shuffle_vector_expression<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    shuffle_vector_expression: &'ast ShuffleVectorExpression,
+    _span: &'ast Span,
+) {
+    visitor.visit_expression(
+        &shuffle_vector_expression.vector_1.node,
+        &shuffle_vector_expression.vector_1.span,
+    );
+    visitor.visit_expression(
+        &shuffle_vector_expression.vector_2.node,
+        &shuffle_vector_expression.vector_2.span,
+    );
+    for index in &shuffle_vector_expression.indices {
+        visitor.visit_expression(&index.node, &index.span);
+    }
+}
+
 pub fn visit_offset_of_expression<'ast, V: Visit<'ast> + ?Sized>(
     visitor: &mut V,
     offset_of_expression: &'ast OffsetOfExpression,
@@ -964,10 +1139,12 @@ pub fn visit_type_specifier<'ast, V: Visit<'ast> + ?Sized>(
 ) {
     match *type_specifier {
         TypeSpecifier::Atomic(ref a) => visitor.visit_type_name(&a.node, &a.span),
+        TypeSpecifier::BitInt(ref e) => visitor.visit_expression(&e.node, &e.span),
         TypeSpecifier::Struct(ref s) => visitor.visit_struct_type(&s.node, &s.span),
         TypeSpecifier::Enum(ref e) => visitor.visit_enum_type(&e.node, &e.span),
         TypeSpecifier::TypedefName(ref t) => visitor.visit_identifier(&t.node, &t.span),
         TypeSpecifier::TypeOf(ref t) => visitor.visit_type_of(&t.node, &t.span),
+        TypeSpecifier::TypeOfUnqual(ref t) => visitor.visit_type_of(&t.node, &t.span),
         TypeSpecifier::TS18661Float(ref t) => visitor.visit_ts18661_float_type(t, span),
         _ => {}
     }
@@ -994,6 +1171,9 @@ pub fn visit_struct_type<'ast, V: Visit<'ast> + ?Sized>(
     _span: &'ast Span,
 ) {
     visitor.visit_struct_kind(&struct_type.kind.node, &struct_type.kind.span);
+    for extension in &struct_type.extensions {
+        visitor.visit_extension(&extension.node, &extension.span);
+    }
     if let Some(ref identifier) = struct_type.identifier {
         visitor.visit_identifier(&identifier.node, &identifier.span);
     }
@@ -1019,6 +1199,7 @@ pub fn visit_struct_declaration<'ast, V: Visit<'ast> + ?Sized>(
     match *struct_declaration {
         StructDeclaration::Field(ref f) => visitor.visit_struct_field(&f.node, &f.span),
         StructDeclaration::StaticAssert(ref s) => visitor.visit_static_assert(&s.node, &s.span),
+        StructDeclaration::Pragma(ref p) => visitor.visit_pragma(&p.node, &p.span),
     }
 }
 
@@ -1043,6 +1224,7 @@ pub fn visit_specifier_qualifier<'ast, V: Visit<'ast> + ?Sized>(
     match *specifier_qualifier {
         SpecifierQualifier::TypeSpecifier(ref t) => visitor.visit_type_specifier(&t.node, &t.span),
         SpecifierQualifier::TypeQualifier(ref t) => visitor.visit_type_qualifier(&t.node, &t.span),
+        SpecifierQualifier::Alignment(ref a) => visitor.visit_alignment_specifier(&a.node, &a.span),
         SpecifierQualifier::Extension(ref e) => {
             for n in e {
                 visitor.visit_extension(&n.node, &n.span);
@@ -1176,7 +1358,10 @@ pub fn visit_array_declarator<'ast, V: Visit<'ast> + ?Sized>(
     for qualifier in &array_declarator.qualifiers {
         visitor.visit_type_qualifier(&qualifier.node, &qualifier.span);
     }
-    visitor.visit_array_size(&array_declarator.size, span)
+    visitor.visit_array_size(&array_declarator.size, span);
+    for extension in &array_declarator.extensions {
+        visitor.visit_extension(&extension.node, &extension.span);
+    }
 }
 
 pub fn visit_function_declarator<'ast, V: Visit<'ast> + ?Sized>(
@@ -1188,6 +1373,9 @@ pub fn visit_function_declarator<'ast, V: Visit<'ast> + ?Sized>(
         visitor.visit_parameter_declaration(&parameter.node, &parameter.span);
     }
     visitor.visit_ellipsis(&function_declarator.ellipsis, span);
+    for extension in &function_declarator.extensions {
+        visitor.visit_extension(&extension.node, &extension.span);
+    }
 }
 
 pub fn visit_pointer_qualifier<'ast, V: Visit<'ast> + ?Sized>(
@@ -1312,7 +1500,9 @@ pub fn visit_static_assert<'ast, V: Visit<'ast> + ?Sized>(
         &static_assert.expression.node,
         &static_assert.expression.span,
     );
-    visitor.visit_string_literal(&static_assert.message.node, &static_assert.message.span);
+    if let Some(ref message) = static_assert.message {
+        visitor.visit_string_literal(&message.node, &message.span);
+    }
 }
 
 pub fn visit_statement<'ast, V: Visit<'ast> + ?Sized>(
@@ -1336,10 +1526,13 @@ pub fn visit_statement<'ast, V: Visit<'ast> + ?Sized>(
         Statement::DoWhile(ref d) => visitor.visit_do_while_statement(&d.node, &d.span),
         Statement::For(ref f) => visitor.visit_for_statement(&f.node, &f.span),
         Statement::Goto(ref g) => visitor.visit_identifier(&g.node, &g.span),
+        Statement::GotoComputed(ref e) => visitor.visit_expression(&e.node, &e.span),
         Statement::Return(Some(ref r)) => {
             visitor.visit_expression(&r.node, &r.span);
         }
+        Statement::Attributed(ref a) => visitor.visit_attributed_statement(&a.node, &a.span),
         Statement::Asm(ref a) => visitor.visit_asm_statement(&a.node, &a.span),
+        Statement::Try(ref t) => visitor.visit_try_statement(&t.node, &t.span),
         _ => {}
     }
 }
@@ -1356,6 +1549,52 @@ pub fn visit_labeled_statement<'ast, V: Visit<'ast> + ?Sized>(
     );
 }
 
+pub fn visit_attributed_statement<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    attributed_statement: &'ast AttributedStatement,
+    _span: &'ast Span,
+) {
+    for attribute in &attributed_statement.attributes {
+        visitor.visit_extension(&attribute.node, &attribute.span);
+    }
+    visitor.visit_statement(
+        &attributed_statement.statement.node,
+        &attributed_statement.statement.span,
+    );
+}
+
+pub fn visit_try_statement<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    try_statement: &'ast TryStatement,
+    _span: &'ast Span,
+) {
+    visitor.visit_statement(&try_statement.statement.node, &try_statement.statement.span);
+    visitor.visit_try_handler(&try_statement.handler.node, &try_statement.handler.span);
+}
+
+pub fn visit_try_handler<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    try_handler: &'ast TryHandler,
+    _span: &'ast Span,
+) {
+    match *try_handler {
+        TryHandler::Except(ref e) => visitor.visit_except_handler(&e.node, &e.span),
+        TryHandler::Finally(ref s) => visitor.visit_statement(&s.node, &s.span),
+    }
+}
+
+pub fn visit_except_handler<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    except_handler: &'ast ExceptHandler,
+    _span: &'ast Span,
+) {
+    visitor.visit_expression(&except_handler.filter.node, &except_handler.filter.span);
+    visitor.visit_statement(
+        &except_handler.statement.node,
+        &except_handler.statement.span,
+    );
+}
+
 pub fn visit_if_statement<'ast, V: Visit<'ast> + ?Sized>(
     visitor: &mut V,
     if_statement: &'ast IfStatement,
@@ -1478,6 +1717,14 @@ pub fn visit_block_item<'ast, V: Visit<'ast> + ?Sized>(
         BlockItem::Declaration(ref d) => visitor.visit_declaration(&d.node, &d.span),
         BlockItem::StaticAssert(ref s) => visitor.visit_static_assert(&s.node, &s.span),
         BlockItem::Statement(ref s) => visitor.visit_statement(&s.node, &s.span),
+        BlockItem::LabelDeclaration(ref l) => {
+            for label in l {
+                visitor.visit_identifier(&label.node, &label.span);
+            }
+        }
+        BlockItem::FunctionDefinition(ref f) => visitor.visit_function_definition(&f.node, &f.span),
+        BlockItem::Pragma(ref p) => visitor.visit_pragma(&p.node, &p.span),
+        BlockItem::Error => {}
     }
 }
 
@@ -1501,6 +1748,9 @@ pub fn visit_external_declaration<'ast, V: Visit<'ast> + ?Sized>(
         ExternalDeclaration::FunctionDefinition(ref f) => {
             visitor.visit_function_definition(&f.node, &f.span)
         }
+        ExternalDeclaration::Asm(ref a) => visitor.visit_asm_statement(&a.node, &a.span),
+        ExternalDeclaration::Pragma(ref p) => visitor.visit_pragma(&p.node, &p.span),
+        ExternalDeclaration::Error => {}
     }
 }
 
@@ -1525,6 +1775,13 @@ pub fn visit_function_definition<'ast, V: Visit<'ast> + ?Sized>(
     );
 }
 
+pub fn visit_pragma<'ast, V: Visit<'ast> + ?Sized>(
+    _visitor: &mut V,
+    _pragma: &'ast Pragma,
+    _span: &'ast Span,
+) {
+}
+
 pub fn visit_extension<'ast, V: Visit<'ast> + ?Sized>(
     visitor: &mut V,
     extension: &'ast Extension,
@@ -1536,6 +1793,9 @@ pub fn visit_extension<'ast, V: Visit<'ast> + ?Sized>(
         Extension::AvailabilityAttribute(ref a) => {
             visitor.visit_availability_attribute(&a.node, &a.span)
         }
+        Extension::StandardAttribute(ref a) => visitor.visit_standard_attribute(&a.node, &a.span),
+        Extension::Declspec(ref a) => visitor.visit_attribute(a, span),
+        Extension::CallingConvention(ref c) => visitor.visit_calling_convention(c, span),
     }
 }
 
@@ -1555,7 +1815,7 @@ pub fn visit_asm_statement<'ast, V: Visit<'ast> + ?Sized>(
     span: &'ast Span,
 ) {
     match *asm_statement {
-        AsmStatement::GnuBasic(ref g) => visitor.visit_string_literal(&g.node, &g.span),
+        AsmStatement::GnuBasic(ref g) => visitor.visit_gnu_basic_asm_statement(g, span),
         AsmStatement::GnuExtended(ref g) => visitor.visit_gnu_extended_asm_statement(g, span),
     }
 }
@@ -1570,13 +1830,27 @@ pub fn visit_availability_attribute<'ast, V: Visit<'ast> + ?Sized>(
     }
 }
 
+pub fn visit_gnu_basic_asm_statement<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    gnu_basic_asm_statement: &'ast GnuBasicAsmStatement,
+    _span: &'ast Span,
+) {
+    for qualifier in &gnu_basic_asm_statement.qualifiers {
+        visitor.visit_asm_qualifier(&qualifier.node, &qualifier.span);
+    }
+    visitor.visit_string_literal(
+        &gnu_basic_asm_statement.template.node,
+        &gnu_basic_asm_statement.template.span,
+    );
+}
+
 pub fn visit_gnu_extended_asm_statement<'ast, V: Visit<'ast> + ?Sized>(
     visitor: &mut V,
     gnu_extended_asm_statement: &'ast GnuExtendedAsmStatement,
     _span: &'ast Span,
 ) {
-    if let Some(ref qualifier) = gnu_extended_asm_statement.qualifier {
-        visitor.visit_type_qualifier(&qualifier.node, &qualifier.span);
+    for qualifier in &gnu_extended_asm_statement.qualifiers {
+        visitor.visit_asm_qualifier(&qualifier.node, &qualifier.span);
     }
     visitor.visit_string_literal(
         &gnu_extended_asm_statement.template.node,
@@ -1591,6 +1865,9 @@ pub fn visit_gnu_extended_asm_statement<'ast, V: Visit<'ast> + ?Sized>(
     for clobber in &gnu_extended_asm_statement.clobbers {
         visitor.visit_string_literal(&clobber.node, &clobber.span);
     }
+    for label in &gnu_extended_asm_statement.labels {
+        visitor.visit_identifier(&label.node, &label.span);
+    }
 }
 
 pub fn visit_gnu_asm_operand<'ast, V: Visit<'ast> + ?Sized>(
@@ -1621,3 +1898,2028 @@ pub fn visit_type_of<'ast, V: Visit<'ast> + ?Sized>(
         TypeOf::Type(ref t) => visitor.visit_type_name(&t.node, &t.span),
     }
 }
+
+pub trait VisitMut {
+    fn visit_identifier_mut(&mut self, identifier: &mut Identifier, span: &mut Span) {
+        visit_identifier_mut(self, identifier, span)
+    }
+
+    fn visit_constant_mut(&mut self, constant: &mut Constant, span: &mut Span) {
+        visit_constant_mut(self, constant, span)
+    }
+
+    fn visit_integer_mut(&mut self, integer: &mut Integer, span: &mut Span) {
+        visit_integer_mut(self, integer, span)
+    }
+
+    fn visit_integer_base_mut(&mut self, integer_base: &mut IntegerBase, span: &mut Span) {
+        visit_integer_base_mut(self, integer_base, span)
+    }
+
+    fn visit_integer_suffix_mut(&mut self, integer_suffix: &mut IntegerSuffix, span: &mut Span) {
+        visit_integer_suffix_mut(self, integer_suffix, span)
+    }
+
+    fn visit_integer_size_mut(&mut self, integer_size: &mut IntegerSize, span: &mut Span) {
+        visit_integer_size_mut(self, integer_size, span)
+    }
+
+    fn visit_float_mut(&mut self, float: &mut Float, span: &mut Span) {
+        visit_float_mut(self, float, span)
+    }
+
+    fn visit_float_base_mut(&mut self, float_base: &mut FloatBase, span: &mut Span) {
+        visit_float_base_mut(self, float_base, span)
+    }
+
+    fn visit_float_suffix_mut(&mut self, float_suffix: &mut FloatSuffix, span: &mut Span) {
+        visit_float_suffix_mut(self, float_suffix, span)
+    }
+
+    fn visit_float_format_mut(&mut self, float_format: &mut FloatFormat, span: &mut Span) {
+        visit_float_format_mut(self, float_format, span)
+    }
+
+    fn visit_string_literal_mut(&mut self, string_literal: &mut StringLiteral, span: &mut Span) {
+        visit_string_literal_mut(self, string_literal, span)
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression, span: &mut Span) {
+        visit_expression_mut(self, expression, span)
+    }
+
+    fn visit_member_operator_mut(&mut self, member_operator: &mut MemberOperator, span: &mut Span) {
+        visit_member_operator_mut(self, member_operator, span)
+    }
+
+    fn visit_generic_selection_mut(
+        &mut self,
+        generic_selection: &mut GenericSelection,
+        span: &mut Span,
+    ) {
+        visit_generic_selection_mut(self, generic_selection, span)
+    }
+
+    fn visit_generic_association_mut(
+        &mut self,
+        generic_association: &mut GenericAssociation,
+        span: &mut Span,
+    ) {
+        visit_generic_association_mut(self, generic_association, span)
+    }
+
+    fn visit_generic_association_type_mut(
+        &mut self,
+        generic_association_type: &mut GenericAssociationType,
+        span: &mut Span,
+    ) {
+        visit_generic_association_type_mut(self, generic_association_type, span)
+    }
+
+    fn visit_member_expression_mut(
+        &mut self,
+        member_expression: &mut MemberExpression,
+        span: &mut Span,
+    ) {
+        visit_member_expression_mut(self, member_expression, span)
+    }
+
+    fn visit_call_expression_mut(&mut self, call_expression: &mut CallExpression, span: &mut Span) {
+        visit_call_expression_mut(self, call_expression, span)
+    }
+
+    fn visit_compound_literal_mut(
+        &mut self,
+        compound_literal: &mut CompoundLiteral,
+        span: &mut Span,
+    ) {
+        visit_compound_literal_mut(self, compound_literal, span)
+    }
+
+    fn visit_sizeofty_mut(&mut self, sizeofty: &mut SizeOfTy, span: &mut Span) {
+        visit_sizeofty_mut(self, sizeofty, span)
+    }
+    fn visit_sizeofval_mut(&mut self, sizeofval: &mut SizeOfVal, span: &mut Span) {
+        visit_sizeofval_mut(self, sizeofval, span)
+    }
+
+    fn visit_alignof_mut(&mut self, alignofty: &mut AlignOf, span: &mut Span) {
+        visit_alignof_mut(self, alignofty, span)
+    }
+
+    fn visit_unary_operator_mut(&mut self, unary_operator: &mut UnaryOperator, span: &mut Span) {
+        visit_unary_operator_mut(self, unary_operator, span)
+    }
+
+    fn visit_unary_operator_expression_mut(
+        &mut self,
+        unary_operator_expression: &mut UnaryOperatorExpression,
+        span: &mut Span,
+    ) {
+        visit_unary_operator_expression_mut(self, unary_operator_expression, span)
+    }
+
+    fn visit_cast_expression_mut(&mut self, cast_expression: &mut CastExpression, span: &mut Span) {
+        visit_cast_expression_mut(self, cast_expression, span)
+    }
+
+    fn visit_binary_operator_mut(&mut self, binary_operator: &mut BinaryOperator, span: &mut Span) {
+        visit_binary_operator_mut(self, binary_operator, span)
+    }
+
+    fn visit_binary_operator_expression_mut(
+        &mut self,
+        binary_operator_expression: &mut BinaryOperatorExpression,
+        span: &mut Span,
+    ) {
+        visit_binary_operator_expression_mut(self, binary_operator_expression, span)
+    }
+
+    fn visit_conditional_expression_mut(
+        &mut self,
+        conditional_expression: &mut ConditionalExpression,
+        span: &mut Span,
+    ) {
+        visit_conditional_expression_mut(self, conditional_expression, span)
+    }
+
+    fn visit_va_arg_expression_mut(
+        &mut self,
+        va_arg_expression: &mut VaArgExpression,
+        span: &mut Span,
+    ) {
+        visit_va_arg_expression_mut(self, va_arg_expression, span)
+    }
+
+    fn visit_types_compatible_expression_mut(
+        &mut self,
+        types_compatible_expression: &mut TypesCompatibleExpression,
+        span: &mut Span,
+    ) {
+        visit_types_compatible_expression_mut(self, types_compatible_expression, span)
+    }
+
+    fn visit_choose_expression_mut(
+        &mut self,
+        choose_expression: &mut ChooseExpression,
+        span: &mut Span,
+    ) {
+        visit_choose_expression_mut(self, choose_expression, span)
+    }
+
+    fn visit_convert_vector_expression_mut(
+        &mut self,
+        convert_vector_expression: &mut ConvertVectorExpression,
+        span: &mut Span,
+    ) {
+        visit_convert_vector_expression_mut(self, convert_vector_expression, span)
+    }
+
+    fn visit_shuffle_vector_expression_mut(
+        &mut self,
+        shuffle_vector_expression: &mut ShuffleVectorExpression,
+        span: &mut Span,
+    ) {
+        visit_shuffle_vector_expression_mut(self, shuffle_vector_expression, span)
+    }
+
+    fn visit_offset_of_expression_mut(
+        &mut self,
+        offset_of_expression: &mut OffsetOfExpression,
+        span: &mut Span,
+    ) {
+        visit_offset_of_expression_mut(self, offset_of_expression, span)
+    }
+
+    fn visit_offset_designator_mut(
+        &mut self,
+        offset_designator: &mut OffsetDesignator,
+        span: &mut Span,
+    ) {
+        visit_offset_designator_mut(self, offset_designator, span)
+    }
+
+    fn visit_offset_member_mut(&mut self, offset_member: &mut OffsetMember, span: &mut Span) {
+        visit_offset_member_mut(self, offset_member, span)
+    }
+
+    fn visit_declaration_mut(&mut self, declaration: &mut Declaration, span: &mut Span) {
+        visit_declaration_mut(self, declaration, span)
+    }
+
+    fn visit_declaration_specifier_mut(
+        &mut self,
+        declaration_specifier: &mut DeclarationSpecifier,
+        span: &mut Span,
+    ) {
+        visit_declaration_specifier_mut(self, declaration_specifier, span)
+    }
+
+    fn visit_init_declarator_mut(&mut self, init_declarator: &mut InitDeclarator, span: &mut Span) {
+        visit_init_declarator_mut(self, init_declarator, span)
+    }
+
+    fn visit_storage_class_specifier_mut(
+        &mut self,
+        storage_class_specifier: &mut StorageClassSpecifier,
+        span: &mut Span,
+    ) {
+        visit_storage_class_specifier_mut(self, storage_class_specifier, span)
+    }
+
+    fn visit_type_specifier_mut(&mut self, type_specifier: &mut TypeSpecifier, span: &mut Span) {
+        visit_type_specifier_mut(self, type_specifier, span)
+    }
+
+    fn visit_ts18661_float_type_mut(
+        &mut self,
+        ts18661_float_type: &mut TS18661FloatType,
+        span: &mut Span,
+    ) {
+        visit_ts18661_float_type_mut(self, ts18661_float_type, span)
+    }
+
+    fn visit_ts18661_float_format_mut(
+        &mut self,
+        ts18661_float_format: &mut TS18661FloatFormat,
+        span: &mut Span,
+    ) {
+        visit_ts18661_float_format_mut(self, ts18661_float_format, span)
+    }
+
+    fn visit_struct_type_mut(&mut self, struct_type: &mut StructType, span: &mut Span) {
+        visit_struct_type_mut(self, struct_type, span)
+    }
+
+    fn visit_struct_kind_mut(&mut self, struct_kind: &mut StructKind, span: &mut Span) {
+        visit_struct_kind_mut(self, struct_kind, span)
+    }
+
+    fn visit_struct_declaration_mut(
+        &mut self,
+        struct_declaration: &mut StructDeclaration,
+        span: &mut Span,
+    ) {
+        visit_struct_declaration_mut(self, struct_declaration, span)
+    }
+
+    fn visit_struct_field_mut(&mut self, struct_field: &mut StructField, span: &mut Span) {
+        visit_struct_field_mut(self, struct_field, span)
+    }
+
+    fn visit_specifier_qualifier_mut(
+        &mut self,
+        specifier_qualifier: &mut SpecifierQualifier,
+        span: &mut Span,
+    ) {
+        visit_specifier_qualifier_mut(self, specifier_qualifier, span)
+    }
+
+    fn visit_struct_declarator_mut(
+        &mut self,
+        struct_declarator: &mut StructDeclarator,
+        span: &mut Span,
+    ) {
+        visit_struct_declarator_mut(self, struct_declarator, span)
+    }
+
+    fn visit_enum_type_mut(&mut self, enum_type: &mut EnumType, span: &mut Span) {
+        visit_enum_type_mut(self, enum_type, span)
+    }
+
+    fn visit_enumerator_mut(&mut self, enumerator: &mut Enumerator, span: &mut Span) {
+        visit_enumerator_mut(self, enumerator, span)
+    }
+
+    fn visit_type_qualifier_mut(&mut self, type_qualifier: &mut TypeQualifier, span: &mut Span) {
+        visit_type_qualifier_mut(self, type_qualifier, span)
+    }
+
+    fn visit_function_specifier_mut(
+        &mut self,
+        function_specifier: &mut FunctionSpecifier,
+        span: &mut Span,
+    ) {
+        visit_function_specifier_mut(self, function_specifier, span)
+    }
+
+    fn visit_alignment_specifier_mut(
+        &mut self,
+        alignment_specifier: &mut AlignmentSpecifier,
+        span: &mut Span,
+    ) {
+        visit_alignment_specifier_mut(self, alignment_specifier, span)
+    }
+
+    fn visit_declarator_mut(&mut self, declarator: &mut Declarator, span: &mut Span) {
+        visit_declarator_mut(self, declarator, span)
+    }
+
+    fn visit_declarator_kind_mut(&mut self, declarator_kind: &mut DeclaratorKind, span: &mut Span) {
+        visit_declarator_kind_mut(self, declarator_kind, span)
+    }
+
+    fn visit_derived_declarator_mut(
+        &mut self,
+        derived_declarator: &mut DerivedDeclarator,
+        span: &mut Span,
+    ) {
+        visit_derived_declarator_mut(self, derived_declarator, span)
+    }
+
+    fn visit_array_declarator_mut(
+        &mut self,
+        array_declarator: &mut ArrayDeclarator,
+        span: &mut Span,
+    ) {
+        visit_array_declarator_mut(self, array_declarator, span)
+    }
+
+    fn visit_function_declarator_mut(
+        &mut self,
+        function_declarator: &mut FunctionDeclarator,
+        span: &mut Span,
+    ) {
+        visit_function_declarator_mut(self, function_declarator, span)
+    }
+
+    fn visit_pointer_qualifier_mut(
+        &mut self,
+        pointer_qualifier: &mut PointerQualifier,
+        span: &mut Span,
+    ) {
+        visit_pointer_qualifier_mut(self, pointer_qualifier, span)
+    }
+
+    fn visit_array_size_mut(&mut self, array_size: &mut ArraySize, span: &mut Span) {
+        visit_array_size_mut(self, array_size, span)
+    }
+
+    fn visit_parameter_declaration_mut(
+        &mut self,
+        parameter_declaration: &mut ParameterDeclaration,
+        span: &mut Span,
+    ) {
+        visit_parameter_declaration_mut(self, parameter_declaration, span)
+    }
+
+    fn visit_ellipsis_mut(&mut self, ellipsis: &mut Ellipsis, span: &mut Span) {
+        visit_ellipsis_mut(self, ellipsis, span)
+    }
+
+    fn visit_type_name_mut(&mut self, type_name: &mut TypeName, span: &mut Span) {
+        visit_type_name_mut(self, type_name, span)
+    }
+
+    fn visit_initializer_mut(&mut self, initializer: &mut Initializer, span: &mut Span) {
+        visit_initializer_mut(self, initializer, span)
+    }
+
+    fn visit_initializer_list_item_mut(
+        &mut self,
+        initializer_list_item: &mut InitializerListItem,
+        span: &mut Span,
+    ) {
+        visit_initializer_list_item_mut(self, initializer_list_item, span)
+    }
+
+    fn visit_designator_mut(&mut self, designator: &mut Designator, span: &mut Span) {
+        visit_designator_mut(self, designator, span)
+    }
+
+    fn visit_range_designator_mut(
+        &mut self,
+        range_designator: &mut RangeDesignator,
+        span: &mut Span,
+    ) {
+        visit_range_designator_mut(self, range_designator, span)
+    }
+
+    fn visit_static_assert_mut(&mut self, static_assert: &mut StaticAssert, span: &mut Span) {
+        visit_static_assert_mut(self, static_assert, span)
+    }
+
+    fn visit_statement_mut(&mut self, statement: &mut Statement, span: &mut Span) {
+        visit_statement_mut(self, statement, span)
+    }
+
+    fn visit_labeled_statement_mut(
+        &mut self,
+        labeled_statement: &mut LabeledStatement,
+        span: &mut Span,
+    ) {
+        visit_labeled_statement_mut(self, labeled_statement, span)
+    }
+
+    fn visit_attributed_statement_mut(
+        &mut self,
+        attributed_statement: &mut AttributedStatement,
+        span: &mut Span,
+    ) {
+        visit_attributed_statement_mut(self, attributed_statement, span)
+    }
+
+    fn visit_try_statement_mut(&mut self, try_statement: &mut TryStatement, span: &mut Span) {
+        visit_try_statement_mut(self, try_statement, span)
+    }
+
+    fn visit_try_handler_mut(&mut self, try_handler: &mut TryHandler, span: &mut Span) {
+        visit_try_handler_mut(self, try_handler, span)
+    }
+
+    fn visit_except_handler_mut(&mut self, except_handler: &mut ExceptHandler, span: &mut Span) {
+        visit_except_handler_mut(self, except_handler, span)
+    }
+
+    fn visit_if_statement_mut(&mut self, if_statement: &mut IfStatement, span: &mut Span) {
+        visit_if_statement_mut(self, if_statement, span)
+    }
+
+    fn visit_switch_statement_mut(
+        &mut self,
+        switch_statement: &mut SwitchStatement,
+        span: &mut Span,
+    ) {
+        visit_switch_statement_mut(self, switch_statement, span)
+    }
+
+    fn visit_while_statement_mut(&mut self, while_statement: &mut WhileStatement, span: &mut Span) {
+        visit_while_statement_mut(self, while_statement, span)
+    }
+
+    fn visit_do_while_statement_mut(
+        &mut self,
+        do_while_statement: &mut DoWhileStatement,
+        span: &mut Span,
+    ) {
+        visit_do_while_statement_mut(self, do_while_statement, span)
+    }
+
+    fn visit_for_statement_mut(&mut self, for_statement: &mut ForStatement, span: &mut Span) {
+        visit_for_statement_mut(self, for_statement, span)
+    }
+
+    fn visit_label_mut(&mut self, label: &mut Label, span: &mut Span) {
+        visit_label_mut(self, label, span)
+    }
+
+    fn visit_case_range_mut(&mut self, range: &mut CaseRange, span: &mut Span) {
+        visit_case_range_mut(self, range, span)
+    }
+
+    fn visit_for_initializer_mut(&mut self, for_initializer: &mut ForInitializer, span: &mut Span) {
+        visit_for_initializer_mut(self, for_initializer, span)
+    }
+
+    fn visit_block_item_mut(&mut self, block_item: &mut BlockItem, span: &mut Span) {
+        visit_block_item_mut(self, block_item, span)
+    }
+
+    fn visit_translation_unit_mut(&mut self, translation_unit: &mut TranslationUnit) {
+        visit_translation_unit_mut(self, translation_unit)
+    }
+
+    fn visit_external_declaration_mut(
+        &mut self,
+        external_declaration: &mut ExternalDeclaration,
+        span: &mut Span,
+    ) {
+        visit_external_declaration_mut(self, external_declaration, span)
+    }
+
+    fn visit_function_definition_mut(
+        &mut self,
+        function_definition: &mut FunctionDefinition,
+        span: &mut Span,
+    ) {
+        visit_function_definition_mut(self, function_definition, span)
+    }
+
+    fn visit_pragma_mut(&mut self, pragma: &mut Pragma, span: &mut Span) {
+        visit_pragma_mut(self, pragma, span)
+    }
+
+    fn visit_extension_mut(&mut self, extension: &mut Extension, span: &mut Span) {
+        visit_extension_mut(self, extension, span)
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut Attribute, span: &mut Span) {
+        visit_attribute_mut(self, attribute, span)
+    }
+
+    fn visit_asm_statement_mut(&mut self, asm_statement: &mut AsmStatement, span: &mut Span) {
+        visit_asm_statement_mut(self, asm_statement, span)
+    }
+
+    fn visit_availability_attribute_mut(
+        &mut self,
+        availability: &mut AvailabilityAttribute,
+        span: &mut Span,
+    ) {
+        visit_availability_attribute_mut(self, availability, span)
+    }
+
+    fn visit_availability_clause_mut(
+        &mut self,
+        _clause: &mut AvailabilityClause,
+        _span: &mut Span,
+    ) {
+    }
+
+    fn visit_standard_attribute_mut(
+        &mut self,
+        _standard_attribute: &mut StandardAttribute,
+        _span: &mut Span,
+    ) {
+    }
+
+    fn visit_calling_convention_mut(
+        &mut self,
+        _calling_convention: &mut CallingConvention,
+        _span: &mut Span,
+    ) {
+    }
+
+    fn visit_gnu_basic_asm_statement_mut(
+        &mut self,
+        gnu_basic_asm_statement: &mut GnuBasicAsmStatement,
+        span: &mut Span,
+    ) {
+        visit_gnu_basic_asm_statement_mut(self, gnu_basic_asm_statement, span)
+    }
+
+    fn visit_gnu_extended_asm_statement_mut(
+        &mut self,
+        gnu_extended_asm_statement: &mut GnuExtendedAsmStatement,
+        span: &mut Span,
+    ) {
+        visit_gnu_extended_asm_statement_mut(self, gnu_extended_asm_statement, span)
+    }
+
+    fn visit_asm_qualifier_mut(&mut self, _asm_qualifier: &mut AsmQualifier, _span: &mut Span) {}
+
+    fn visit_gnu_asm_operand_mut(&mut self, gnu_asm_operand: &mut GnuAsmOperand, span: &mut Span) {
+        visit_gnu_asm_operand_mut(self, gnu_asm_operand, span)
+    }
+
+    fn visit_type_of_mut(&mut self, type_of: &mut TypeOf, span: &mut Span) {
+        visit_type_of_mut(self, type_of, span)
+    }
+}
+
+pub fn visit_identifier_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _identifier: &mut Identifier,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_constant_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    constant: &mut Constant,
+    span: &mut Span,
+) {
+    match *constant {
+        Constant::Integer(ref mut i) => visitor.visit_integer_mut(i, span),
+        Constant::Float(ref mut f) => visitor.visit_float_mut(f, span),
+        Constant::Character(_) => {}
+        Constant::Bool(_) => {}
+        Constant::Nullptr => {}
+    }
+}
+
+pub fn visit_integer_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    integer: &mut Integer,
+    span: &mut Span,
+) {
+    visitor.visit_integer_base_mut(&mut integer.base, span);
+    visitor.visit_integer_suffix_mut(&mut integer.suffix, span);
+}
+
+pub fn visit_integer_base_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _integer_base: &mut IntegerBase,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_integer_suffix_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    integer_suffix: &mut IntegerSuffix,
+    span: &mut Span,
+) {
+    visitor.visit_integer_size_mut(&mut integer_suffix.size, span);
+}
+
+pub fn visit_integer_size_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _integer_size: &mut IntegerSize,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_float_mut<V: VisitMut + ?Sized>(visitor: &mut V, float: &mut Float, span: &mut Span) {
+    visitor.visit_float_base_mut(&mut float.base, span);
+    visitor.visit_float_suffix_mut(&mut float.suffix, span);
+}
+
+pub fn visit_float_base_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _float_base: &mut FloatBase,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_float_suffix_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    float_suffix: &mut FloatSuffix,
+    span: &mut Span,
+) {
+    visitor.visit_float_format_mut(&mut float_suffix.format, span);
+}
+
+pub fn visit_float_format_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    float_format: &mut FloatFormat,
+    span: &mut Span,
+) {
+    if let FloatFormat::TS18661Format(ref mut f) = *float_format {
+        visitor.visit_ts18661_float_type_mut(f, span);
+    }
+}
+
+pub fn visit_string_literal_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _string_literal: &mut StringLiteral,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut Expression,
+    _span: &mut Span,
+) {
+    match *expression {
+        Expression::Identifier(ref mut i) => visitor.visit_identifier_mut(&mut i.node, &mut i.span),
+        Expression::Constant(ref mut c) => visitor.visit_constant_mut(&mut c.node, &mut c.span),
+        Expression::StringLiteral(ref mut s) => {
+            visitor.visit_string_literal_mut(&mut s.node, &mut s.span)
+        }
+        Expression::GenericSelection(ref mut g) => {
+            visitor.visit_generic_selection_mut(&mut g.node, &mut g.span)
+        }
+        Expression::Member(ref mut m) => {
+            visitor.visit_member_expression_mut(&mut m.node, &mut m.span)
+        }
+        Expression::Call(ref mut c) => visitor.visit_call_expression_mut(&mut c.node, &mut c.span),
+        Expression::CompoundLiteral(ref mut c) => {
+            visitor.visit_compound_literal_mut(&mut c.node, &mut c.span)
+        }
+        Expression::SizeOfTy(ref mut s) => visitor.visit_sizeofty_mut(&mut s.node, &mut s.span),
+        Expression::SizeOfVal(ref mut s) => visitor.visit_sizeofval_mut(&mut s.node, &mut s.span),
+        Expression::AlignOf(ref mut a) => visitor.visit_alignof_mut(&mut a.node, &mut a.span),
+        Expression::UnaryOperator(ref mut u) => {
+            visitor.visit_unary_operator_expression_mut(&mut u.node, &mut u.span)
+        }
+        Expression::Cast(ref mut c) => visitor.visit_cast_expression_mut(&mut c.node, &mut c.span),
+        Expression::BinaryOperator(ref mut b) => {
+            visitor.visit_binary_operator_expression_mut(&mut b.node, &mut b.span)
+        }
+        Expression::Conditional(ref mut c) => {
+            visitor.visit_conditional_expression_mut(&mut c.node, &mut c.span)
+        }
+        Expression::Comma(ref mut comma) => {
+            for c in comma.iter_mut() {
+                visitor.visit_expression_mut(&mut c.node, &mut c.span);
+            }
+        }
+        Expression::OffsetOf(ref mut o) => {
+            visitor.visit_offset_of_expression_mut(&mut o.node, &mut o.span)
+        }
+        Expression::VaArg(ref mut v) => {
+            visitor.visit_va_arg_expression_mut(&mut v.node, &mut v.span)
+        }
+        Expression::Statement(ref mut s) => visitor.visit_statement_mut(&mut s.node, &mut s.span),
+        Expression::LabelAddress(ref mut l) => {
+            visitor.visit_identifier_mut(&mut l.node, &mut l.span)
+        }
+        Expression::TypesCompatible(ref mut t) => {
+            visitor.visit_types_compatible_expression_mut(&mut t.node, &mut t.span)
+        }
+        Expression::ChooseExpr(ref mut c) => {
+            visitor.visit_choose_expression_mut(&mut c.node, &mut c.span)
+        }
+        Expression::ConvertVector(ref mut c) => {
+            visitor.visit_convert_vector_expression_mut(&mut c.node, &mut c.span)
+        }
+        Expression::ShuffleVector(ref mut v) => {
+            visitor.visit_shuffle_vector_expression_mut(&mut v.node, &mut v.span)
+        }
+    }
+}
+
+pub fn visit_member_operator_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _member_operator: &mut MemberOperator,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_generic_selection_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    generic_selection: &mut GenericSelection,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut generic_selection.expression.node,
+        &mut generic_selection.expression.span,
+    );
+    for association in &mut generic_selection.associations {
+        visitor.visit_generic_association_mut(&mut association.node, &mut association.span);
+    }
+}
+
+pub fn visit_generic_association_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    generic_association: &mut GenericAssociation,
+    _span: &mut Span,
+) {
+    match *generic_association {
+        GenericAssociation::Type(ref mut t) => {
+            visitor.visit_generic_association_type_mut(&mut t.node, &mut t.span)
+        }
+        GenericAssociation::Default(ref mut d) => {
+            visitor.visit_expression_mut(&mut d.node, &mut d.span)
+        }
+    }
+}
+
+pub fn visit_generic_association_type_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    generic_association_type: &mut GenericAssociationType,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(
+        &mut generic_association_type.type_name.node,
+        &mut generic_association_type.type_name.span,
+    );
+    visitor.visit_expression_mut(
+        &mut generic_association_type.expression.node,
+        &mut generic_association_type.expression.span,
+    );
+}
+
+pub fn visit_member_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    member_expression: &mut MemberExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_member_operator_mut(
+        &mut member_expression.operator.node,
+        &mut member_expression.operator.span,
+    );
+    visitor.visit_expression_mut(
+        &mut member_expression.expression.node,
+        &mut member_expression.expression.span,
+    );
+    visitor.visit_identifier_mut(
+        &mut member_expression.identifier.node,
+        &mut member_expression.identifier.span,
+    );
+}
+
+pub fn visit_call_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    call_expression: &mut CallExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut call_expression.callee.node,
+        &mut call_expression.callee.span,
+    );
+    for argument in &mut call_expression.arguments {
+        visitor.visit_expression_mut(&mut argument.node, &mut argument.span);
+    }
+}
+
+pub fn visit_compound_literal_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    compound_literal: &mut CompoundLiteral,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(
+        &mut compound_literal.type_name.node,
+        &mut compound_literal.type_name.span,
+    );
+    for initializer in &mut compound_literal.initializer_list {
+        visitor.visit_initializer_list_item_mut(&mut initializer.node, &mut initializer.span);
+    }
+}
+
+pub fn visit_sizeofty_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    sizeofty: &mut SizeOfTy,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(&mut sizeofty.0.node, &mut sizeofty.0.span);
+}
+
+pub fn visit_sizeofval_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    sizeofval: &mut SizeOfVal,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(&mut sizeofval.0.node, &mut sizeofval.0.span);
+}
+
+pub fn visit_alignof_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    alignofty: &mut AlignOf,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(&mut alignofty.0.node, &mut alignofty.0.span);
+}
+
+pub fn visit_unary_operator_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _unary_operator: &mut UnaryOperator,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_unary_operator_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    unary_operator_expression: &mut UnaryOperatorExpression,
+    _span: &mut Span,
+) {
+    match unary_operator_expression.operator.node {
+        UnaryOperator::PostIncrement | UnaryOperator::PostDecrement => {
+            visitor.visit_expression_mut(
+                &mut unary_operator_expression.operand.node,
+                &mut unary_operator_expression.operand.span,
+            );
+            visitor.visit_unary_operator_mut(
+                &mut unary_operator_expression.operator.node,
+                &mut unary_operator_expression.operator.span,
+            );
+        }
+        _ => {
+            visitor.visit_unary_operator_mut(
+                &mut unary_operator_expression.operator.node,
+                &mut unary_operator_expression.operator.span,
+            );
+            visitor.visit_expression_mut(
+                &mut unary_operator_expression.operand.node,
+                &mut unary_operator_expression.operand.span,
+            );
+        }
+    }
+}
+
+pub fn visit_cast_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    cast_expression: &mut CastExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(
+        &mut cast_expression.type_name.node,
+        &mut cast_expression.type_name.span,
+    );
+    visitor.visit_expression_mut(
+        &mut cast_expression.expression.node,
+        &mut cast_expression.expression.span,
+    );
+}
+
+pub fn visit_binary_operator_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _binary_operator: &mut BinaryOperator,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_binary_operator_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    binary_operator_expression: &mut BinaryOperatorExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut binary_operator_expression.lhs.node,
+        &mut binary_operator_expression.lhs.span,
+    );
+    visitor.visit_expression_mut(
+        &mut binary_operator_expression.rhs.node,
+        &mut binary_operator_expression.rhs.span,
+    );
+    visitor.visit_binary_operator_mut(
+        &mut binary_operator_expression.operator.node,
+        &mut binary_operator_expression.operator.span,
+    );
+}
+
+pub fn visit_conditional_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    conditional_expression: &mut ConditionalExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut conditional_expression.condition.node,
+        &mut conditional_expression.condition.span,
+    );
+    if let Some(ref mut e) = conditional_expression.then_expression {
+        visitor.visit_expression_mut(&mut e.node, &mut e.span);
+    }
+    visitor.visit_expression_mut(
+        &mut conditional_expression.else_expression.node,
+        &mut conditional_expression.else_expression.span,
+    );
+}
+
+pub fn visit_va_arg_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    va_arg_expression: &mut VaArgExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut va_arg_expression.va_list.node,
+        &mut va_arg_expression.va_list.span,
+    );
+    visitor.visit_type_name_mut(
+        &mut va_arg_expression.type_name.node,
+        &mut va_arg_expression.type_name.span,
+    );
+}
+
+pub fn visit_types_compatible_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    types_compatible_expression: &mut TypesCompatibleExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(
+        &mut types_compatible_expression.type_name_1.node,
+        &mut types_compatible_expression.type_name_1.span,
+    );
+    visitor.visit_type_name_mut(
+        &mut types_compatible_expression.type_name_2.node,
+        &mut types_compatible_expression.type_name_2.span,
+    );
+}
+
+pub fn visit_choose_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    choose_expression: &mut ChooseExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut choose_expression.condition.node,
+        &mut choose_expression.condition.span,
+    );
+    visitor.visit_expression_mut(
+        &mut choose_expression.then_expression.node,
+        &mut choose_expression.then_expression.span,
+    );
+    visitor.visit_expression_mut(
+        &mut choose_expression.else_expression.node,
+        &mut choose_expression.else_expression.span,
+    );
+}
+
+pub fn visit_convert_vector_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    convert_vector_expression: &mut ConvertVectorExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut convert_vector_expression.vector.node,
+        &mut convert_vector_expression.vector.span,
+    );
+    visitor.visit_type_name_mut(
+        &mut convert_vector_expression.type_name.node,
+        &mut convert_vector_expression.type_name.span,
+    );
+}
+
+pub fn visit_shuffle_vector_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    shuffle_vector_expression: &mut ShuffleVectorExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut shuffle_vector_expression.vector_1.node,
+        &mut shuffle_vector_expression.vector_1.span,
+    );
+    visitor.visit_expression_mut(
+        &mut shuffle_vector_expression.vector_2.node,
+        &mut shuffle_vector_expression.vector_2.span,
+    );
+    for index in &mut shuffle_vector_expression.indices {
+        visitor.visit_expression_mut(&mut index.node, &mut index.span);
+    }
+}
+
+pub fn visit_offset_of_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    offset_of_expression: &mut OffsetOfExpression,
+    _span: &mut Span,
+) {
+    visitor.visit_type_name_mut(
+        &mut offset_of_expression.type_name.node,
+        &mut offset_of_expression.type_name.span,
+    );
+    visitor.visit_offset_designator_mut(
+        &mut offset_of_expression.designator.node,
+        &mut offset_of_expression.designator.span,
+    );
+}
+
+pub fn visit_offset_designator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    offset_designator: &mut OffsetDesignator,
+    _span: &mut Span,
+) {
+    visitor.visit_identifier_mut(
+        &mut offset_designator.base.node,
+        &mut offset_designator.base.span,
+    );
+    for member in &mut offset_designator.members {
+        visitor.visit_offset_member_mut(&mut member.node, &mut member.span);
+    }
+}
+
+pub fn visit_offset_member_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    offset_member: &mut OffsetMember,
+    _span: &mut Span,
+) {
+    match *offset_member {
+        OffsetMember::Member(ref mut m) => visitor.visit_identifier_mut(&mut m.node, &mut m.span),
+        OffsetMember::IndirectMember(ref mut m) => {
+            visitor.visit_identifier_mut(&mut m.node, &mut m.span)
+        }
+        OffsetMember::Index(ref mut i) => visitor.visit_expression_mut(&mut i.node, &mut i.span),
+    }
+}
+
+pub fn visit_declaration_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut Declaration,
+    _span: &mut Span,
+) {
+    for specifier in &mut declaration.specifiers {
+        visitor.visit_declaration_specifier_mut(&mut specifier.node, &mut specifier.span);
+    }
+
+    for declarator in &mut declaration.declarators {
+        visitor.visit_init_declarator_mut(&mut declarator.node, &mut declarator.span);
+    }
+}
+
+pub fn visit_declaration_specifier_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    declaration_specifier: &mut DeclarationSpecifier,
+    _span: &mut Span,
+) {
+    match *declaration_specifier {
+        DeclarationSpecifier::StorageClass(ref mut s) => {
+            visitor.visit_storage_class_specifier_mut(&mut s.node, &mut s.span)
+        }
+        DeclarationSpecifier::TypeSpecifier(ref mut t) => {
+            visitor.visit_type_specifier_mut(&mut t.node, &mut t.span)
+        }
+        DeclarationSpecifier::TypeQualifier(ref mut t) => {
+            visitor.visit_type_qualifier_mut(&mut t.node, &mut t.span)
+        }
+        DeclarationSpecifier::Function(ref mut f) => {
+            visitor.visit_function_specifier_mut(&mut f.node, &mut f.span)
+        }
+        DeclarationSpecifier::Alignment(ref mut a) => {
+            visitor.visit_alignment_specifier_mut(&mut a.node, &mut a.span)
+        }
+        DeclarationSpecifier::Extension(ref mut e) => {
+            for extension in e {
+                visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+            }
+        }
+    }
+}
+
+pub fn visit_init_declarator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    init_declarator: &mut InitDeclarator,
+    _span: &mut Span,
+) {
+    visitor.visit_declarator_mut(
+        &mut init_declarator.declarator.node,
+        &mut init_declarator.declarator.span,
+    );
+    if let Some(ref mut initializer) = init_declarator.initializer {
+        visitor.visit_initializer_mut(&mut initializer.node, &mut initializer.span);
+    }
+}
+
+pub fn visit_storage_class_specifier_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _storage_class_specifier: &mut StorageClassSpecifier,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_type_specifier_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    type_specifier: &mut TypeSpecifier,
+    span: &mut Span,
+) {
+    match *type_specifier {
+        TypeSpecifier::Atomic(ref mut a) => visitor.visit_type_name_mut(&mut a.node, &mut a.span),
+        TypeSpecifier::BitInt(ref mut e) => visitor.visit_expression_mut(&mut e.node, &mut e.span),
+        TypeSpecifier::Struct(ref mut s) => visitor.visit_struct_type_mut(&mut s.node, &mut s.span),
+        TypeSpecifier::Enum(ref mut e) => visitor.visit_enum_type_mut(&mut e.node, &mut e.span),
+        TypeSpecifier::TypedefName(ref mut t) => {
+            visitor.visit_identifier_mut(&mut t.node, &mut t.span)
+        }
+        TypeSpecifier::TypeOf(ref mut t) => visitor.visit_type_of_mut(&mut t.node, &mut t.span),
+        TypeSpecifier::TypeOfUnqual(ref mut t) => {
+            visitor.visit_type_of_mut(&mut t.node, &mut t.span)
+        }
+        TypeSpecifier::TS18661Float(ref mut t) => visitor.visit_ts18661_float_type_mut(t, span),
+        _ => {}
+    }
+}
+
+pub fn visit_ts18661_float_type_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ts18661_float_type: &mut TS18661FloatType,
+    span: &mut Span,
+) {
+    visitor.visit_ts18661_float_format_mut(&mut ts18661_float_type.format, span);
+}
+
+pub fn visit_ts18661_float_format_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _ts18661_float_format: &mut TS18661FloatFormat,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_struct_type_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    struct_type: &mut StructType,
+    _span: &mut Span,
+) {
+    visitor.visit_struct_kind_mut(&mut struct_type.kind.node, &mut struct_type.kind.span);
+    for extension in &mut struct_type.extensions {
+        visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+    }
+    if let Some(ref mut identifier) = struct_type.identifier {
+        visitor.visit_identifier_mut(&mut identifier.node, &mut identifier.span);
+    }
+    if let Some(ref mut declarations) = struct_type.declarations {
+        for declaration in declarations {
+            visitor.visit_struct_declaration_mut(&mut declaration.node, &mut declaration.span);
+        }
+    }
+}
+
+pub fn visit_struct_kind_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _struct_kind: &mut StructKind,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_struct_declaration_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    struct_declaration: &mut StructDeclaration,
+    _span: &mut Span,
+) {
+    match *struct_declaration {
+        StructDeclaration::Field(ref mut f) => {
+            visitor.visit_struct_field_mut(&mut f.node, &mut f.span)
+        }
+        StructDeclaration::StaticAssert(ref mut s) => {
+            visitor.visit_static_assert_mut(&mut s.node, &mut s.span)
+        }
+        StructDeclaration::Pragma(ref mut p) => visitor.visit_pragma_mut(&mut p.node, &mut p.span),
+    }
+}
+
+pub fn visit_struct_field_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    struct_field: &mut StructField,
+    _span: &mut Span,
+) {
+    for specifier in &mut struct_field.specifiers {
+        visitor.visit_specifier_qualifier_mut(&mut specifier.node, &mut specifier.span);
+    }
+    for declarator in &mut struct_field.declarators {
+        visitor.visit_struct_declarator_mut(&mut declarator.node, &mut declarator.span);
+    }
+}
+
+pub fn visit_specifier_qualifier_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    specifier_qualifier: &mut SpecifierQualifier,
+    _span: &mut Span,
+) {
+    match *specifier_qualifier {
+        SpecifierQualifier::TypeSpecifier(ref mut t) => {
+            visitor.visit_type_specifier_mut(&mut t.node, &mut t.span)
+        }
+        SpecifierQualifier::TypeQualifier(ref mut t) => {
+            visitor.visit_type_qualifier_mut(&mut t.node, &mut t.span)
+        }
+        SpecifierQualifier::Alignment(ref mut a) => {
+            visitor.visit_alignment_specifier_mut(&mut a.node, &mut a.span)
+        }
+        SpecifierQualifier::Extension(ref mut e) => {
+            for n in e {
+                visitor.visit_extension_mut(&mut n.node, &mut n.span);
+            }
+        }
+    }
+}
+
+pub fn visit_struct_declarator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    struct_declarator: &mut StructDeclarator,
+    _span: &mut Span,
+) {
+    if let Some(ref mut declarator) = struct_declarator.declarator {
+        visitor.visit_declarator_mut(&mut declarator.node, &mut declarator.span);
+    }
+    if let Some(ref mut bit_width) = struct_declarator.bit_width {
+        visitor.visit_expression_mut(&mut bit_width.node, &mut bit_width.span);
+    }
+}
+
+pub fn visit_enum_type_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    enum_type: &mut EnumType,
+    _span: &mut Span,
+) {
+    if let Some(ref mut identifier) = enum_type.identifier {
+        visitor.visit_identifier_mut(&mut identifier.node, &mut identifier.span);
+    }
+    for enumerator in &mut enum_type.enumerators {
+        visitor.visit_enumerator_mut(&mut enumerator.node, &mut enumerator.span);
+    }
+}
+
+pub fn visit_enumerator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    enumerator: &mut Enumerator,
+    _span: &mut Span,
+) {
+    visitor.visit_identifier_mut(
+        &mut enumerator.identifier.node,
+        &mut enumerator.identifier.span,
+    );
+    if let Some(ref mut expression) = enumerator.expression {
+        visitor.visit_expression_mut(&mut expression.node, &mut expression.span);
+    }
+    for extension in &mut enumerator.extensions {
+        visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+    }
+}
+
+pub fn visit_type_qualifier_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _type_qualifier: &mut TypeQualifier,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_function_specifier_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _function_specifier: &mut FunctionSpecifier,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_alignment_specifier_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    alignment_specifier: &mut AlignmentSpecifier,
+    _span: &mut Span,
+) {
+    match *alignment_specifier {
+        AlignmentSpecifier::Type(ref mut t) => {
+            visitor.visit_type_name_mut(&mut t.node, &mut t.span)
+        }
+        AlignmentSpecifier::Constant(ref mut c) => {
+            visitor.visit_expression_mut(&mut c.node, &mut c.span)
+        }
+    }
+}
+
+pub fn visit_declarator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    declarator: &mut Declarator,
+    _span: &mut Span,
+) {
+    visitor.visit_declarator_kind_mut(&mut declarator.kind.node, &mut declarator.kind.span);
+    for derived in &mut declarator.derived {
+        visitor.visit_derived_declarator_mut(&mut derived.node, &mut derived.span);
+    }
+    for extension in &mut declarator.extensions {
+        visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+    }
+}
+
+pub fn visit_declarator_kind_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    declarator_kind: &mut DeclaratorKind,
+    _span: &mut Span,
+) {
+    match *declarator_kind {
+        DeclaratorKind::Identifier(ref mut i) => {
+            visitor.visit_identifier_mut(&mut i.node, &mut i.span)
+        }
+        DeclaratorKind::Declarator(ref mut d) => {
+            visitor.visit_declarator_mut(&mut d.node, &mut d.span)
+        }
+        _ => {}
+    }
+}
+
+pub fn visit_derived_declarator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    derived_declarator: &mut DerivedDeclarator,
+    _span: &mut Span,
+) {
+    match *derived_declarator {
+        DerivedDeclarator::Pointer(ref mut p) => {
+            for pointer in p {
+                visitor.visit_pointer_qualifier_mut(&mut pointer.node, &mut pointer.span);
+            }
+        }
+        DerivedDeclarator::Array(ref mut a) => {
+            visitor.visit_array_declarator_mut(&mut a.node, &mut a.span)
+        }
+        DerivedDeclarator::Function(ref mut f) => {
+            visitor.visit_function_declarator_mut(&mut f.node, &mut f.span)
+        }
+        DerivedDeclarator::KRFunction(ref mut k) => {
+            for identifier in k {
+                visitor.visit_identifier_mut(&mut identifier.node, &mut identifier.span);
+            }
+        }
+        DerivedDeclarator::Block(ref mut qs) => {
+            for q in qs {
+                visitor.visit_pointer_qualifier_mut(&mut q.node, &mut q.span);
+            }
+        }
+    }
+}
+
+pub fn visit_array_declarator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    array_declarator: &mut ArrayDeclarator,
+    span: &mut Span,
+) {
+    for qualifier in &mut array_declarator.qualifiers {
+        visitor.visit_type_qualifier_mut(&mut qualifier.node, &mut qualifier.span);
+    }
+    visitor.visit_array_size_mut(&mut array_declarator.size, span);
+    for extension in &mut array_declarator.extensions {
+        visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+    }
+}
+
+pub fn visit_function_declarator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    function_declarator: &mut FunctionDeclarator,
+    span: &mut Span,
+) {
+    for parameter in &mut function_declarator.parameters {
+        visitor.visit_parameter_declaration_mut(&mut parameter.node, &mut parameter.span);
+    }
+    visitor.visit_ellipsis_mut(&mut function_declarator.ellipsis, span);
+    for extension in &mut function_declarator.extensions {
+        visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+    }
+}
+
+pub fn visit_pointer_qualifier_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    pointer_qualifier: &mut PointerQualifier,
+    _span: &mut Span,
+) {
+    match *pointer_qualifier {
+        PointerQualifier::TypeQualifier(ref mut t) => {
+            visitor.visit_type_qualifier_mut(&mut t.node, &mut t.span)
+        }
+        PointerQualifier::Extension(ref mut e) => {
+            for extension in e {
+                visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+            }
+        }
+    }
+}
+
+pub fn visit_array_size_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    array_size: &mut ArraySize,
+    _span: &mut Span,
+) {
+    match *array_size {
+        ArraySize::VariableExpression(ref mut e) => {
+            visitor.visit_expression_mut(&mut e.node, &mut e.span)
+        }
+        ArraySize::StaticExpression(ref mut s) => {
+            visitor.visit_expression_mut(&mut s.node, &mut s.span)
+        }
+        _ => {}
+    }
+}
+
+pub fn visit_parameter_declaration_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    parameter_declaration: &mut ParameterDeclaration,
+    _span: &mut Span,
+) {
+    for specifier in &mut parameter_declaration.specifiers {
+        visitor.visit_declaration_specifier_mut(&mut specifier.node, &mut specifier.span);
+    }
+    if let Some(ref mut declarator) = parameter_declaration.declarator {
+        visitor.visit_declarator_mut(&mut declarator.node, &mut declarator.span);
+    }
+    for extension in &mut parameter_declaration.extensions {
+        visitor.visit_extension_mut(&mut extension.node, &mut extension.span);
+    }
+}
+
+pub fn visit_ellipsis_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _ellipsis: &mut Ellipsis,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_type_name_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    type_name: &mut TypeName,
+    _span: &mut Span,
+) {
+    for specifier in &mut type_name.specifiers {
+        visitor.visit_specifier_qualifier_mut(&mut specifier.node, &mut specifier.span);
+    }
+    if let Some(ref mut declarator) = type_name.declarator {
+        visitor.visit_declarator_mut(&mut declarator.node, &mut declarator.span);
+    }
+}
+
+pub fn visit_initializer_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    initializer: &mut Initializer,
+    _span: &mut Span,
+) {
+    match *initializer {
+        Initializer::Expression(ref mut e) => {
+            visitor.visit_expression_mut(&mut e.node, &mut e.span)
+        }
+        Initializer::List(ref mut l) => {
+            for item in l {
+                visitor.visit_initializer_list_item_mut(&mut item.node, &mut item.span);
+            }
+        }
+    }
+}
+
+pub fn visit_initializer_list_item_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    initializer_list_item: &mut InitializerListItem,
+    _span: &mut Span,
+) {
+    for designation in &mut initializer_list_item.designation {
+        visitor.visit_designator_mut(&mut designation.node, &mut designation.span);
+    }
+    visitor.visit_initializer_mut(
+        &mut initializer_list_item.initializer.node,
+        &mut initializer_list_item.initializer.span,
+    );
+}
+
+pub fn visit_designator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    designator: &mut Designator,
+    _span: &mut Span,
+) {
+    match *designator {
+        Designator::Index(ref mut i) => visitor.visit_expression_mut(&mut i.node, &mut i.span),
+        Designator::Member(ref mut m) => visitor.visit_identifier_mut(&mut m.node, &mut m.span),
+        Designator::Range(ref mut r) => {
+            visitor.visit_range_designator_mut(&mut r.node, &mut r.span)
+        }
+    }
+}
+
+pub fn visit_range_designator_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    range_designator: &mut RangeDesignator,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut range_designator.from.node,
+        &mut range_designator.from.span,
+    );
+    visitor.visit_expression_mut(&mut range_designator.to.node, &mut range_designator.to.span);
+}
+
+pub fn visit_static_assert_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    static_assert: &mut StaticAssert,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut static_assert.expression.node,
+        &mut static_assert.expression.span,
+    );
+    if let Some(ref mut message) = static_assert.message {
+        visitor.visit_string_literal_mut(&mut message.node, &mut message.span);
+    }
+}
+
+pub fn visit_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    statement: &mut Statement,
+    _span: &mut Span,
+) {
+    match *statement {
+        Statement::Labeled(ref mut l) => {
+            visitor.visit_labeled_statement_mut(&mut l.node, &mut l.span)
+        }
+        Statement::Compound(ref mut c) => {
+            for item in c {
+                visitor.visit_block_item_mut(&mut item.node, &mut item.span);
+            }
+        }
+        Statement::Expression(Some(ref mut e)) => {
+            visitor.visit_expression_mut(&mut e.node, &mut e.span);
+        }
+        Statement::If(ref mut i) => visitor.visit_if_statement_mut(&mut i.node, &mut i.span),
+        Statement::Switch(ref mut s) => {
+            visitor.visit_switch_statement_mut(&mut s.node, &mut s.span)
+        }
+        Statement::While(ref mut w) => visitor.visit_while_statement_mut(&mut w.node, &mut w.span),
+        Statement::DoWhile(ref mut d) => {
+            visitor.visit_do_while_statement_mut(&mut d.node, &mut d.span)
+        }
+        Statement::For(ref mut f) => visitor.visit_for_statement_mut(&mut f.node, &mut f.span),
+        Statement::Goto(ref mut g) => visitor.visit_identifier_mut(&mut g.node, &mut g.span),
+        Statement::GotoComputed(ref mut e) => {
+            visitor.visit_expression_mut(&mut e.node, &mut e.span)
+        }
+        Statement::Return(Some(ref mut r)) => {
+            visitor.visit_expression_mut(&mut r.node, &mut r.span);
+        }
+        Statement::Attributed(ref mut a) => {
+            visitor.visit_attributed_statement_mut(&mut a.node, &mut a.span)
+        }
+        Statement::Asm(ref mut a) => visitor.visit_asm_statement_mut(&mut a.node, &mut a.span),
+        Statement::Try(ref mut t) => visitor.visit_try_statement_mut(&mut t.node, &mut t.span),
+        _ => {}
+    }
+}
+
+pub fn visit_labeled_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    labeled_statement: &mut LabeledStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_label_mut(
+        &mut labeled_statement.label.node,
+        &mut labeled_statement.label.span,
+    );
+    visitor.visit_statement_mut(
+        &mut labeled_statement.statement.node,
+        &mut labeled_statement.statement.span,
+    );
+}
+
+pub fn visit_attributed_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    attributed_statement: &mut AttributedStatement,
+    _span: &mut Span,
+) {
+    for attribute in &mut attributed_statement.attributes {
+        visitor.visit_extension_mut(&mut attribute.node, &mut attribute.span);
+    }
+    visitor.visit_statement_mut(
+        &mut attributed_statement.statement.node,
+        &mut attributed_statement.statement.span,
+    );
+}
+
+pub fn visit_try_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    try_statement: &mut TryStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_statement_mut(
+        &mut try_statement.statement.node,
+        &mut try_statement.statement.span,
+    );
+    visitor.visit_try_handler_mut(
+        &mut try_statement.handler.node,
+        &mut try_statement.handler.span,
+    );
+}
+
+pub fn visit_try_handler_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    try_handler: &mut TryHandler,
+    _span: &mut Span,
+) {
+    match *try_handler {
+        TryHandler::Except(ref mut e) => visitor.visit_except_handler_mut(&mut e.node, &mut e.span),
+        TryHandler::Finally(ref mut s) => visitor.visit_statement_mut(&mut s.node, &mut s.span),
+    }
+}
+
+pub fn visit_except_handler_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    except_handler: &mut ExceptHandler,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut except_handler.filter.node,
+        &mut except_handler.filter.span,
+    );
+    visitor.visit_statement_mut(
+        &mut except_handler.statement.node,
+        &mut except_handler.statement.span,
+    );
+}
+
+pub fn visit_if_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    if_statement: &mut IfStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut if_statement.condition.node,
+        &mut if_statement.condition.span,
+    );
+    visitor.visit_statement_mut(
+        &mut if_statement.then_statement.node,
+        &mut if_statement.then_statement.span,
+    );
+    if let Some(ref mut e) = if_statement.else_statement {
+        visitor.visit_statement_mut(&mut e.node, &mut e.span);
+    }
+}
+
+pub fn visit_switch_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    switch_statement: &mut SwitchStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut switch_statement.expression.node,
+        &mut switch_statement.expression.span,
+    );
+    visitor.visit_statement_mut(
+        &mut switch_statement.statement.node,
+        &mut switch_statement.statement.span,
+    );
+}
+
+pub fn visit_while_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    while_statement: &mut WhileStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(
+        &mut while_statement.expression.node,
+        &mut while_statement.expression.span,
+    );
+    visitor.visit_statement_mut(
+        &mut while_statement.statement.node,
+        &mut while_statement.statement.span,
+    );
+}
+
+pub fn visit_do_while_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    do_while_statement: &mut DoWhileStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_statement_mut(
+        &mut do_while_statement.statement.node,
+        &mut do_while_statement.statement.span,
+    );
+    visitor.visit_expression_mut(
+        &mut do_while_statement.expression.node,
+        &mut do_while_statement.expression.span,
+    );
+}
+
+pub fn visit_for_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    for_statement: &mut ForStatement,
+    _span: &mut Span,
+) {
+    visitor.visit_for_initializer_mut(
+        &mut for_statement.initializer.node,
+        &mut for_statement.initializer.span,
+    );
+    if let Some(ref mut c) = for_statement.condition {
+        visitor.visit_expression_mut(&mut c.node, &mut c.span);
+    }
+    if let Some(ref mut s) = for_statement.step {
+        visitor.visit_expression_mut(&mut s.node, &mut s.span);
+    }
+    visitor.visit_statement_mut(
+        &mut for_statement.statement.node,
+        &mut for_statement.statement.span,
+    );
+}
+
+pub fn visit_label_mut<V: VisitMut + ?Sized>(visitor: &mut V, label: &mut Label, _span: &mut Span) {
+    match *label {
+        Label::Identifier(ref mut i) => visitor.visit_identifier_mut(&mut i.node, &mut i.span),
+        Label::Case(ref mut c) => visitor.visit_expression_mut(&mut c.node, &mut c.span),
+        Label::CaseRange(ref mut c) => visitor.visit_case_range_mut(&mut c.node, &mut c.span),
+        Label::Default => {}
+    }
+}
+
+pub fn visit_case_range_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    range: &mut CaseRange,
+    _span: &mut Span,
+) {
+    visitor.visit_expression_mut(&mut range.low.node, &mut range.low.span);
+    visitor.visit_expression_mut(&mut range.high.node, &mut range.high.span);
+}
+
+pub fn visit_for_initializer_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    for_initializer: &mut ForInitializer,
+    _span: &mut Span,
+) {
+    match *for_initializer {
+        ForInitializer::Empty => {}
+        ForInitializer::Expression(ref mut e) => {
+            visitor.visit_expression_mut(&mut e.node, &mut e.span)
+        }
+        ForInitializer::Declaration(ref mut d) => {
+            visitor.visit_declaration_mut(&mut d.node, &mut d.span)
+        }
+        ForInitializer::StaticAssert(ref mut s) => {
+            visitor.visit_static_assert_mut(&mut s.node, &mut s.span)
+        }
+    }
+}
+
+pub fn visit_block_item_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    block_item: &mut BlockItem,
+    _span: &mut Span,
+) {
+    match *block_item {
+        BlockItem::Declaration(ref mut d) => {
+            visitor.visit_declaration_mut(&mut d.node, &mut d.span)
+        }
+        BlockItem::StaticAssert(ref mut s) => {
+            visitor.visit_static_assert_mut(&mut s.node, &mut s.span)
+        }
+        BlockItem::Statement(ref mut s) => visitor.visit_statement_mut(&mut s.node, &mut s.span),
+        BlockItem::LabelDeclaration(ref mut l) => {
+            for label in l {
+                visitor.visit_identifier_mut(&mut label.node, &mut label.span);
+            }
+        }
+        BlockItem::FunctionDefinition(ref mut f) => {
+            visitor.visit_function_definition_mut(&mut f.node, &mut f.span)
+        }
+        BlockItem::Pragma(ref mut p) => visitor.visit_pragma_mut(&mut p.node, &mut p.span),
+        BlockItem::Error => {}
+    }
+}
+
+pub fn visit_translation_unit_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    translation_unit: &mut TranslationUnit,
+) {
+    for element in &mut translation_unit.0 {
+        visitor.visit_external_declaration_mut(&mut element.node, &mut element.span);
+    }
+}
+
+pub fn visit_external_declaration_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    external_declaration: &mut ExternalDeclaration,
+    _span: &mut Span,
+) {
+    match *external_declaration {
+        ExternalDeclaration::Declaration(ref mut d) => {
+            visitor.visit_declaration_mut(&mut d.node, &mut d.span)
+        }
+        ExternalDeclaration::StaticAssert(ref mut s) => {
+            visitor.visit_static_assert_mut(&mut s.node, &mut s.span)
+        }
+        ExternalDeclaration::FunctionDefinition(ref mut f) => {
+            visitor.visit_function_definition_mut(&mut f.node, &mut f.span)
+        }
+        ExternalDeclaration::Asm(ref mut a) => {
+            visitor.visit_asm_statement_mut(&mut a.node, &mut a.span)
+        }
+        ExternalDeclaration::Pragma(ref mut p) => {
+            visitor.visit_pragma_mut(&mut p.node, &mut p.span)
+        }
+        ExternalDeclaration::Error => {}
+    }
+}
+
+pub fn visit_function_definition_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    function_definition: &mut FunctionDefinition,
+    _span: &mut Span,
+) {
+    for specifier in &mut function_definition.specifiers {
+        visitor.visit_declaration_specifier_mut(&mut specifier.node, &mut specifier.span);
+    }
+    visitor.visit_declarator_mut(
+        &mut function_definition.declarator.node,
+        &mut function_definition.declarator.span,
+    );
+    for declaration in &mut function_definition.declarations {
+        visitor.visit_declaration_mut(&mut declaration.node, &mut declaration.span);
+    }
+    visitor.visit_statement_mut(
+        &mut function_definition.statement.node,
+        &mut function_definition.statement.span,
+    );
+}
+
+pub fn visit_pragma_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _pragma: &mut Pragma,
+    _span: &mut Span,
+) {
+}
+
+pub fn visit_extension_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    extension: &mut Extension,
+    span: &mut Span,
+) {
+    match *extension {
+        Extension::Attribute(ref mut a) => visitor.visit_attribute_mut(a, span),
+        Extension::AsmLabel(ref mut a) => {
+            visitor.visit_string_literal_mut(&mut a.node, &mut a.span)
+        }
+        Extension::AvailabilityAttribute(ref mut a) => {
+            visitor.visit_availability_attribute_mut(&mut a.node, &mut a.span)
+        }
+        Extension::StandardAttribute(ref mut a) => {
+            visitor.visit_standard_attribute_mut(&mut a.node, &mut a.span)
+        }
+        Extension::Declspec(ref mut a) => visitor.visit_attribute_mut(a, span),
+        Extension::CallingConvention(ref mut c) => visitor.visit_calling_convention_mut(c, span),
+    }
+}
+
+pub fn visit_attribute_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    attribute: &mut Attribute,
+    _span: &mut Span,
+) {
+    for argument in &mut attribute.arguments {
+        visitor.visit_expression_mut(&mut argument.node, &mut argument.span);
+    }
+}
+
+pub fn visit_asm_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    asm_statement: &mut AsmStatement,
+    span: &mut Span,
+) {
+    match *asm_statement {
+        AsmStatement::GnuBasic(ref mut g) => visitor.visit_gnu_basic_asm_statement_mut(g, span),
+        AsmStatement::GnuExtended(ref mut g) => {
+            visitor.visit_gnu_extended_asm_statement_mut(g, span)
+        }
+    }
+}
+
+pub fn visit_availability_attribute_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    availability: &mut AvailabilityAttribute,
+    _span: &mut Span,
+) {
+    for clause in &mut availability.clauses {
+        visitor.visit_availability_clause_mut(&mut clause.node, &mut clause.span);
+    }
+}
+
+pub fn visit_gnu_basic_asm_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    gnu_basic_asm_statement: &mut GnuBasicAsmStatement,
+    _span: &mut Span,
+) {
+    for qualifier in &mut gnu_basic_asm_statement.qualifiers {
+        visitor.visit_asm_qualifier_mut(&mut qualifier.node, &mut qualifier.span);
+    }
+    visitor.visit_string_literal_mut(
+        &mut gnu_basic_asm_statement.template.node,
+        &mut gnu_basic_asm_statement.template.span,
+    );
+}
+
+pub fn visit_gnu_extended_asm_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    gnu_extended_asm_statement: &mut GnuExtendedAsmStatement,
+    _span: &mut Span,
+) {
+    for qualifier in &mut gnu_extended_asm_statement.qualifiers {
+        visitor.visit_asm_qualifier_mut(&mut qualifier.node, &mut qualifier.span);
+    }
+    visitor.visit_string_literal_mut(
+        &mut gnu_extended_asm_statement.template.node,
+        &mut gnu_extended_asm_statement.template.span,
+    );
+    for output in &mut gnu_extended_asm_statement.outputs {
+        visitor.visit_gnu_asm_operand_mut(&mut output.node, &mut output.span);
+    }
+    for input in &mut gnu_extended_asm_statement.inputs {
+        visitor.visit_gnu_asm_operand_mut(&mut input.node, &mut input.span);
+    }
+    for clobber in &mut gnu_extended_asm_statement.clobbers {
+        visitor.visit_string_literal_mut(&mut clobber.node, &mut clobber.span);
+    }
+    for label in &mut gnu_extended_asm_statement.labels {
+        visitor.visit_identifier_mut(&mut label.node, &mut label.span);
+    }
+}
+
+pub fn visit_gnu_asm_operand_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    gnu_asm_operand: &mut GnuAsmOperand,
+    _span: &mut Span,
+) {
+    if let Some(ref mut name) = gnu_asm_operand.symbolic_name {
+        visitor.visit_identifier_mut(&mut name.node, &mut name.span);
+    }
+    visitor.visit_string_literal_mut(
+        &mut gnu_asm_operand.constraints.node,
+        &mut gnu_asm_operand.constraints.span,
+    );
+    visitor.visit_expression_mut(
+        &mut gnu_asm_operand.variable_name.node,
+        &mut gnu_asm_operand.variable_name.span,
+    );
+}
+
+pub fn visit_type_of_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    type_of: &mut TypeOf,
+    _span: &mut Span,
+) {
+    match *type_of {
+        TypeOf::Expression(ref mut e) => visitor.visit_expression_mut(&mut e.node, &mut e.span),
+        TypeOf::Type(ref mut t) => visitor.visit_type_name_mut(&mut t.node, &mut t.span),
+    }
+}
+
+#[cfg(test)]
+struct Rewrite;
+
+#[cfg(test)]
+impl VisitMut for Rewrite {
+    fn visit_identifier_mut(&mut self, identifier: &mut Identifier, span: &mut Span) {
+        identifier.name = identifier.name.to_uppercase();
+        visit_identifier_mut(self, identifier, span);
+    }
+
+    fn visit_binary_operator_expression_mut(
+        &mut self,
+        binary_operator_expression: &mut BinaryOperatorExpression,
+        span: &mut Span,
+    ) {
+        {
+            let e = &mut *binary_operator_expression;
+            if e.operator.node == BinaryOperator::Plus {
+                ::std::mem::swap(&mut e.lhs, &mut e.rhs);
+            }
+        }
+        visit_binary_operator_expression_mut(self, binary_operator_expression, span);
+    }
+}
+
+#[test]
+fn test_visit_mut() {
+    use driver::{parse_preprocessed, Config};
+    use unparse::Unparser;
+
+    let source = "int f(int a) { return a + f(a - 1) + 2; }";
+    let mut parse = parse_preprocessed(&Config::default(), source.into()).unwrap();
+    Rewrite.visit_translation_unit_mut(&mut parse.unit);
+
+    let s = &mut String::new();
+    Unparser::new(s).translation_unit(&parse.unit);
+    assert_eq!(s, "int F(int A)\n{\n    return 2 + (F(A - 1) + A);\n}\n");
+}